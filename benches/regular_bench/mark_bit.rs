@@ -0,0 +1,55 @@
+//! Benchmarks for the protocol of atomically setting a mark bit, as used by
+//! `ImmixSpace::attempt_mark`.  `mark_bit_seqcst` uses `SeqCst` for both the load and the CAS,
+//! while `mark_bit_relaxed` uses `Relaxed` for both, as `ImmixSpace::attempt_mark` does.  Each
+//! iteration marks every object in a block once (the slow path), and then tries to mark them
+//! again (the fast path, as the objects are already marked).
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use criterion::{black_box, Criterion};
+
+const BLOCK_BYTES: usize = 32768usize; // Match an Immix block size.
+const MIN_OBJECT_BYTES: usize = 8usize;
+
+// One mark bit per object granule.
+const NUM_GRANULES: usize = BLOCK_BYTES / MIN_OBJECT_BYTES;
+const BLOCK_META_BYTES: usize = NUM_GRANULES / 8;
+
+/// Atomically set the mark bit of the `granule`-th object.  Return true if this call set the bit.
+#[inline(always)]
+fn attempt_mark(bitmap: &[AtomicU8], granule: usize, load: Ordering, cas: Ordering) -> bool {
+    let byte = &bitmap[granule >> 3];
+    let mask = 1u8 << (granule & 7);
+    let mut old = byte.load(load);
+    loop {
+        if old & mask != 0 {
+            return false;
+        }
+        match byte.compare_exchange(old, old | mask, cas, cas) {
+            Ok(_) => return true,
+            Err(current) => old = current,
+        }
+    }
+}
+
+fn bench_protocol(c: &mut Criterion, name: &str, load: Ordering, cas: Ordering) {
+    let bitmap: Vec<AtomicU8> = (0..BLOCK_META_BYTES).map(|_| AtomicU8::new(0)).collect();
+    c.bench_function(name, |b| {
+        b.iter(|| {
+            for byte in bitmap.iter() {
+                byte.store(0, Ordering::Relaxed);
+            }
+            for granule in 0..NUM_GRANULES {
+                black_box(attempt_mark(&bitmap, granule, load, cas));
+            }
+            for granule in 0..NUM_GRANULES {
+                black_box(attempt_mark(&bitmap, granule, load, cas));
+            }
+        })
+    });
+}
+
+pub fn bench(c: &mut Criterion) {
+    bench_protocol(c, "mark_bit_seqcst", Ordering::SeqCst, Ordering::SeqCst);
+    bench_protocol(c, "mark_bit_relaxed", Ordering::Relaxed, Ordering::Relaxed);
+}
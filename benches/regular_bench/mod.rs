@@ -1,7 +1,9 @@
 pub use criterion::Criterion;
 
 mod bulk_meta;
+mod mark_bit;
 
 pub fn bench(c: &mut Criterion) {
     bulk_meta::bench(c);
+    mark_bit::bench(c);
 }
@@ -80,7 +80,7 @@ impl<VM: VMBinding> ProcessEdgesWork for MyGCProcessEdges<VM> {
     }
 
     fn create_scan_work(&self, nodes: Vec<ObjectReference>) -> ScanObjects<Self> {
-        ScanObjects::<Self>::new(nodes, false, self.bucket)
+        ScanObjects::<Self>::new(nodes, false, self.bucket, None)
     }
 }
 // ANCHOR_END: mygc_process_edges_impl
@@ -1,8 +1,10 @@
+use crate::util::copy::{CopyAllocStats, CopySemantics};
+use crate::util::ObjectReference;
 use atomic_refcell::AtomicRefCell;
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Mutex;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 /// This stores some global states for an MMTK instance.
 /// Some MMTK components like plans and allocators may keep an reference to the struct, and can access it.
@@ -36,8 +38,12 @@ pub struct GlobalState {
     pub(crate) max_collection_attempts: AtomicUsize,
     // Current collection attempt
     pub(crate) cur_collection_attempts: AtomicUsize,
-    /// A counter for per-mutator stack scanning
+    /// A counter for per-mutator stack scanning.  It counts the finished stack-scanning tasks,
+    /// including one task for each mutator and the tasks forked by the binding.
     pub(crate) scanned_stacks: AtomicUsize,
+    /// The number of stack-scanning tasks forked by the binding during this GC.  The stacks are
+    /// scanned when `scanned_stacks` reaches the number of mutators plus this number.
+    pub(crate) forked_stack_scans: AtomicUsize,
     /// Have we scanned all the stacks?
     pub(crate) stacks_prepared: AtomicBool,
     /// A counter that keeps tracks of the number of bytes allocated since last stress test
@@ -47,6 +53,37 @@ pub struct GlobalState {
     pub(crate) malloc_bytes: AtomicUsize,
     /// This stores the live bytes and the used bytes (by pages) for each space in last GC. This counter is only updated in the GC release phase.
     pub(crate) live_bytes_in_last_gc: AtomicRefCell<HashMap<&'static str, LiveBytesStats>>,
+    /// The number of objects scanned in each space in last GC. This is updated in the GC release phase.
+    pub(crate) scanned_objects_in_last_gc: AtomicRefCell<HashMap<&'static str, usize>>,
+    /// This stores the block acquisition statistics of the copy allocators of all workers in last GC.
+    /// This is updated at the end of a GC.
+    pub(crate) copy_alloc_stats_in_last_gc: AtomicRefCell<CopyAllocStats>,
+    /// Was the last GC a defrag GC?  This is reset at the end of every GC, and set by the Immix space
+    /// if it did defrag in the GC.
+    pub(crate) last_gc_was_defrag: AtomicBool,
+    /// The bytes copied into Immix spaces for each copy semantics in the last GC if the option
+    /// `record_copy_breakdown` is enabled.  This is updated at the end of a GC.
+    pub(crate) copy_breakdown_in_last_gc: AtomicRefCell<Vec<(CopySemantics, usize)>>,
+    /// The `(parent, child)` edges recorded in the last GC if the option `record_heap_edges` is enabled.
+    /// This is updated at the end of a GC.
+    pub(crate) heap_edges_in_last_gc: AtomicRefCell<Vec<(ObjectReference, ObjectReference)>>,
+    /// The number of slots updated to point to moved objects in the last GC.
+    /// This is updated at the end of a GC.
+    #[cfg(feature = "count_updated_slots")]
+    pub(crate) updated_slots_in_last_gc: AtomicUsize,
+    /// The objects forwarded in the last GC.  This is updated at the end of a GC.
+    #[cfg(feature = "forwarding_log")]
+    pub(crate) forwarding_log_in_last_gc: AtomicRefCell<Vec<crate::util::copy::ForwardingLogEntry>>,
+    /// When did the `StopMutators` work packet start stopping the mutators? Only accessed by the
+    /// `StopMutators` work packet and `notify_mutators_paused`.
+    pub(crate) stop_mutators_start_time: AtomicRefCell<Option<Instant>>,
+    /// The time between `StopMutators` starting to stop the mutators and all the mutators being
+    /// paused in the last GC, i.e. the time the VM took to bring all the mutators to safepoints.
+    pub(crate) time_to_safepoint_in_last_gc: Mutex<Duration>,
+    /// The total idle time of all GC workers in the last GC, and the parallel efficiency, i.e. the
+    /// fraction of the worker time spent executing work packets.  This is updated at the end of a
+    /// GC.
+    pub(crate) worker_idle_time_in_last_gc: Mutex<(Duration, f64)>,
 }
 
 impl GlobalState {
@@ -134,22 +171,35 @@ impl GlobalState {
     /// This should be called before doing stack scanning.
     pub fn prepare_for_stack_scanning(&self) {
         self.scanned_stacks.store(0, Ordering::SeqCst);
+        self.forked_stack_scans.store(0, Ordering::SeqCst);
         self.stacks_prepared.store(false, Ordering::SeqCst);
     }
 
-    /// Inform that 1 stack has been scanned. The argument `n_mutators` indicates the
-    /// total stacks we should scan. This method returns true if the number of scanned
-    /// stacks equals the total mutator count. Otherwise it returns false. This method
-    /// is thread safe and we guarantee only one thread will return true.
+    /// Inform that the scanning of a stack will be finished by one more task.  This must be called
+    /// by a stack-scanning task that has not been finished, so that the forked task is counted
+    /// before the scanning can be considered done.  The forked task must call
+    /// `inform_stack_scanned()` when it finishes.
+    pub fn inform_stack_scan_forked(&self) {
+        debug_assert!(!self.stacks_prepared());
+        self.forked_stack_scans.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Inform that 1 stack-scanning task has finished. The argument `n_mutators` indicates the
+    /// total stacks we should scan. This method returns true if the number of finished tasks
+    /// equals the total mutator count plus the number of forked tasks. Otherwise it returns false.
+    /// This method is thread safe and we guarantee only one thread will return true.
     pub fn inform_stack_scanned(&self, n_mutators: usize) -> bool {
         let old = self.scanned_stacks.fetch_add(1, Ordering::SeqCst);
+        // Forked tasks are counted before their parent task finishes.  So if all the tasks we know
+        // have finished, no more tasks can be forked.
+        let n_tasks = n_mutators + self.forked_stack_scans.load(Ordering::SeqCst);
         debug_assert!(
-            old < n_mutators,
-            "The number of scanned stacks ({}) is more than the number of mutators ({})",
+            old < n_tasks,
+            "The number of scanned stacks ({}) is more than the number of stack-scanning tasks ({})",
             old,
-            n_mutators
+            n_tasks
         );
-        let scanning_done = old + 1 == n_mutators;
+        let scanning_done = old + 1 == n_tasks;
         if scanning_done {
             self.stacks_prepared.store(true, Ordering::SeqCst);
         }
@@ -199,18 +249,35 @@ impl Default for GlobalState {
             max_collection_attempts: AtomicUsize::new(0),
             cur_collection_attempts: AtomicUsize::new(0),
             scanned_stacks: AtomicUsize::new(0),
+            forked_stack_scans: AtomicUsize::new(0),
             allocation_bytes: AtomicUsize::new(0),
             #[cfg(feature = "malloc_counted_size")]
             malloc_bytes: AtomicUsize::new(0),
             live_bytes_in_last_gc: AtomicRefCell::new(HashMap::new()),
+            scanned_objects_in_last_gc: AtomicRefCell::new(HashMap::new()),
+            copy_alloc_stats_in_last_gc: AtomicRefCell::new(CopyAllocStats::default()),
+            last_gc_was_defrag: AtomicBool::new(false),
+            copy_breakdown_in_last_gc: AtomicRefCell::new(vec![]),
+            heap_edges_in_last_gc: AtomicRefCell::new(vec![]),
+            #[cfg(feature = "count_updated_slots")]
+            updated_slots_in_last_gc: AtomicUsize::new(0),
+            #[cfg(feature = "forwarding_log")]
+            forwarding_log_in_last_gc: AtomicRefCell::new(vec![]),
+            stop_mutators_start_time: AtomicRefCell::new(None),
+            time_to_safepoint_in_last_gc: Mutex::new(Duration::ZERO),
+            worker_idle_time_in_last_gc: Mutex::new((Duration::ZERO, 1.0)),
         }
     }
 }
 
-#[derive(PartialEq)]
+/// The status of GC.  See [`crate::vm::Collection::on_gc_status_change`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum GcStatus {
+    /// No GC is in progress.
     NotInGC,
+    /// A GC has started, and the mutators are being stopped and their roots are being scanned.
     GcPrepare,
+    /// All the mutator stacks have been scanned, and the GC is tracing and releasing memory.
     GcProper,
 }
 
@@ -46,7 +46,12 @@ pub(crate) use mmtk::MMAPPER;
 pub use mmtk::MMTK;
 
 mod global_state;
+pub use crate::global_state::GcStatus;
 pub use crate::global_state::LiveBytesStats;
+pub use crate::policy::immix::block::BlockState;
+pub use crate::policy::immix::block::ImmixBlock;
+pub use crate::policy::immix::block::ReusableBlockStats;
+pub use crate::policy::immix::immixspace::EvacuateBlockError;
 
 mod policy;
 
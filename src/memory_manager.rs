@@ -15,7 +15,7 @@ use crate::mmtk::MMTKBuilder;
 use crate::mmtk::MMTK;
 use crate::plan::AllocationSemantics;
 use crate::plan::{Mutator, MutatorContext};
-use crate::scheduler::WorkBucketStage;
+use crate::scheduler::{CustomBucketId, WorkBucketStage};
 use crate::scheduler::{GCWork, GCWorker};
 use crate::util::alloc::allocators::AllocatorSelector;
 use crate::util::constants::{LOG_BYTES_IN_PAGE, MIN_OBJECT_SIZE};
@@ -547,6 +547,390 @@ pub fn live_bytes_in_last_gc<VM: VMBinding>(
     mmtk.state.live_bytes_in_last_gc.borrow().clone()
 }
 
+/// Return a hash map of the number of objects scanned in each space in the last GC.
+///
+/// Unlike [`live_bytes_in_last_gc`], this is always counted, as counting objects does not need to
+/// query object sizes.  It gives a rough signal of the liveness of each space.  The value returned
+/// by this method is only updated when we finish tracing in a GC.
+pub fn scanned_objects_in_last_gc<VM: VMBinding>(mmtk: &MMTK<VM>) -> HashMap<&'static str, usize> {
+    mmtk.state.scanned_objects_in_last_gc.borrow().clone()
+}
+
+/// Return the block acquisition statistics of the copy allocators of all GC workers in the last GC,
+/// including the number of blocks acquired and the number of failed attempts to acquire a clean
+/// block.  This can help diagnose the sizing of the copy reserve.
+///
+/// The value returned by this method is only updated at the end of a GC.
+pub fn copy_alloc_stats_in_last_gc<VM: VMBinding>(
+    mmtk: &MMTK<VM>,
+) -> crate::util::copy::CopyAllocStats {
+    *mmtk.state.copy_alloc_stats_in_last_gc.borrow()
+}
+
+/// Return the time the VM took to stop all the mutators in the last GC, measured from the start of
+/// the `StopMutators` work packet to the moment all the mutators are paused.  This is the time to
+/// safepoint, and it separates the safepoint latency of the VM from the time spent doing GC work
+/// in a pause.  It is also accumulated in the `time_to_safepoint.ns` statistics counter.
+///
+/// The value returned by this method is updated once the mutators are paused in each GC.
+pub fn time_to_safepoint_in_last_gc<VM: VMBinding>(mmtk: &MMTK<VM>) -> std::time::Duration {
+    *mmtk.state.time_to_safepoint_in_last_gc.lock().unwrap()
+}
+
+/// Return the total time the GC workers were idle in the last GC, i.e. the GC time of all the
+/// workers minus the time they spent executing work packets.  Workers are idle when they wait for
+/// other workers to produce more work, so a long idle time indicates poor load balancing.  It is
+/// also accumulated in the `worker_idle.ns` statistics counter.
+///
+/// This is only measured if the option `record_worker_idle_time` is enabled.  Otherwise, it returns
+/// zero.  The value returned by this method is only updated when a GC finishes.
+pub fn worker_idle_time_in_last_gc<VM: VMBinding>(mmtk: &MMTK<VM>) -> std::time::Duration {
+    mmtk.state.worker_idle_time_in_last_gc.lock().unwrap().0
+}
+
+/// Return the parallel efficiency of the last GC, i.e. the fraction of the GC time of all the GC
+/// workers that was spent executing work packets, between 0.0 and 1.0.  Bindings can use this to
+/// tune the number of GC workers.  See [`worker_idle_time_in_last_gc`].
+///
+/// This is only measured if the option `record_worker_idle_time` is enabled.  Otherwise, it returns
+/// 1.0.  The value returned by this method is only updated when a GC finishes.  It is 1.0 before
+/// the first GC.
+pub fn parallel_efficiency_in_last_gc<VM: VMBinding>(mmtk: &MMTK<VM>) -> f64 {
+    mmtk.state.worker_idle_time_in_last_gc.lock().unwrap().1
+}
+
+/// Return the number of slots that `ProcessEdgesWork` packets updated to point to moved objects in
+/// the last GC.  An object referenced from multiple slots is counted once for each slot.  This can be
+/// used to verify that a non-moving trace moved nothing, or that a defrag GC moved the expected
+/// amount of objects.  Objects traced by the VM with `Scanning::scan_object_and_trace_edges` are not
+/// counted, as the VM updates their fields.
+///
+/// The value returned by this method is only updated at the end of a GC.
+#[cfg(feature = "count_updated_slots")]
+pub fn updated_slots_in_last_gc<VM: VMBinding>(mmtk: &MMTK<VM>) -> usize {
+    mmtk.state
+        .updated_slots_in_last_gc
+        .load(std::sync::atomic::Ordering::Relaxed)
+}
+
+/// Take the `(parent, child)` edges recorded in the last GC.  Edges are only recorded if the option
+/// `record_heap_edges` is enabled, and only for objects scanned with `Scanning::scan_object` (objects
+/// scanned with `Scanning::scan_object_and_trace_edges` are not recorded).  The child of an edge is
+/// the reference held in the slot before the child was traced, so with a plan that moves objects,
+/// a child may be reported by its old address.  Use a non-moving plan for a consistent heap graph.
+///
+/// The edges are updated at the end of each GC, and this method leaves an empty list behind.
+pub fn take_heap_edges_in_last_gc<VM: VMBinding>(
+    mmtk: &MMTK<VM>,
+) -> Vec<(ObjectReference, ObjectReference)> {
+    std::mem::take(&mut *mmtk.state.heap_edges_in_last_gc.borrow_mut())
+}
+
+/// Call `f` with the Immix space of the current plan, and return its result, or `None` if the plan
+/// does not have an Immix space.  If the plan has more than one Immix space, only the first one is
+/// used.
+fn with_immix_space<VM: VMBinding, R>(
+    mmtk: &MMTK<VM>,
+    f: impl FnOnce(&crate::policy::immix::ImmixSpace<VM>) -> R,
+) -> Option<R> {
+    use crate::policy::immix::ImmixSpace;
+    let mut f = Some(f);
+    let mut result = None;
+    mmtk.get_plan().for_each_space(&mut |space| {
+        if let Some(immix_space) = space.downcast_ref::<ImmixSpace<VM>>() {
+            if let Some(f) = f.take() {
+                result = Some(f(immix_space));
+            }
+        }
+    });
+    result
+}
+
+/// Return statistics of the reusable blocks in the Immix space of the current plan, or `None` if
+/// the plan does not have an Immix space.
+///
+/// Reusable blocks are blocks that have some free lines after a GC.  The statistics include the
+/// number of reusable blocks, the number of available lines in them, and the distribution of the
+/// number of holes per block.  It can be used to diagnose fragmentation without enabling defrag
+/// statistics.  It iterates through the reusable block list, and should not be called while a GC is
+/// in progress.
+///
+/// Arguments:
+/// * `mmtk`: A reference to an MMTk instance.
+pub fn immix_reusable_block_stats<VM: VMBinding>(
+    mmtk: &MMTK<VM>,
+) -> Option<crate::ReusableBlockStats> {
+    with_immix_space(mmtk, |immix_space| immix_space.reusable_block_stats())
+}
+
+/// Return a snapshot of the chunks of the Immix space of the current plan as a list of
+/// `(start, allocated, blocks)`, or `None` if the plan does not have an Immix space.  See
+/// `ImmixSpace::chunk_occupancy` for the meaning of the values.  This can be used to render a heap
+/// occupancy map.  It should not be called while a GC is in progress.
+///
+/// Arguments:
+/// * `mmtk`: A reference to an MMTk instance.
+pub fn immix_chunk_occupancy<VM: VMBinding>(
+    mmtk: &MMTK<VM>,
+) -> Option<Vec<(Address, bool, usize)>> {
+    with_immix_space(mmtk, |immix_space| immix_space.chunk_occupancy())
+}
+
+/// Return the Immix block that contains `object`, or `None` if the object is not in an Immix space.
+/// This lets external tools such as heap visualizers map objects to blocks and query the state and
+/// the number of holes of the blocks.  The block state is only stable when no GC is in progress.
+///
+/// Arguments:
+/// * `mmtk`: A reference to an MMTk instance.
+/// * `object`: The object to look up.
+pub fn immix_block_of<VM: VMBinding>(
+    mmtk: &MMTK<VM>,
+    object: ObjectReference,
+) -> Option<crate::ImmixBlock> {
+    use crate::policy::space::Space;
+    with_immix_space(mmtk, |immix_space| {
+        immix_space
+            .in_space(object)
+            .then(|| immix_space.block_of(object).into())
+    })
+    .flatten()
+}
+
+/// Request the Immix block that contains the given address to be evacuated in the next defrag GC,
+/// and request the next GC to be a defrag GC.  The live objects in the block will be moved when they
+/// are traced, unless they are pinned or there is no space to copy them to.  For generational
+/// plans, the block is evacuated at the next full-heap GC.
+///
+/// This must be called when no GC is in progress, e.g. by a mutator.
+///
+/// Return an error if the address is not in an allocated block of an Immix space, if the block is
+/// pinned, if the Immix space never moves objects, or if a GC is in progress.
+///
+/// Arguments:
+/// * `mmtk`: A reference to an MMTk instance.
+/// * `addr`: An address in the block to evacuate.
+pub fn evacuate_immix_block<VM: VMBinding>(
+    mmtk: &MMTK<VM>,
+    addr: Address,
+) -> Result<(), crate::EvacuateBlockError> {
+    use crate::policy::immix::block::Block;
+    use crate::policy::space::Space;
+    use crate::util::linear_scan::Region;
+    with_immix_space(mmtk, |immix_space| {
+        if immix_space.address_in_space(addr) {
+            immix_space.evacuate_block(Block::from_unaligned_address(addr))
+        } else {
+            Err(crate::EvacuateBlockError::NotAllocated)
+        }
+    })
+    .unwrap_or(Err(crate::EvacuateBlockError::NotAllocated))
+}
+
+/// Call `f` for each live object in the Immix space of the current plan, i.e. the objects that
+/// have been marked in the last GC and have not died since.  This does nothing if the plan does not
+/// have an Immix space.  It can be used for producing heap dumps.
+///
+/// This must only be called when the world is stopped and the GC has finished marking, e.g. at the
+/// end of a GC before mutators are resumed.  It uses VO bits to locate objects, so it requires the
+/// `vo_bit` feature.
+///
+/// Arguments:
+/// * `mmtk`: A reference to an MMTk instance.
+/// * `f`: The callback to call for each live object.
+#[cfg(feature = "vo_bit")]
+pub fn for_each_live_immix_object<VM: VMBinding>(
+    mmtk: &MMTK<VM>,
+    mut f: impl FnMut(ObjectReference),
+) {
+    with_immix_space(mmtk, |immix_space| immix_space.for_each_live_object(&mut f));
+}
+
+/// Return the live objects in the Immix space of the current plan that are larger than `bytes`.
+/// This helps finding objects that should arguably live in a large object space.  This returns an
+/// empty vector if the plan does not have an Immix space.  See `ImmixSpace::objects_larger_than`.
+///
+/// The same restrictions as [`for_each_live_immix_object`] apply: this must only be called when the
+/// world is stopped and the GC has finished marking, and it requires the `vo_bit` feature.
+///
+/// Arguments:
+/// * `mmtk`: A reference to an MMTk instance.
+/// * `bytes`: The size threshold in bytes.  Only objects strictly larger than it are returned.
+#[cfg(feature = "vo_bit")]
+pub fn immix_objects_larger_than<VM: VMBinding>(
+    mmtk: &MMTK<VM>,
+    bytes: usize,
+) -> Vec<ObjectReference> {
+    with_immix_space(mmtk, |immix_space| immix_space.objects_larger_than(bytes)).unwrap_or_default()
+}
+
+/// Return the number of clean blocks acquired by mutator allocators and by copy allocators,
+/// respectively, in the Immix space of the current plan since the space was last prepared for a
+/// GC.  This returns `(0, 0)` if the plan does not have an Immix space.  See
+/// `ImmixSpace::clean_block_counts`.
+///
+/// Arguments:
+/// * `mmtk`: A reference to an MMTk instance.
+pub fn immix_clean_block_counts<VM: VMBinding>(mmtk: &MMTK<VM>) -> (usize, usize) {
+    with_immix_space(mmtk, |immix_space| immix_space.clean_block_counts()).unwrap_or((0, 0))
+}
+
+/// Return the number of clean pages that may still be acquired for copying in the current GC
+/// before the defrag space of the Immix space of the current plan is exhausted.  This can be called
+/// during a GC to monitor how close defrag is to exhaustion.  This returns `None` if the plan does
+/// not have an Immix space.  See `ImmixSpace::remaining_defrag_headroom_pages`.
+///
+/// Arguments:
+/// * `mmtk`: A reference to an MMTk instance.
+pub fn immix_remaining_defrag_headroom_pages<VM: VMBinding>(mmtk: &MMTK<VM>) -> Option<usize> {
+    with_immix_space(mmtk, |immix_space| {
+        immix_space.remaining_defrag_headroom_pages()
+    })
+}
+
+/// Return the live objects in the Immix space of the current plan that are still pinned.  This
+/// helps debugging pinning leaks where the VM forgets to unpin objects.  This returns an empty
+/// vector if the plan does not have an Immix space.  See `ImmixSpace::pinned_objects`.
+///
+/// The same restrictions as [`for_each_live_immix_object`] apply: this must only be called when the
+/// world is stopped and the GC has finished marking.  It requires the `vo_bit` and the
+/// `object_pinning` features.
+///
+/// Arguments:
+/// * `mmtk`: A reference to an MMTk instance.
+#[cfg(all(feature = "vo_bit", feature = "object_pinning"))]
+pub fn immix_pinned_objects<VM: VMBinding>(mmtk: &MMTK<VM>) -> Vec<ObjectReference> {
+    with_immix_space(mmtk, |immix_space| immix_space.pinned_objects()).unwrap_or_default()
+}
+
+/// Return the current mark states of the Immix space of the current plan as a tuple of
+/// `(mark_state, line_mark_state, line_unavail_state)`, or `None` if the plan does not have an Immix
+/// space.  See `ImmixSpace::mark_state_snapshot` for the meaning of the values.  This is intended for
+/// experimenting with concurrent or incremental marking, where a write barrier needs to know which
+/// mark values are current.
+///
+/// Arguments:
+/// * `mmtk`: A reference to an MMTk instance.
+pub fn immix_mark_state_snapshot<VM: VMBinding>(mmtk: &MMTK<VM>) -> Option<(u8, u8, u8)> {
+    with_immix_space(mmtk, |immix_space| immix_space.mark_state_snapshot())
+}
+
+/// Return the defrag threshold used in the current or the last GC by the Immix space of the
+/// current plan, or `None` if that GC is not a defrag GC or the plan does not have an Immix space.
+/// See `ImmixSpace::current_defrag_threshold`.
+///
+/// Arguments:
+/// * `mmtk`: A reference to an MMTk instance.
+pub fn immix_current_defrag_threshold<VM: VMBinding>(mmtk: &MMTK<VM>) -> Option<usize> {
+    with_immix_space(mmtk, |immix_space| immix_space.current_defrag_threshold()).flatten()
+}
+
+/// Return the number of lines consumed by allocators in the Immix space of the current plan since
+/// the last GC, or `None` if the plan does not have an Immix space.  Sampled together with
+/// timestamps, this gives the allocation rate, which can be used to drive a custom GC trigger.  See
+/// `ImmixSpace::lines_consumed_since_gc`.
+///
+/// Arguments:
+/// * `mmtk`: A reference to an MMTk instance.
+pub fn immix_lines_consumed_since_gc<VM: VMBinding>(mmtk: &MMTK<VM>) -> Option<usize> {
+    with_immix_space(mmtk, |immix_space| immix_space.lines_consumed_since_gc())
+}
+
+/// Return the number of times the line mark state of the Immix space of the current plan wrapped
+/// around since MMTk started, or `None` if the plan does not have an Immix space.  The current line
+/// mark state can be obtained with [`immix_mark_state_snapshot`].
+///
+/// Arguments:
+/// * `mmtk`: A reference to an MMTk instance.
+pub fn immix_line_mark_state_wraps<VM: VMBinding>(mmtk: &MMTK<VM>) -> Option<usize> {
+    with_immix_space(mmtk, |immix_space| immix_space.line_mark_state_wraps())
+}
+
+/// Reserve `pages` pages of the defrag headroom of the Immix space of the current plan, so that
+/// the next defrag GC does not use them to evacuate objects.  A binding that is about to allocate a
+/// very large object, possibly after triggering a GC, can use this to avoid running out of memory
+/// right after the GC.  Reservations accumulate until the end of the next defrag GC, or until
+/// [`release_immix_defrag_headroom`] is called.  This has no effect if the plan does not have an
+/// Immix space.
+///
+/// Arguments:
+/// * `mmtk`: A reference to an MMTk instance.
+/// * `pages`: The number of pages to reserve.
+pub fn reserve_immix_defrag_headroom<VM: VMBinding>(mmtk: &MMTK<VM>, pages: usize) {
+    with_immix_space(mmtk, |immix_space| immix_space.reserve_headroom(pages));
+}
+
+/// Release the defrag headroom reserved with [`reserve_immix_defrag_headroom`].
+///
+/// Arguments:
+/// * `mmtk`: A reference to an MMTk instance.
+pub fn release_immix_defrag_headroom<VM: VMBinding>(mmtk: &MMTK<VM>) {
+    with_immix_space(mmtk, |immix_space| immix_space.release_headroom());
+}
+
+/// Check if an object in the Immix space of the current plan has been reached in the current GC,
+/// without marking or enqueuing it.  Return the new location of the object if it has been
+/// forwarded, the object itself if it has been marked in place, or `None` otherwise (including
+/// the case that the object is not in an Immix space).  This is useful for bindings that clean up
+/// weak tables during weak reference processing.  See `ImmixSpace::is_live_or_forwarded`.
+///
+/// Arguments:
+/// * `mmtk`: A reference to an MMTk instance.
+/// * `object`: The object to check.
+pub fn immix_is_live_or_forwarded<VM: VMBinding>(
+    mmtk: &MMTK<VM>,
+    object: ObjectReference,
+) -> Option<ObjectReference> {
+    use crate::policy::space::Space;
+    with_immix_space(mmtk, |immix_space| {
+        immix_space
+            .in_space(object)
+            .then(|| immix_space.is_live_or_forwarded(object))
+            .flatten()
+    })
+    .flatten()
+}
+
+/// Return the fragmentation ratio of the Immix space of the current plan, or `None` if the plan does
+/// not have an Immix space.  The ratio is the fraction of free lines in reusable blocks over all the
+/// lines in reusable and full blocks, as of the last GC.  It can be used to decide when to request a
+/// defrag GC.  See `ImmixSpace::fragmentation_ratio` for details.
+///
+/// Arguments:
+/// * `mmtk`: A reference to an MMTk instance.
+pub fn immix_fragmentation_ratio<VM: VMBinding>(mmtk: &MMTK<VM>) -> Option<f64> {
+    with_immix_space(mmtk, |immix_space| immix_space.fragmentation_ratio())
+}
+
+/// Return true if the address is in the Immix space of the current plan.  Unlike
+/// [`is_in_mmtk_spaces`], this takes an arbitrary address instead of an object reference, and it does
+/// not consult the SFT map, which makes it suitable for filtering potential pointers during
+/// conservative stack scanning.  It returns false if the plan does not have an Immix space.
+///
+/// Arguments:
+/// * `mmtk`: A reference to an MMTk instance.
+/// * `addr`: The address to test.
+pub fn is_in_immix_space<VM: VMBinding>(mmtk: &MMTK<VM>, addr: Address) -> bool {
+    with_immix_space(mmtk, |immix_space| immix_space.in_space_fast(addr)).unwrap_or(false)
+}
+
+/// Fault in up to `pages` pages of the Immix space of the current plan ahead of time, so that the
+/// first allocations do not pay for page faults. This is intended for latency-sensitive services
+/// that want to pay the cost at startup. Pretouching stops early instead of triggering a GC if the
+/// heap budget would be exceeded. Returns the number of pages pretouched, which is 0 if the plan does
+/// not have an Immix space. See `ImmixSpace::pretouch` for details.
+///
+/// Arguments:
+/// * `mmtk`: A reference to an MMTk instance.
+/// * `tls`: The thread that requests the pretouching.
+/// * `pages`: The number of pages to pretouch.
+pub fn pretouch_immix_space<VM: VMBinding>(
+    mmtk: &MMTK<VM>,
+    tls: VMMutatorThread,
+    pages: usize,
+) -> usize {
+    with_immix_space(mmtk, |immix_space| immix_space.pretouch(tls.0, pages)).unwrap_or(0)
+}
+
 /// Return the starting address of the heap. *Note that currently MMTk uses
 /// a fixed address range as heap.*
 pub fn starting_heap_address() -> Address {
@@ -800,6 +1184,36 @@ pub fn unpin_object(object: ObjectReference) -> bool {
         .unpin_object(object)
 }
 
+/// Pin the block that contains an object. MMTk will make sure that no objects in the block move
+/// during GC until the block is unpinned.  This is coarser than [`pin_object`], but is much cheaper
+/// than pinning many objects in the same block individually.  Currently only the Immix space
+/// supports pinning blocks.  It returns true if the pinning operation has been performed, i.e., the
+/// block status changed from non-pinned to pinned.
+///
+/// Arguments:
+/// * `object`: An object in the block to be pinned
+#[cfg(feature = "object_pinning")]
+pub fn pin_block_of_object(object: ObjectReference) -> bool {
+    use crate::mmtk::SFT_MAP;
+    SFT_MAP
+        .get_checked(object.to_raw_address())
+        .pin_block(object)
+}
+
+/// Unpin the block that contains an object, which was pinned by [`pin_block_of_object`].
+/// Returns true if the unpinning operation has been performed, i.e., the block status changed
+/// from pinned to non-pinned.
+///
+/// Arguments:
+/// * `object`: An object in the block to be unpinned
+#[cfg(feature = "object_pinning")]
+pub fn unpin_block_of_object(object: ObjectReference) -> bool {
+    use crate::mmtk::SFT_MAP;
+    SFT_MAP
+        .get_checked(object.to_raw_address())
+        .unpin_block(object)
+}
+
 /// Check whether an object is currently pinned
 ///
 /// Arguments:
@@ -883,6 +1297,57 @@ pub fn num_of_workers<VM: VMBinding>(mmtk: &'static MMTK<VM>) -> usize {
     mmtk.scheduler.num_workers()
 }
 
+/// Split the root scanning of a mutator into more tasks.  The binding may call this in
+/// [`crate::vm::Scanning::scan_roots_in_mutator_thread`] for each task (usually a work packet added
+/// by [`add_work_packet`]) that will continue scanning part of the mutator's roots after
+/// `scan_roots_in_mutator_thread` returns.  Each forked task may use a clone of the
+/// [`crate::vm::RootsWorkFactory`] to create root-processing work packets, and must call
+/// [`finish_forked_stack_scan`] when it finishes.  MMTk will only call
+/// [`crate::vm::Scanning::notify_initial_thread_scan_complete`] after all the mutators and all the
+/// forked tasks have been scanned.
+///
+/// This must be called before the task that forks the new task (either
+/// `scan_roots_in_mutator_thread` or another forked task) finishes.
+///
+/// Arguments:
+/// * `mmtk`: A reference to an MMTk instance.
+pub fn fork_stack_scan<VM: VMBinding>(mmtk: &'static MMTK<VM>) {
+    mmtk.state.inform_stack_scan_forked();
+}
+
+/// Inform MMTk that a stack-scanning task forked by [`fork_stack_scan`] has finished.
+///
+/// Arguments:
+/// * `tls`: The thread that executes the task.
+/// * `mmtk`: A reference to an MMTk instance.
+pub fn finish_forked_stack_scan<VM: VMBinding>(tls: VMWorkerThread, mmtk: &'static MMTK<VM>) {
+    use crate::vm::ActivePlan;
+    let mutators = VM::VMActivePlan::number_of_mutators();
+    crate::scheduler::gc_work::inform_stack_scanned(tls, mmtk, mutators);
+}
+
+/// Get the number of work packets pending in the given work bucket.  A binding that delivers a
+/// large number of roots (for example, with conservative stack scanning) can use this to throttle
+/// or batch its calls to [`crate::vm::RootsWorkFactory`] methods based on the queue depth.  This is
+/// cheap and does not take any lock, but the result is only a snapshot, and it does not include the
+/// packets that workers have taken into their local queues.
+///
+/// Arguments:
+/// * `mmtk`: A reference to an MMTk instance.
+/// * `bucket`: The work bucket to query.
+pub fn pending_work_count<VM: VMBinding>(mmtk: &MMTK<VM>, bucket: WorkBucketStage) -> usize {
+    mmtk.scheduler.pending_work_count(bucket)
+}
+
+/// Get the total number of work packets pending in all the work buckets, including custom buckets.
+/// See [`pending_work_count`].
+///
+/// Arguments:
+/// * `mmtk`: A reference to an MMTk instance.
+pub fn total_pending_work_count<VM: VMBinding>(mmtk: &MMTK<VM>) -> usize {
+    mmtk.scheduler.total_pending_work_count()
+}
+
 /// Add a work packet to the given work bucket. Note that this simply adds the work packet to the given
 /// work bucket, and the scheduler will decide when to execute the work packet.
 ///
@@ -898,6 +1363,38 @@ pub fn add_work_packet<VM: VMBinding, W: GCWork<VM>>(
     mmtk.scheduler.work_buckets[bucket].add(packet)
 }
 
+/// Register a custom work bucket that is opened after the given stage, and before the next stage.
+/// This allows plans and bindings to add a stage without changing [`WorkBucketStage`].  Custom
+/// buckets registered after the same stage are opened in the order of registration.  The returned
+/// handle can be used with [`add_work_packet_to_custom_bucket`].
+///
+/// This must be called before the first GC.  At most 8 custom buckets can be registered.
+///
+/// Arguments:
+/// * `mmtk`: A reference to an MMTk instance.
+/// * `after`: The stage after which the custom bucket is opened.  It cannot be
+///   [`WorkBucketStage::Unconstrained`] or [`WorkBucketStage::Final`].
+pub fn register_custom_work_bucket<VM: VMBinding>(
+    mmtk: &'static MMTK<VM>,
+    after: WorkBucketStage,
+) -> CustomBucketId {
+    mmtk.scheduler.register_custom_bucket(after)
+}
+
+/// Add a work packet to a custom work bucket registered by [`register_custom_work_bucket`].
+///
+/// Arguments:
+/// * `mmtk`: A reference to an MMTk instance.
+/// * `bucket`: The handle of the custom bucket.
+/// * `packet`: The work packet to be added.
+pub fn add_work_packet_to_custom_bucket<VM: VMBinding, W: GCWork<VM>>(
+    mmtk: &'static MMTK<VM>,
+    bucket: CustomBucketId,
+    packet: W,
+) {
+    mmtk.scheduler.custom_bucket(bucket).add(packet)
+}
+
 /// Bulk add a number of work packets to the given work bucket. Note that this simply adds the work packets
 /// to the given work bucket, and the scheduler will decide when to execute the work packets.
 ///
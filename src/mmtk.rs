@@ -4,9 +4,8 @@ use crate::plan::gc_requester::GCRequester;
 use crate::plan::CreateGeneralPlanArgs;
 use crate::plan::Plan;
 use crate::policy::sft_map::{create_sft_map, SFTMap};
-use crate::scheduler::GCWorkScheduler;
+use crate::scheduler::{GCWorkScheduler, GCWorker, WorkBucketStage};
 
-#[cfg(feature = "vo_bit")]
 use crate::util::address::ObjectReference;
 #[cfg(feature = "analysis")]
 use crate::util::analysis::AnalysisManager;
@@ -24,6 +23,7 @@ use crate::util::sanity::sanity_checker::SanityChecker;
 #[cfg(feature = "extreme_assertions")]
 use crate::util::slot_logger::SlotLogger;
 use crate::util::statistics::stats::Stats;
+use crate::vm::Collection;
 use crate::vm::ReferenceGlue;
 use crate::vm::VMBinding;
 use std::cell::UnsafeCell;
@@ -123,6 +123,8 @@ pub struct MMTK<VM: VMBinding> {
     pub(crate) gc_trigger: Arc<GCTrigger<VM>>,
     pub(crate) gc_requester: Arc<GCRequester<VM>>,
     pub(crate) stats: Arc<Stats>,
+    /// Slots reported by [`MMTK::enqueue_barrier_slots`] that have not been processed, yet.
+    pub(crate) barrier_slots: Mutex<Vec<Vec<VM::VMSlot>>>,
     inside_harness: AtomicBool,
     #[cfg(feature = "sanity")]
     inside_sanity: AtomicBool,
@@ -148,7 +150,22 @@ impl<VM: VMBinding> MMTK<VM> {
             *options.threads
         };
 
-        let scheduler = GCWorkScheduler::new(num_workers, (*options.thread_affinity).clone());
+        let scheduler = GCWorkScheduler::new(
+            num_workers,
+            (*options.thread_affinity).clone(),
+            *options.deterministic_gc_scheduling,
+            *options.record_bucket_times,
+            *options.record_worker_idle_time,
+            [
+                (WorkBucketStage::Prepare, *options.prepare_bucket_workers),
+                (WorkBucketStage::Release, *options.release_bucket_workers),
+            ]
+            .into_iter()
+            .filter(|(_, n)| *n != 0)
+            .map(|(stage, n)| (stage, (0..num_workers).map(|i| i < n).collect()))
+            .collect(),
+            *options.work_packet_warn_threshold,
+        );
 
         let state = Arc::new(GlobalState::default());
 
@@ -228,6 +245,7 @@ impl<VM: VMBinding> MMTK<VM> {
             gc_trigger,
             gc_requester,
             stats,
+            barrier_slots: Mutex::new(vec![]),
         }
     }
 
@@ -354,18 +372,26 @@ impl<VM: VMBinding> MMTK<VM> {
     }
 
     pub(crate) fn set_gc_status(&self, s: GcStatus) {
-        let mut gc_status = self.state.gc_status.lock().unwrap();
-        if *gc_status == GcStatus::NotInGC {
-            self.state.stacks_prepared.store(false, Ordering::SeqCst);
-            // FIXME stats
-            self.stats.start_gc();
-        }
-        *gc_status = s;
-        if *gc_status == GcStatus::NotInGC {
-            // FIXME stats
-            if self.stats.get_gathering_stats() {
-                self.stats.end_gc();
+        let old_status = {
+            let mut gc_status = self.state.gc_status.lock().unwrap();
+            let old_status = *gc_status;
+            if *gc_status == GcStatus::NotInGC {
+                self.state.stacks_prepared.store(false, Ordering::SeqCst);
+                // FIXME stats
+                self.stats.start_gc();
+            }
+            *gc_status = s;
+            if *gc_status == GcStatus::NotInGC {
+                // FIXME stats
+                if self.stats.get_gathering_stats() {
+                    self.stats.end_gc();
+                }
             }
+            old_status
+        };
+        // Call the hook without holding the lock, so the VM can query the GC status in the hook.
+        if old_status != s {
+            VM::VMCollection::on_gc_status_change(old_status, s);
         }
     }
 
@@ -474,11 +500,46 @@ impl<VM: VMBinding> MMTK<VM> {
         &mut **(self.plan.get())
     }
 
+    /// Enqueue slots dirtied by a mutator write barrier so that they are traced in the transitive
+    /// closure, like the slots in remembered sets (see
+    /// [`crate::vm::RootsWorkFactory::create_process_remembered_slots`]).  The slots are not roots.
+    /// This is intended for experimenting with snapshot-at-the-beginning or incremental-update
+    /// barriers.
+    ///
+    /// MMTk does not have a concurrent marking phase at the moment, so the slots are always
+    /// buffered, and they are processed in the `Closure` bucket of the next GC, when the
+    /// VM-specific roots are scanned.  The slots must still be valid at that time.  Slots enqueued
+    /// after the VM-specific roots of a GC are scanned are processed in the GC after.
+    ///
+    /// This method is thread-safe.  Mutators may call it concurrently, and the slots from each call
+    /// are kept in a separate batch in a buffer protected by a mutex.  To reduce contention, the
+    /// mutators should buffer slots locally and enqueue them in batches.
+    ///
+    /// Arguments:
+    /// * `slots`: The slots dirtied by a write barrier.
+    pub fn enqueue_barrier_slots(&self, slots: Vec<VM::VMSlot>) {
+        if slots.is_empty() {
+            return;
+        }
+        self.barrier_slots.lock().unwrap().push(slots);
+    }
+
     /// Get the run time options.
     pub fn get_options(&self) -> &Options {
         &self.options
     }
 
+    /// Return the objects forwarded in the last GC, in the order each GC worker forwarded them.
+    /// The entries of different workers are not interleaved by time.  Each worker keeps at most a
+    /// fixed number of the latest entries in each GC, so the log may be incomplete if a GC moves
+    /// many objects.  This is useful for debugging stale pointers to moved objects.
+    ///
+    /// The log is updated at the end of each GC, and this method leaves an empty log behind.
+    #[cfg(feature = "forwarding_log")]
+    pub fn dump_forwarding_log(&self) -> Vec<crate::util::copy::ForwardingLogEntry> {
+        std::mem::take(&mut *self.state.forwarding_log_in_last_gc.borrow_mut())
+    }
+
     /// Enumerate objects in all spaces in this MMTK instance.
     ///
     /// The call-back function `f` is called for every object that has the valid object bit (VO
@@ -529,6 +590,114 @@ impl<VM: VMBinding> MMTK<VM> {
         })
     }
 
+    /// Enumerate objects in all spaces in this MMTK instance in ascending address order.
+    ///
+    /// [`MMTK::enumerate_objects`] visits spaces in the order of the plan, and some spaces (e.g.
+    /// the large object space) do not keep their objects in address order, so the order of visited
+    /// objects may differ between runs.  This method visits the same objects as
+    /// [`MMTK::enumerate_objects`], but sorted by address, which is useful for reproducible heap
+    /// dumps (e.g. golden-file tests).  It collects all object references into a temporary buffer
+    /// before calling `f`, so it needs memory proportional to the number of objects.
+    ///
+    /// The same requirements about allocation and GC as [`MMTK::enumerate_objects`] apply.
+    #[cfg(feature = "vo_bit")]
+    pub fn enumerate_objects_ordered<F>(&self, mut f: F)
+    where
+        F: FnMut(ObjectReference),
+    {
+        let mut objects = vec![];
+        self.enumerate_objects(|object| objects.push(object));
+        objects.sort_unstable();
+        objects.into_iter().for_each(&mut f);
+    }
+
+    /// Get the live bytes of each space in the last GC, keyed by the space name.
+    ///
+    /// This is a per-space breakdown of the live bytes counted when the option
+    /// `count_live_bytes_in_gc` is enabled.  It is empty if the option is not enabled or no GC has
+    /// happened yet.  Spaces that have no pages reserved are not included.  See
+    /// [`crate::memory_manager::live_bytes_in_last_gc`] for more detailed statistics.
+    pub fn get_live_bytes_per_space(&self) -> HashMap<&'static str, usize> {
+        self.state
+            .live_bytes_in_last_gc
+            .borrow()
+            .iter()
+            .map(|(&name, stats)| (name, stats.live_bytes))
+            .collect()
+    }
+
+    /// Find all the objects transitively reachable from `roots`, including the roots themselves, by
+    /// scanning objects on the current GC worker.  The objects are returned in the order they are
+    /// visited.  Objects outside MMTk spaces are also included if they are reachable.  This can be
+    /// used for snapshotting a part of the heap or for verification.
+    ///
+    /// This uses the same tracer machinery as the GC (see [`crate::vm::ObjectTracerContext`]), with
+    /// a tracer that does not mark, move or otherwise modify any object, so it does not interfere
+    /// with the state of the GC.  It keeps track of visited objects in a hash set, so it is slow
+    /// and takes memory proportional to the number of reachable objects.
+    ///
+    /// This must only be called when the world is stopped and no object is being moved, e.g. in a
+    /// work packet in the `Release` stage or in a custom work bucket (see
+    /// [`crate::memory_manager::register_custom_work_bucket`]).
+    ///
+    /// Arguments:
+    /// * `worker`: The current GC worker.  Its thread is passed to the object scanning methods in
+    ///   [`crate::vm::Scanning`].
+    /// * `roots`: The objects to start from.
+    pub fn reachable_from(
+        &self,
+        worker: &mut GCWorker<VM>,
+        roots: &[ObjectReference],
+    ) -> Vec<ObjectReference> {
+        crate::scheduler::gc_work::reachable_from(worker, roots)
+    }
+
+    /// Get the time spent in each work bucket stage in the last GC, in the order the stages were
+    /// opened.  The time of a stage is measured from when its bucket was opened until the work in
+    /// the stage was done (i.e. when the next stage was opened, or when the GC finished).  Stages
+    /// that have no work may have a zero duration.
+    ///
+    /// This is only recorded if the option `record_bucket_times` is enabled.  Otherwise, it returns
+    /// an empty vector.
+    pub fn last_gc_bucket_times(&self) -> Vec<(WorkBucketStage, std::time::Duration)> {
+        self.scheduler.last_gc_bucket_times()
+    }
+
+    /// Return `true` if the last GC was a defrag GC, i.e. an Immix space evacuated objects from
+    /// fragmented blocks in that GC.  This is updated at the end of every GC, and is `false` before
+    /// the first GC and for plans without an Immix space.  Runtimes can use this to correlate defrag
+    /// GCs with latency spikes.
+    pub fn last_gc_was_defrag(&self) -> bool {
+        self.state.last_gc_was_defrag.load(Ordering::Relaxed)
+    }
+
+    /// Get the bytes copied into Immix spaces for each copy semantics in the last GC, aggregated
+    /// over all GC workers.  Copy semantics that copied nothing are omitted.  This helps diagnose
+    /// copy amplification.
+    ///
+    /// This is only recorded if the option `record_copy_breakdown` is enabled.  Otherwise, it
+    /// returns an empty vector.
+    pub fn last_gc_copy_breakdown(&self) -> Vec<(crate::util::copy::CopySemantics, usize)> {
+        self.state.copy_breakdown_in_last_gc.borrow().clone()
+    }
+
+    /// Request the next GC to be a defrag GC, regardless of the defrag heuristics.  This only sets
+    /// a flag and does not trigger a GC.  The binding can trigger a GC with
+    /// [`crate::memory_manager::handle_user_collection_request`] after calling this.
+    ///
+    /// The request only affects plans that use an Immix space with defrag enabled.  For
+    /// generational plans, it takes effect at the next full-heap GC, as nursery GCs do not defrag
+    /// the Immix space.  The request is cleared once a defrag GC finishes.  This is useful for
+    /// testing and for measuring the worst-case cost of defrag GCs.
+    pub fn request_defrag_gc(&self) {
+        use crate::policy::immix::ImmixSpace;
+        self.get_plan().for_each_space(&mut |space| {
+            if let Some(immix_space) = space.downcast_ref::<ImmixSpace<VM>>() {
+                immix_space.request_defrag();
+            }
+        });
+    }
+
     /// Aggregate a hash map of live bytes per space with the space stats to produce
     /// a map of live bytes stats for the spaces.
     pub(crate) fn aggregate_live_bytes_in_last_gc(
@@ -559,6 +728,21 @@ impl<VM: VMBinding> MMTK<VM> {
         ret
     }
 
+    /// Map the numbers of scanned objects, indexed by space index, to the names of the spaces.
+    pub(crate) fn aggregate_scanned_objects_in_last_gc(
+        &self,
+        scanned_objects_per_space: [usize; MAX_SPACES],
+    ) -> HashMap<&'static str, usize> {
+        use crate::policy::space::Space;
+        let mut ret = HashMap::new();
+        self.get_plan()
+            .for_each_space(&mut |space: &dyn Space<VM>| {
+                let space_idx = space.get_descriptor().get_index();
+                ret.insert(space.get_name(), scanned_objects_per_space[space_idx]);
+            });
+        ret
+    }
+
     /// Print VM maps.  It will print the memory ranges used by spaces as well as some attributes of
     /// the spaces.
     ///
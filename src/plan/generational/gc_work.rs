@@ -53,21 +53,35 @@ impl<VM: VMBinding, P: GenerationalPlanExt<VM> + PlanTraceObject<VM>, const KIND
     }
 
     fn process_slot(&mut self, slot: SlotOf<Self>) {
-        let Some(object) = slot.load() else {
-            // Skip slots that are not holding an object reference.
+        let Some(object) = slot
+            .load()
+            .and_then(<VM as VMBinding>::VMScanning::filter_slot_object)
+        else {
+            // Skip slots that are not holding an object reference, or rejected by the filter.
             return;
         };
+        if Self::FILTER_NON_MMTK && !Self::in_mmtk_heap(object) {
+            // Skip slots pointing outside the MMTk heap.
+            return;
+        }
         let new_object = self.trace_object(object);
         debug_assert!(!self.plan.is_object_in_nursery(new_object));
         // Note: If `object` is a mature object, `trace_object` will not call `space.trace_object`,
         // but will still return `object`.  In that case, we don't need to write it back.
         if new_object != object {
             slot.store(new_object);
+            self.count_updated_slot();
         }
     }
 
     fn create_scan_work(&self, nodes: Vec<ObjectReference>) -> Self::ScanObjectsWorkType {
-        PlanScanObjects::new(self.plan, nodes, false, self.bucket)
+        PlanScanObjects::new(
+            self.plan,
+            nodes,
+            false,
+            self.bucket,
+            self.max_objects_per_scan_packet(),
+        )
     }
 }
 
@@ -129,7 +143,7 @@ impl<E: ProcessEdgesWork> GCWork<E::VM> for ProcessModBuf<E> {
             // Scan objects in the modbuf and forward pointers
             let modbuf = std::mem::take(&mut self.modbuf);
             GCWork::do_work(
-                &mut ScanObjects::<E>::new(modbuf, false, WorkBucketStage::Closure),
+                &mut ScanObjects::<E>::new(modbuf, false, WorkBucketStage::Closure, None),
                 worker,
                 mmtk,
             )
@@ -240,6 +240,10 @@ impl<VM: VMBinding> crate::plan::generational::global::GenerationalPlanExt<VM> f
 
 impl<VM: VMBinding> GenImmix<VM> {
     pub fn new(args: CreateGeneralPlanArgs<VM>) -> Self {
+        let mark_line_at_scan_time = *args.options.immix_mark_line_at_scan_time;
+        let skip_sweep = *args.options.immix_skip_sweep;
+        let return_empty_chunks = *args.options.immix_return_empty_chunks;
+        let lazy_sweep = *args.options.immix_lazy_sweep;
         let mut plan_args = CreateSpecificPlanArgs {
             global_args: args,
             constraints: &GENIMMIX_CONSTRAINTS,
@@ -255,6 +259,16 @@ impl<VM: VMBinding> GenImmix<VM> {
                 #[cfg(feature = "vo_bit")]
                 mixed_age: false,
                 never_move_objects: false,
+                lazy_sweep,
+                on_block_swept: None,
+                on_sweep_progress: None,
+                on_object_forwarded: None,
+                mark_line_at_scan_time,
+                is_defrag_source: None,
+                shared_defrag_budget: None,
+                skip_sweep,
+                return_empty_chunks,
+                max_reusable_block_holes: None,
             },
         );
 
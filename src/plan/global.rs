@@ -419,6 +419,7 @@ impl<VM: VMBinding> CreateSpecificPlanArgs<'_, VM> {
             scheduler: self.global_args.scheduler.clone(),
             options: self.global_args.options.clone(),
             global_state: self.global_args.state.clone(),
+            stats: self.global_args.stats,
         }
     }
 }
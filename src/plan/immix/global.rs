@@ -130,6 +130,10 @@ impl<VM: VMBinding> Plan for Immix<VM> {
 
 impl<VM: VMBinding> Immix<VM> {
     pub fn new(args: CreateGeneralPlanArgs<VM>) -> Self {
+        let mark_line_at_scan_time = *args.options.immix_mark_line_at_scan_time;
+        let skip_sweep = *args.options.immix_skip_sweep;
+        let return_empty_chunks = *args.options.immix_return_empty_chunks;
+        let lazy_sweep = *args.options.immix_lazy_sweep;
         let plan_args = CreateSpecificPlanArgs {
             global_args: args,
             constraints: &IMMIX_CONSTRAINTS,
@@ -142,6 +146,16 @@ impl<VM: VMBinding> Immix<VM> {
                 #[cfg(feature = "vo_bit")]
                 mixed_age: false,
                 never_move_objects: false,
+                lazy_sweep,
+                on_block_swept: None,
+                on_sweep_progress: None,
+                on_object_forwarded: None,
+                mark_line_at_scan_time,
+                is_defrag_source: None,
+                shared_defrag_budget: None,
+                skip_sweep,
+                return_empty_chunks,
+                max_reusable_block_holes: None,
             },
         )
     }
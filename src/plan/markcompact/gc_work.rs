@@ -58,7 +58,9 @@ impl<VM: VMBinding> GCWork<VM> for UpdateReferences<VM> {
         for mutator in VM::VMActivePlan::mutators() {
             mmtk.scheduler.work_buckets[WorkBucketStage::SecondRoots].add(ScanMutatorRoots::<
                 MarkCompactForwardingGCWorkContext<VM>,
-            >(mutator));
+            >::new(
+                mutator
+            ));
         }
 
         mmtk.scheduler.work_buckets[WorkBucketStage::SecondRoots]
@@ -42,7 +42,7 @@ pub use tracing::{ObjectQueue, ObjectsClosure, VectorObjectQueue, VectorQueue};
 /// Generational plans (with a copying nursery)
 mod generational;
 /// Sticky plans (using sticky marks for generational behaviors without a copying nursery)
-mod sticky;
+pub(crate) mod sticky;
 
 mod immix;
 mod markcompact;
@@ -15,6 +15,7 @@ use crate::util::copy::CopySelector;
 use crate::util::copy::CopySemantics;
 use crate::util::heap::gc_trigger::SpaceStats;
 use crate::util::metadata::side_metadata::SideMetadataContext;
+use crate::util::metadata::MetadataSpec;
 use crate::util::statistics::counter::EventCounter;
 use crate::vm::ObjectModel;
 use crate::vm::VMBinding;
@@ -311,7 +312,12 @@ impl<VM: VMBinding> crate::plan::generational::global::GenerationalPlanExt<VM> f
 
 impl<VM: VMBinding> StickyImmix<VM> {
     pub fn new(args: CreateGeneralPlanArgs<VM>) -> Self {
+        Self::validate_mark_bit_spec(VM::VMObjectModel::LOCAL_MARK_BIT_SPEC.as_spec());
         let full_heap_gc_count = args.stats.new_event_counter("majorGC", true, true);
+        let mark_line_at_scan_time = *args.options.immix_mark_line_at_scan_time;
+        let skip_sweep = *args.options.immix_skip_sweep;
+        let return_empty_chunks = *args.options.immix_return_empty_chunks;
+        let lazy_sweep = *args.options.immix_lazy_sweep;
         let plan_args = CreateSpecificPlanArgs {
             global_args: args,
             constraints: &STICKY_IMMIX_CONSTRAINTS,
@@ -331,6 +337,16 @@ impl<VM: VMBinding> StickyImmix<VM> {
                 #[cfg(feature = "vo_bit")]
                 mixed_age: true,
                 never_move_objects: false,
+                lazy_sweep,
+                on_block_swept: None,
+                on_sweep_progress: None,
+                on_object_forwarded: None,
+                mark_line_at_scan_time,
+                is_defrag_source: None,
+                shared_defrag_budget: None,
+                skip_sweep,
+                return_empty_chunks,
+                max_reusable_block_holes: None,
             },
         );
         Self {
@@ -341,6 +357,17 @@ impl<VM: VMBinding> StickyImmix<VM> {
         }
     }
 
+    /// Check that the mark bits can be used in nursery GCs.  With in-header mark bits, the
+    /// ImmixSpace uses cyclic mark states, and new objects are allocated with the current mark
+    /// state.  Nursery GCs do not flip the mark state, so new objects would appear marked.
+    pub(crate) fn validate_mark_bit_spec(mark_bit_spec: &MetadataSpec) {
+        assert!(
+            mark_bit_spec.is_on_side(),
+            "StickyImmix requires the mark bits to be on the side, \
+             but the mark bits are in the object header"
+        );
+    }
+
     fn requires_full_heap_collection(&self) -> bool {
         // Separate each condition so the code is clear
         #[allow(clippy::if_same_then_else, clippy::needless_bool)]
@@ -32,6 +32,14 @@ impl<T> VectorQueue<T> {
         Self { buffer: Vec::new() }
     }
 
+    /// Create an empty `VectorObjectQueue` that can hold at least `capacity` elements without
+    /// reallocating.  This is useful if the expected number of elements is known.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            buffer: Vec::with_capacity(capacity),
+        }
+    }
+
     /// Return `true` if the queue is empty.
     pub fn is_empty(&self) -> bool {
         self.buffer.is_empty()
@@ -52,13 +60,14 @@ impl<T> VectorQueue<T> {
         self.buffer.len() >= Self::CAPACITY
     }
 
-    /// Push an element to the queue. If the queue is empty, it will reserve
+    /// Push an element to the queue. If the queue has not allocated its buffer
+    /// (i.e. it was not created with [`VectorQueue::with_capacity`]), it will reserve
     /// space to hold the number of elements defined by the capacity.
     /// The user of this method needs to make sure the queue length does
     /// not exceed the capacity to avoid allocating more space
     /// (this method will not check the length against the capacity).
     pub fn push(&mut self, v: T) {
-        if self.buffer.is_empty() {
+        if self.buffer.capacity() == 0 {
             self.buffer.reserve(Self::CAPACITY);
         }
         self.buffer.push(v);
@@ -84,6 +93,10 @@ pub struct ObjectsClosure<'a, E: ProcessEdgesWork> {
     buffer: VectorQueue<SlotOf<E>>,
     pub(crate) worker: &'a mut GCWorker<E::VM>,
     bucket: WorkBucketStage,
+    /// The object whose slots are being visited.  Only set when recording edges.
+    current_object: Option<ObjectReference>,
+    /// The `(parent, child)` edges recorded so far, if edge recording is enabled.
+    edges: Option<Vec<(ObjectReference, ObjectReference)>>,
 }
 
 impl<'a, E: ProcessEdgesWork> ObjectsClosure<'a, E> {
@@ -97,9 +110,23 @@ impl<'a, E: ProcessEdgesWork> ObjectsClosure<'a, E> {
             buffer: VectorQueue::new(),
             worker,
             bucket,
+            current_object: None,
+            edges: None,
         }
     }
 
+    /// Record the edge from the current object (see [`ObjectsClosure::set_current_object`]) to
+    /// each visited slot that holds an object reference.  The edges are appended to the worker's
+    /// edge buffer when the closure is dropped.
+    pub(crate) fn enable_edge_recording(&mut self) {
+        self.edges = Some(vec![]);
+    }
+
+    /// Set the object whose slots are about to be visited.
+    pub(crate) fn set_current_object(&mut self, object: ObjectReference) {
+        self.current_object = Some(object);
+    }
+
     fn flush(&mut self) {
         let buf = self.buffer.take();
         if !buf.is_empty() {
@@ -122,6 +149,12 @@ impl<E: ProcessEdgesWork> SlotVisitor<SlotOf<E>> for ObjectsClosure<'_, E> {
                 slot.load()
             );
         }
+        if let Some(edges) = self.edges.as_mut() {
+            use crate::vm::slot::Slot;
+            if let (Some(parent), Some(child)) = (self.current_object, slot.load()) {
+                edges.push((parent, child));
+            }
+        }
         self.buffer.push(slot);
         if self.buffer.is_full() {
             self.flush();
@@ -132,5 +165,12 @@ impl<E: ProcessEdgesWork> SlotVisitor<SlotOf<E>> for ObjectsClosure<'_, E> {
 impl<E: ProcessEdgesWork> Drop for ObjectsClosure<'_, E> {
     fn drop(&mut self) {
         self.flush();
+        if let Some(mut edges) = self.edges.take() {
+            self.worker
+                .shared
+                .heap_edges
+                .borrow_mut()
+                .append(&mut edges);
+        }
     }
 }
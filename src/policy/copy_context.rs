@@ -1,3 +1,4 @@
+use crate::util::copy::CopyAllocStats;
 use crate::util::Address;
 use crate::util::ObjectReference;
 use crate::vm::VMBinding;
@@ -23,4 +24,9 @@ pub trait PolicyCopyContext: 'static + Send {
         offset: usize,
     ) -> Address;
     fn post_copy(&mut self, _obj: ObjectReference, _bytes: usize) {}
+    /// Return the block acquisition statistics since the last call, and clear them.  By default, a
+    /// policy does not collect any statistics.
+    fn take_alloc_stats(&mut self) -> CopyAllocStats {
+        CopyAllocStats::default()
+    }
 }
@@ -248,8 +248,11 @@ impl<VM: VMBinding> CopySpace<VM> {
         trace!("checking if object is being forwarded");
         if object_forwarding::state_is_forwarded_or_being_forwarded(forwarding_status) {
             trace!("... yes it is");
-            let new_object =
-                object_forwarding::spin_and_get_forwarded_object::<VM>(object, forwarding_status);
+            let new_object = object_forwarding::spin_and_get_forwarded_object_with_limit::<VM>(
+                object,
+                forwarding_status,
+                *self.common.options.forwarding_spin_limit,
+            );
             trace!("Returning");
             new_object
         } else {
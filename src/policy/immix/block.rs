@@ -24,8 +24,12 @@ pub enum BlockState {
     Unmarked,
     /// the block is allocated and marked.
     Marked,
-    /// the block is marked as reusable.
-    Reusable { unavailable_lines: u8 },
+    /// the block is marked as reusable, i.e. it has live objects and free lines.
+    Reusable {
+        /// The number of lines in the block that are not available for allocation, i.e. the lines
+        /// marked in the last GC.
+        unavailable_lines: u8,
+    },
 }
 
 impl BlockState {
@@ -37,6 +41,9 @@ impl BlockState {
     const MARK_MARKED: u8 = u8::MAX - 1;
 }
 
+// The unavailable lines of a reusable block must not collide with the other states.
+const _: () = assert!(Block::LINES - 1 < BlockState::MARK_MARKED as usize);
+
 impl From<u8> for BlockState {
     fn from(state: u8) -> Self {
         match state {
@@ -72,10 +79,7 @@ impl BlockState {
 pub struct Block(Address);
 
 impl Region for Block {
-    #[cfg(not(feature = "immix_smaller_block"))]
-    const LOG_BYTES: usize = 15;
-    #[cfg(feature = "immix_smaller_block")]
-    const LOG_BYTES: usize = 13;
+    const LOG_BYTES: usize = super::LOG_BLOCK_BYTES;
 
     fn from_aligned_address(address: Address) -> Self {
         debug_assert!(address.is_aligned_to(Self::BYTES));
@@ -111,6 +115,15 @@ impl Block {
     pub const MARK_TABLE: SideMetadataSpec =
         crate::util::metadata::side_metadata::spec_defs::IX_BLOCK_MARK;
 
+    /// Block pin table (side)
+    #[cfg(feature = "object_pinning")]
+    pub const PIN_TABLE: SideMetadataSpec =
+        crate::util::metadata::side_metadata::spec_defs::IX_BLOCK_PIN;
+
+    /// Block max hole table (side)
+    pub const MAX_HOLE_TABLE: SideMetadataSpec =
+        crate::util::metadata::side_metadata::spec_defs::IX_BLOCK_MAX_HOLE;
+
     /// Get the chunk containing the block.
     pub fn chunk(&self) -> Chunk {
         Chunk::from_unaligned_address(self.0)
@@ -139,11 +152,15 @@ impl Block {
 
     const DEFRAG_SOURCE_STATE: u8 = u8::MAX;
 
-    /// Test if the block is marked for defragmentation.
+    /// Test if the block is marked for defragmentation.  A pinned block is never a defrag source.
     pub fn is_defrag_source(&self) -> bool {
         let byte = Self::DEFRAG_STATE_TABLE.load_atomic::<u8>(self.start(), Ordering::SeqCst);
         // The byte should be 0 (not defrag source) or 255 (defrag source) if this is a major defrag GC, as we set the values in PrepareBlockState.
         // But it could be any value in a nursery GC.
+        #[cfg(feature = "object_pinning")]
+        if self.is_pinned() {
+            return false;
+        }
         byte == Self::DEFRAG_SOURCE_STATE
     }
 
@@ -153,6 +170,28 @@ impl Block {
         Self::DEFRAG_STATE_TABLE.store_atomic::<u8>(self.start(), byte, Ordering::SeqCst);
     }
 
+    /// Test if the block is pinned.  No objects in a pinned block will be moved.
+    #[cfg(feature = "object_pinning")]
+    pub fn is_pinned(&self) -> bool {
+        // This is checked for every object traced in a defrag GC.  Blocks are pinned and unpinned
+        // by mutators, and the start of a GC synchronizes with them, so a relaxed load is enough.
+        Self::PIN_TABLE.load_atomic::<u8>(self.start(), Ordering::Relaxed) != 0
+    }
+
+    /// Pin or unpin the block.  Return true if the pin state of the block is changed.
+    #[cfg(feature = "object_pinning")]
+    pub fn set_pinned(&self, pinned: bool) -> bool {
+        Self::PIN_TABLE
+            .compare_exchange_atomic::<u8>(
+                self.start(),
+                !pinned as u8,
+                pinned as u8,
+                Ordering::SeqCst,
+                Ordering::SeqCst,
+            )
+            .is_ok()
+    }
+
     /// Record the number of holes in the block.
     pub fn set_holes(&self, holes: usize) {
         Self::DEFRAG_STATE_TABLE.store_atomic::<u8>(self.start(), holes as u8, Ordering::SeqCst);
@@ -165,6 +204,17 @@ impl Block {
         byte as usize
     }
 
+    /// Record the length, in lines, of the largest hole in the block.
+    pub fn set_max_hole_lines(&self, lines: usize) {
+        debug_assert!(lines <= Block::LINES && lines <= u8::MAX as usize);
+        Self::MAX_HOLE_TABLE.store_atomic::<u8>(self.start(), lines as u8, Ordering::SeqCst);
+    }
+
+    /// Get the length, in lines, of the largest hole in the block recorded at the last sweep.
+    pub fn get_max_hole_lines(&self) -> usize {
+        Self::MAX_HOLE_TABLE.load_atomic::<u8>(self.start(), Ordering::SeqCst) as usize
+    }
+
     /// Initialize a clean block after acquired from page-resource.
     pub fn init(&self, copy: bool) {
         self.set_state(if copy {
@@ -178,6 +228,8 @@ impl Block {
     /// Deinitalize a block before releasing.
     pub fn deinit(&self) {
         self.set_state(BlockState::Unallocated);
+        #[cfg(feature = "object_pinning")]
+        self.set_pinned(false);
     }
 
     pub fn start_line(&self) -> Line {
@@ -203,6 +255,9 @@ impl Block {
         mark_histogram: &mut Histogram,
         line_mark_state: Option<u8>,
     ) -> bool {
+        #[cfg(feature = "sanity")]
+        space.verify_defrag_source_block(*self);
+
         if super::BLOCK_ONLY {
             match self.get_state() {
                 BlockState::Unallocated => false,
@@ -233,44 +288,51 @@ impl Block {
                 _ => unreachable!(),
             }
         } else {
-            // Calculate number of marked lines and holes.
-            let mut marked_lines = 0;
-            let mut holes = 0;
-            let mut prev_line_is_marked = true;
             let line_mark_state = line_mark_state.unwrap();
 
-            for line in self.lines() {
-                if line.is_marked(line_mark_state) {
-                    marked_lines += 1;
-                    prev_line_is_marked = true;
-                } else {
-                    if prev_line_is_marked {
-                        holes += 1;
-                    }
-                    // We need to clear the line mark state at least twice in every 128 GC
-                    // otherwise, the line mark state of the last GC will stick around
-                    if line_mark_state > Line::MAX_MARK_STATE - 2 {
-                        line.mark(0);
+            // With lazy sweeping, only count the marked lines and the holes of a live block.  Its
+            // free lines are cleared when the block is taken from the reusable block list (see
+            // `Block::sweep_lazily`).  Near the wrap-around of the line mark state, we sweep
+            // eagerly so that the stale line marks are reset in every block.
+            if space.is_lazy_sweep_enabled() && line_mark_state <= Line::MAX_MARK_STATE - 2 {
+                let (marked_lines, holes) = self.count_lines_without_sweeping(line_mark_state);
+                if marked_lines == 0 {
+                    for line in self.lines() {
+                        Self::clear_unmarked_line::<VM>(line);
                     }
-                    #[cfg(feature = "immix_zero_on_release")]
-                    crate::util::memory::zero(line.start(), Line::BYTES);
+                    self.release_unmarked(space);
+                    return true;
+                }
+                mark_histogram[holes] += marked_lines;
+                // This also overwrites the defrag source state.
+                self.set_holes(holes);
+                // A `Marked` block in the reusable block list has not been swept yet.
+                self.set_state(BlockState::Marked);
+                space.reusable_blocks.push(*self);
 
-                    // We need to clear the pin bit if it is on the side, as this line can be reused
-                    #[cfg(feature = "object_pinning")]
-                    if let MetadataSpec::OnSide(side) = *VM::VMObjectModel::LOCAL_PINNING_BIT_SPEC {
-                        side.bzero_metadata(line.start(), Line::BYTES);
-                    }
+                #[cfg(feature = "vo_bit")]
+                vo_bit::helper::on_region_swept::<VM, _>(self, true);
 
-                    prev_line_is_marked = false;
-                }
+                return false;
             }
 
-            if marked_lines == 0 {
-                #[cfg(feature = "vo_bit")]
-                vo_bit::helper::on_region_swept::<VM, _>(self, false);
+            // Calculate number of marked lines and holes.
+            //
+            // This also handles defrag source blocks that still hold pinned objects (or objects
+            // that could not be evacuated).  Only the lines of the objects left in place are marked
+            // in this GC.  The lines of the evacuated objects are not marked, so they become holes,
+            // and the block becomes reusable instead of staying fully allocated.  No line marks need
+            // to be recomputed after evacuation.  Recording the holes below overwrites the defrag
+            // source state, so the block can be taken from the reusable block list after this GC.
+            // Lines marked in this GC will be unavailable for allocation after this GC because
+            // `ImmixSpace::release` sets `line_unavail_state` to the current `line_mark_state`,
+            // while lines that only hold evacuated objects still carry an older mark state and are
+            // treated as free by `ImmixSpace::get_next_available_lines`.
+            let (marked_lines, holes, max_hole_lines) = self.sweep_lines::<VM>(line_mark_state);
 
+            if marked_lines == 0 {
                 // Release the block if non of its lines are marked.
-                space.release_block(*self);
+                self.release_unmarked(space);
                 true
             } else {
                 // There are some marked lines. Keep the block live.
@@ -279,6 +341,9 @@ impl Block {
                     self.set_state(BlockState::Reusable {
                         unavailable_lines: marked_lines as _,
                     });
+                    if space.is_max_hole_tracking_enabled() {
+                        self.set_max_hole_lines(max_hole_lines);
+                    }
                     space.reusable_blocks.push(*self)
                 } else {
                     // Clear mark state.
@@ -297,6 +362,120 @@ impl Block {
         }
     }
 
+    /// Release a block that has no marked lines when it is swept.
+    fn release_unmarked<VM: VMBinding>(&self, space: &ImmixSpace<VM>) {
+        #[cfg(feature = "vo_bit")]
+        vo_bit::helper::on_region_swept::<VM, _>(self, false);
+
+        space.release_block(*self);
+    }
+
+    /// Count the marked lines, the holes and the length (in lines) of the largest hole in the
+    /// block, and clear the lines that are not marked with `line_mark_state` so that they can be
+    /// reused.
+    fn sweep_lines<VM: VMBinding>(&self, line_mark_state: u8) -> (usize, usize, usize) {
+        let mut marked_lines = 0;
+        let mut holes = 0;
+        let mut prev_line_is_marked = true;
+        // The lengths (in lines) of the current hole and the largest hole seen so far.
+        let mut hole_lines = 0;
+        let mut max_hole_lines = 0;
+
+        for line in self.lines() {
+            if line.is_marked(line_mark_state) {
+                marked_lines += 1;
+                prev_line_is_marked = true;
+            } else {
+                if prev_line_is_marked {
+                    holes += 1;
+                    hole_lines = 0;
+                }
+                hole_lines += 1;
+                max_hole_lines = usize::max(max_hole_lines, hole_lines);
+                // We need to clear the line mark state at least twice in every 128 GC
+                // otherwise, the line mark state of the last GC will stick around
+                if line_mark_state > Line::MAX_MARK_STATE - 2 {
+                    line.mark(0);
+                }
+                Self::clear_unmarked_line::<VM>(line);
+
+                prev_line_is_marked = false;
+            }
+        }
+        (marked_lines, holes, max_hole_lines)
+    }
+
+    /// Finish sweeping a block whose sweeping was deferred by [`Block::sweep`] because lazy
+    /// sweeping is enabled.  This is called when the block is taken from the reusable block list
+    /// for the first time after the GC.  It clears the free lines, and makes the block reusable.
+    /// Return false if the block has no free lines, in which case it must not be used for
+    /// allocation.
+    pub fn sweep_lazily<VM: VMBinding>(&self, space: &ImmixSpace<VM>) -> bool {
+        debug_assert_eq!(self.get_state(), BlockState::Marked);
+        let line_mark_state = space.line_mark_state.load(Ordering::Acquire);
+        let (marked_lines, holes, max_hole_lines) = self.sweep_lines::<VM>(line_mark_state);
+        debug_assert_ne!(marked_lines, 0);
+        debug_assert_eq!(holes, self.get_holes());
+        if marked_lines == Block::LINES {
+            self.set_state(BlockState::Unmarked);
+            return false;
+        }
+        self.set_state(BlockState::Reusable {
+            unavailable_lines: marked_lines as _,
+        });
+        if space.is_max_hole_tracking_enabled() {
+            self.set_max_hole_lines(max_hole_lines);
+        }
+        true
+    }
+
+    /// Count the lines marked with `line_mark_state` and the holes in a block.  This does not clear
+    /// any line.
+    fn count_lines_without_sweeping(&self, line_mark_state: u8) -> (usize, usize) {
+        let mut marked_lines = 0;
+        let mut holes = 0;
+        let mut prev_line_is_marked = true;
+        for line in self.lines() {
+            let marked = line.is_marked(line_mark_state);
+            if marked {
+                marked_lines += 1;
+            } else if prev_line_is_marked {
+                holes += 1;
+            }
+            prev_line_is_marked = marked;
+        }
+        (marked_lines, holes)
+    }
+
+    /// Clear the free lines of a block popped from the reusable block list during a GC, after
+    /// `PrepareBlockState` has reset its state, so that its lazy sweeping no longer happens.  The
+    /// free lines are the lines marked with neither `unavail_state` nor `current_state`.
+    #[allow(clippy::assertions_on_constants)]
+    pub fn clear_free_lines<VM: VMBinding>(&self, unavail_state: u8, current_state: u8) {
+        debug_assert!(!super::BLOCK_ONLY);
+        for line in self.lines() {
+            if !line.is_marked(unavail_state) && !line.is_marked(current_state) {
+                Self::clear_unmarked_line::<VM>(line);
+            }
+        }
+    }
+
+    /// Clear the memory and metadata of an unmarked line so that it can be reused.
+    #[cfg_attr(
+        not(feature = "object_pinning"),
+        allow(clippy::extra_unused_type_parameters)
+    )]
+    fn clear_unmarked_line<VM: VMBinding>(_line: Line) {
+        #[cfg(feature = "immix_zero_on_release")]
+        crate::util::memory::zero(_line.start(), Line::BYTES);
+
+        // We need to clear the pin bit if it is on the side, as this line can be reused
+        #[cfg(feature = "object_pinning")]
+        if let MetadataSpec::OnSide(side) = *VM::VMObjectModel::LOCAL_PINNING_BIT_SPEC {
+            side.bzero_metadata(_line.start(), Line::BYTES);
+        }
+    }
+
     /// Clear VO bits metadata for unmarked regions.
     /// This is useful for clearing VO bits during nursery GC for StickyImmix
     /// at which time young objects (allocated in unmarked regions) may die
@@ -359,6 +538,11 @@ impl ReusableBlockPool {
         self.queue.pop()
     }
 
+    /// Add blocks to the list from any thread.  See [`BlockPool::push_global`].
+    pub fn push_global(&self, blocks: &[Block]) {
+        self.queue.push_global(blocks)
+    }
+
     /// Clear the list.
     pub fn reset(&mut self) {
         self.queue = BlockPool::new(self.num_workers);
@@ -373,4 +557,72 @@ impl ReusableBlockPool {
     pub fn flush_all(&self) {
         self.queue.flush_all();
     }
+
+    /// Compute statistics of the blocks in the list.
+    pub fn stats(&self) -> ReusableBlockStats {
+        let mut stats = ReusableBlockStats {
+            blocks: 0,
+            available_lines: 0,
+            hole_histogram: vec![0; Block::LINES / 2 + 1],
+        };
+        self.iterate_blocks(|block| {
+            stats.blocks += 1;
+            if let BlockState::Reusable { unavailable_lines } = block.get_state() {
+                stats.available_lines += Block::LINES - unavailable_lines as usize;
+            }
+            if !block.is_defrag_source() {
+                stats.hole_histogram[block.get_holes()] += 1;
+            }
+        });
+        stats
+    }
+}
+
+/// Statistics of the reusable blocks in an Immix space.  See
+/// [`crate::memory_manager::immix_reusable_block_stats`].
+#[derive(Clone, Debug)]
+pub struct ReusableBlockStats {
+    /// The number of reusable blocks.
+    pub blocks: usize,
+    /// The total number of lines available for allocation in all the reusable blocks.
+    pub available_lines: usize,
+    /// The distribution of hole counts. `hole_histogram[n]` is the number of reusable blocks that
+    /// have `n` holes.
+    pub hole_histogram: Vec<usize>,
+}
+
+/// A read-only view of an Immix block, for bindings and external tools such as heap visualizers to
+/// map objects to blocks and report block occupancy.  See
+/// [`crate::memory_manager::immix_block_of`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImmixBlock(Block);
+
+impl ImmixBlock {
+    /// Get the start address of the block.
+    pub fn start(&self) -> Address {
+        self.0.start()
+    }
+
+    /// Get the size of the block in bytes.
+    pub const fn bytes() -> usize {
+        Block::BYTES
+    }
+
+    /// Get the allocation state of the block.
+    pub fn get_state(&self) -> BlockState {
+        self.0.get_state()
+    }
+
+    /// Get the number of holes (groups of contiguous free lines) in the block when it was last
+    /// swept.  Return `None` if the block is selected as a defrag source in the current GC, in
+    /// which case the number is not available.
+    pub fn get_holes(&self) -> Option<usize> {
+        (!self.0.is_defrag_source()).then(|| self.0.get_holes())
+    }
+}
+
+impl From<Block> for ImmixBlock {
+    fn from(block: Block) -> Self {
+        Self(block)
+    }
 }
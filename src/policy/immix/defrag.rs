@@ -8,6 +8,7 @@ use crate::{policy::space::Space, Plan};
 use crate::{util::constants::LOG_BYTES_IN_PAGE, vm::*};
 use spin::Mutex;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
 
 pub type Histogram = [usize; Defrag::NUM_BINS];
 
@@ -23,6 +24,94 @@ pub struct Defrag {
     pub defrag_spill_threshold: AtomicUsize,
     /// The number of remaining clean pages in defrag space.
     available_clean_pages_for_defrag: AtomicUsize,
+    /// The number of blocks selected as defrag sources in the current GC.
+    defrag_source_blocks: AtomicUsize,
+    /// Has the user requested the next full-heap GC to be a defrag GC?
+    defrag_requested: AtomicBool,
+    /// The number of defrag headroom pages reserved by the binding for the next defrag GC.
+    reserved_headroom_pages: AtomicUsize,
+    /// The number of blocks with exactly `defrag_spill_threshold` holes that may still be selected
+    /// as defrag sources in this GC.  It is only non-zero if the number of defrag source blocks is
+    /// limited.  See [`Defrag::limit_defrag_source_blocks`].
+    boundary_defrag_source_blocks: AtomicUsize,
+    /// The budget shared with other Immix spaces, if any.  If set, clean blocks for defrag are
+    /// drawn from the shared budget instead of `available_clean_pages_for_defrag`.
+    shared_budget: Option<Arc<SharedDefragBudget>>,
+    /// The number of clean blocks acquired by mutator allocators since the last reset.
+    mutator_clean_blocks: AtomicUsize,
+    /// The number of clean blocks acquired by copy allocators since the last reset.
+    copy_clean_blocks: AtomicUsize,
+}
+
+/// A defrag budget shared by multiple Immix spaces, e.g. a space for strings and a space for other
+/// objects.  Each space still computes its own defrag headroom (see
+/// [`Defrag::defrag_headroom_pages`]), so the plan accounts for the headroom of each space once.
+/// But in each defrag GC, the clean pages available for copying are pooled: the budget starts with
+/// the free pages of the heap plus the collection reserve, and each space adds its headroom when
+/// it is prepared.  Clean blocks acquired for copying by any of the spaces are deducted from the
+/// pool, and once the pool is used up, the defrag space is exhausted for all the spaces, so that
+/// one space cannot use the headroom that the others counted on.
+///
+/// All the spaces sharing a budget must be prepared for defrag in the same GCs, i.e. in every
+/// full-heap GC.  The budget is reset when the first of them is prepared.
+#[derive(Debug, Default)]
+pub struct SharedDefragBudget {
+    /// The number of spaces sharing this budget.
+    num_spaces: AtomicUsize,
+    /// The number of times a space sharing this budget was prepared.  The budget is reset when
+    /// this is a multiple of `num_spaces`.
+    prepared_spaces: AtomicUsize,
+    /// The number of remaining clean pages for defrag in all the spaces sharing this budget.
+    available_clean_pages: AtomicUsize,
+    /// Is the shared defrag space exhausted?
+    exhausted: AtomicBool,
+}
+
+impl SharedDefragBudget {
+    /// Register a space that shares this budget.
+    fn register_space(&self) {
+        self.num_spaces.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Add the headroom of a space being prepared to the pool.  If it is the first space prepared
+    /// in this GC, reset the pool to `copy_headroom_pages` first.
+    fn prepare_space(&self, copy_headroom_pages: usize, space_headroom_pages: usize) {
+        let num_spaces = self.num_spaces.load(Ordering::Relaxed);
+        let prepared = self.prepared_spaces.fetch_add(1, Ordering::AcqRel);
+        if prepared % num_spaces == 0 {
+            self.exhausted.store(false, Ordering::Release);
+            self.available_clean_pages
+                .store(copy_headroom_pages, Ordering::Release);
+        }
+        self.available_clean_pages
+            .fetch_add(space_headroom_pages, Ordering::AcqRel);
+    }
+
+    /// The number of clean pages left in the pool, or 0 if the pool is exhausted.
+    fn remaining_pages(&self) -> usize {
+        if self.space_exhausted() {
+            return 0;
+        }
+        self.available_clean_pages.load(Ordering::Acquire)
+    }
+
+    /// Deduct a clean block from the pool.
+    fn notify_new_clean_block(&self) {
+        let available = self
+            .available_clean_pages
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |available| {
+                Some(available.saturating_sub(Block::PAGES))
+            })
+            .unwrap();
+        if available <= Block::PAGES {
+            self.exhausted.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// Check if the shared defrag space is exhausted.
+    pub fn space_exhausted(&self) -> bool {
+        self.exhausted.load(Ordering::Acquire)
+    }
 }
 
 pub struct StatsForDefrag {
@@ -39,6 +128,11 @@ impl StatsForDefrag {
             collection_reserved_pages: plan.get_collection_reserved_pages(),
         }
     }
+
+    /// The number of pages that are free for copying in this GC, including the collection reserve.
+    pub fn copy_headroom_pages(&self) -> usize {
+        (self.total_pages + self.collection_reserved_pages).saturating_sub(self.reserved_pages)
+    }
 }
 
 impl Defrag {
@@ -47,6 +141,18 @@ impl Defrag {
     const MIN_SPILL_THRESHOLD: usize = 2;
     const DEFRAG_HEADROOM_PERCENT: usize = super::DEFRAG_HEADROOM_PERCENT;
 
+    /// Create the defrag utilities for a space.  If `shared_budget` is set, the space draws clean
+    /// blocks for defrag from the shared budget.
+    pub fn new(shared_budget: Option<Arc<SharedDefragBudget>>) -> Self {
+        if let Some(shared_budget) = shared_budget.as_ref() {
+            shared_budget.register_space();
+        }
+        Self {
+            shared_budget,
+            ..Default::default()
+        }
+    }
+
     /// Allocate a new local histogram.
     pub const fn new_histogram(&self) -> Histogram {
         [0; Self::NUM_BINS]
@@ -79,26 +185,73 @@ impl Defrag {
                 || (collection_attempts > 1)
                 || !exhausted_reusable_space
                 || super::STRESS_DEFRAG
-                || (collect_whole_heap && user_triggered && full_heap_system_gc));
+                || (collect_whole_heap && user_triggered && full_heap_system_gc)
+                || self.defrag_requested.load(Ordering::Acquire));
         info!("Defrag: {}", in_defrag);
         probe!(mmtk, immix_defrag, in_defrag);
         self.in_defrag_collection
             .store(in_defrag, Ordering::Release)
     }
 
-    /// Get the number of defrag headroom pages.
+    /// Get the number of defrag headroom pages, excluding the pages reserved with
+    /// [`Defrag::reserve_headroom`].
     pub fn defrag_headroom_pages<VM: VMBinding>(&self, space: &ImmixSpace<VM>) -> usize {
-        space.get_page_resource().reserved_pages() * Self::DEFRAG_HEADROOM_PERCENT / 100
+        (space.get_page_resource().reserved_pages() * Self::DEFRAG_HEADROOM_PERCENT / 100)
+            .saturating_sub(self.reserved_headroom_pages.load(Ordering::Acquire))
+    }
+
+    /// Reserve `pages` more pages of the defrag headroom so that the next defrag GC does not
+    /// use them for copying.
+    pub fn reserve_headroom(&self, pages: usize) {
+        self.reserved_headroom_pages
+            .fetch_add(pages, Ordering::AcqRel);
+    }
+
+    /// Release all the defrag headroom reserved with [`Defrag::reserve_headroom`].
+    pub fn release_headroom(&self) {
+        self.reserved_headroom_pages.store(0, Ordering::Release);
     }
 
-    /// Check if the defrag space is exhausted.
+    /// Check if the defrag space is exhausted.  If the space shares a defrag budget with other
+    /// spaces, this checks whether the shared budget is exhausted, which may happen because other
+    /// spaces acquired clean blocks for copying.
     pub fn space_exhausted(&self) -> bool {
+        if let Some(shared_budget) = self.shared_budget.as_ref() {
+            return shared_budget.space_exhausted();
+        }
         self.defrag_space_exhausted.load(Ordering::Acquire)
     }
 
-    /// Update available_clean_pages_for_defrag counter when a clean block is allocated.
+    /// Get the number of clean pages that copy allocators may still acquire in the current GC
+    /// before [`Defrag::space_exhausted`] returns true.  This reads the same counters as
+    /// `space_exhausted`, including the shared budget if the space shares one, and does not take
+    /// any lock, so it can be polled during tracing.  The value may be stale by the time the caller
+    /// uses it, as copy allocators acquire clean blocks concurrently.  It is 0 if the defrag space
+    /// is exhausted or if defrag is disabled for `space`.
+    pub fn remaining_headroom_pages<VM: VMBinding>(&self, space: &ImmixSpace<VM>) -> usize {
+        if !space.is_defrag_enabled() {
+            return 0;
+        }
+        if let Some(shared_budget) = self.shared_budget.as_ref() {
+            return shared_budget.remaining_pages();
+        }
+        if self.defrag_space_exhausted.load(Ordering::Acquire) {
+            return 0;
+        }
+        self.available_clean_pages_for_defrag
+            .load(Ordering::Acquire)
+    }
+
+    /// Update available_clean_pages_for_defrag counter when a clean block is allocated.  If the
+    /// space shares a defrag budget with other spaces, the block is deducted from the shared budget
+    /// instead.
     pub fn notify_new_clean_block(&self, copy: bool) {
         if copy {
+            self.copy_clean_blocks.fetch_add(1, Ordering::Relaxed);
+            if let Some(shared_budget) = self.shared_budget.as_ref() {
+                shared_budget.notify_new_clean_block();
+                return;
+            }
             let available_clean_pages_for_defrag =
                 self.available_clean_pages_for_defrag.fetch_update(
                     Ordering::SeqCst,
@@ -114,13 +267,33 @@ impl Defrag {
             if available_clean_pages_for_defrag.unwrap() <= Block::PAGES {
                 self.defrag_space_exhausted.store(true, Ordering::SeqCst);
             }
+        } else {
+            self.mutator_clean_blocks.fetch_add(1, Ordering::Relaxed);
         }
     }
 
+    /// Get the number of clean blocks acquired by mutator allocators and by copy allocators,
+    /// respectively, since the last call to [`Defrag::reset_clean_block_counts`].
+    pub fn clean_block_counts(&self) -> (usize, usize) {
+        (
+            self.mutator_clean_blocks.load(Ordering::Relaxed),
+            self.copy_clean_blocks.load(Ordering::Relaxed),
+        )
+    }
+
+    /// Reset the counts returned by [`Defrag::clean_block_counts`].
+    pub fn reset_clean_block_counts(&self) {
+        self.mutator_clean_blocks.store(0, Ordering::Relaxed);
+        self.copy_clean_blocks.store(0, Ordering::Relaxed);
+    }
+
     /// Prepare work. Should be called in ImmixSpace::prepare.
     pub fn prepare<VM: VMBinding>(&self, space: &ImmixSpace<VM>, plan_stats: StatsForDefrag) {
         debug_assert!(space.is_defrag_enabled());
         self.defrag_space_exhausted.store(false, Ordering::Release);
+        self.defrag_source_blocks.store(0, Ordering::Release);
+        self.boundary_defrag_source_blocks
+            .store(0, Ordering::Release);
 
         // Calculate available free space for defragmentation.
 
@@ -142,6 +315,80 @@ impl Defrag {
             available_clean_pages_for_defrag as usize + plan_stats.collection_reserved_pages,
             Ordering::Release,
         );
+
+        if let Some(shared_budget) = self.shared_budget.as_ref() {
+            shared_budget.prepare_space(
+                plan_stats.copy_headroom_pages(),
+                self.defrag_headroom_pages(space),
+            );
+        }
+    }
+
+    /// Limit the number of defrag source blocks in this GC to `max_blocks` by raising the defrag
+    /// threshold.  Should be called after [`Defrag::prepare`] in a defrag GC.
+    ///
+    /// Blocks are selected as defrag sources by `PrepareBlockState` packets executed by multiple
+    /// workers in parallel, so no single worker can see all the candidates and pick the worst ones.
+    /// Instead, we count the blocks by the number of holes here, before the packets are created.
+    /// Starting from the blocks with the most holes, we find the number of holes `h` such that the
+    /// blocks with more than `h` holes are no more than `max_blocks`, but adding the blocks with `h`
+    /// holes would exceed it.  `h` becomes the new threshold, so all the blocks with more holes are
+    /// selected.  The blocks with exactly `h` holes share the rest of the budget via an atomic
+    /// counter (see [`Defrag::take_boundary_defrag_source_block`]), and which of them are selected
+    /// depends on the order the workers visit them.
+    ///
+    /// This only applies if `ImmixSpaceArgs::is_defrag_source` is not set.  Otherwise, the predicate
+    /// is given the raised threshold, but it decides on its own.
+    pub fn limit_defrag_source_blocks<VM: VMBinding>(
+        &self,
+        space: &ImmixSpace<VM>,
+        max_blocks: usize,
+    ) {
+        // Count the blocks that may be defrag sources by the number of holes.
+        let mut block_counts = self.new_histogram();
+        for chunk in space.chunk_map.all_chunks() {
+            for block in chunk.iter_region::<Block>() {
+                if block.get_state() != BlockState::Unallocated && !space.is_block_pinned(block) {
+                    block_counts[block.get_holes()] += 1;
+                }
+            }
+        }
+
+        let threshold = self.defrag_spill_threshold.load(Ordering::Acquire);
+        let mut selected_blocks = 0;
+        for holes in (threshold + 1..Self::NUM_BINS).rev() {
+            if selected_blocks + block_counts[holes] > max_blocks {
+                debug!(
+                    "Defrag threshold raised from {} to {} to select at most {} blocks",
+                    threshold, holes, max_blocks
+                );
+                self.defrag_spill_threshold.store(holes, Ordering::Release);
+                self.boundary_defrag_source_blocks
+                    .store(max_blocks - selected_blocks, Ordering::Release);
+                return;
+            }
+            selected_blocks += block_counts[holes];
+        }
+    }
+
+    /// Try to select a block with exactly `defrag_spill_threshold` holes as a defrag source.
+    /// Return `true` if the limit set by [`Defrag::limit_defrag_source_blocks`] allows it.
+    pub fn take_boundary_defrag_source_block(&self) -> bool {
+        self.boundary_defrag_source_blocks
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |blocks| {
+                blocks.checked_sub(1)
+            })
+            .is_ok()
+    }
+
+    /// Called when a block is selected as a defrag source.
+    pub fn notify_new_defrag_source_block(&self) {
+        self.defrag_source_blocks.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Get the number of blocks selected as defrag sources in the current GC.
+    pub fn defrag_source_blocks(&self) -> usize {
+        self.defrag_source_blocks.load(Ordering::Relaxed)
     }
 
     /// Get the numebr of all the recyclable lines in all the reusable blocks.
@@ -155,6 +402,9 @@ impl Defrag {
             let bucket = block.get_holes();
             let unavailable_lines = match block.get_state() {
                 BlockState::Reusable { unavailable_lines } => unavailable_lines as usize,
+                // A block that has not been swept lazily since the last GC.  Its free lines are
+                // unknown, and it is not counted.
+                BlockState::Marked => Block::LINES,
                 s => unreachable!("{:?} {:?}", block, s),
             };
             let available_lines = Block::LINES - unavailable_lines;
@@ -211,4 +461,14 @@ impl Defrag {
     pub fn reset_in_defrag(&self) {
         self.in_defrag_collection.store(false, Ordering::Release);
     }
+
+    /// Force the next full-heap GC to be a defrag GC.
+    pub fn request_defrag(&self) {
+        self.defrag_requested.store(true, Ordering::Release);
+    }
+
+    /// Clear the request set by [`Defrag::request_defrag`].
+    pub fn clear_defrag_request(&self) {
+        self.defrag_requested.store(false, Ordering::Release);
+    }
 }
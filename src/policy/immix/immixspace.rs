@@ -1,6 +1,10 @@
 use super::defrag::StatsForDefrag;
 use super::line::*;
-use super::{block::*, defrag::Defrag};
+use super::{
+    block::*,
+    defrag::{Defrag, SharedDefragBudget},
+};
+use crate::global_state::GcStatus;
 use crate::plan::VectorObjectQueue;
 use crate::policy::gc_work::{TraceKind, TRACE_KIND_TRANSITIVE_PIN};
 use crate::policy::sft::GCWorkerMutRef;
@@ -19,6 +23,7 @@ use crate::util::metadata::vo_bit;
 use crate::util::metadata::{self, MetadataSpec};
 use crate::util::object_enum::ObjectEnumerator;
 use crate::util::object_forwarding;
+use crate::util::statistics::counter::EventCounter;
 use crate::util::{copy::*, epilogue, object_enum};
 use crate::util::{Address, ObjectReference};
 use crate::vm::*;
@@ -29,7 +34,21 @@ use crate::{
     MMTK,
 };
 use atomic::Ordering;
-use std::sync::{atomic::AtomicU8, atomic::AtomicUsize, Arc};
+use std::collections::HashMap;
+use std::sync::{atomic::AtomicU8, atomic::AtomicUsize, Arc, Mutex};
+
+/// The reason why `ImmixSpace::evacuate_block` failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvacuateBlockError {
+    /// The address is not in an allocated block of the Immix space.
+    NotAllocated,
+    /// The block is pinned, and its objects cannot be moved.
+    BlockPinned,
+    /// The Immix space never moves objects, so there are no defrag GCs to evacuate the block in.
+    DefragDisabled,
+    /// A GC is in progress.  Blocks can only be requested to be evacuated between GCs.
+    GCInProgress,
+}
 
 pub(crate) const TRACE_KIND_FAST: TraceKind = 0;
 pub(crate) const TRACE_KIND_DEFRAG: TraceKind = 1;
@@ -55,8 +74,74 @@ pub struct ImmixSpace<VM: VMBinding> {
     scheduler: Arc<GCWorkScheduler<VM>>,
     /// Some settings for this space
     space_args: ImmixSpaceArgs,
+    /// The number of chunks that `PrepareBlockState` skipped because they have no allocated blocks.
+    /// Chunks that `SweepChunk` found empty are freed in the chunk map, and get no packets at all.
+    prepare_skipped_chunks: Arc<Mutex<EventCounter>>,
+    /// The number of blocks selected as defrag sources.
+    defrag_source_blocks: Arc<Mutex<EventCounter>>,
+    /// The number of pages each [`CopyPurpose`] may still acquire as clean blocks in this GC,
+    /// indexed by the purpose.
+    copy_budget_pages: [AtomicUsize; 2],
+    /// Set if the copy budget of each [`CopyPurpose`] is used up in this GC, indexed by the
+    /// purpose.  Objects are no longer copied opportunistically for the purpose from then on.
+    copy_budget_exhausted: [std::sync::atomic::AtomicBool; 2],
+    /// The live objects in defrag source blocks that are marked in place in this GC, e.g. because
+    /// they are reachable from pinning roots, or they are pinned.  Used by
+    /// [`ImmixSpace::verify_defrag_source_block`].
+    #[cfg(feature = "sanity")]
+    defrag_objects_marked_in_place: Mutex<std::collections::HashSet<ObjectReference>>,
+    /// Set if copying is disabled for the rest of the current GC, in which case objects are marked
+    /// in place.  See [`ImmixSpace::disable_copying_for_rest_of_gc`].
+    copying_disabled: std::sync::atomic::AtomicBool,
+    /// Whether the current (or last) GC of this space is a nursery GC.  Set in
+    /// [`ImmixSpace::prepare`].
+    in_nursery_gc: bool,
+    /// The number of times `line_mark_state` wrapped around since the space was created.
+    line_mark_state_wraps: AtomicUsize,
+    /// Whether we have warned about stale line marks in `verify_line_marks_before_wrap`.
+    #[cfg(debug_assertions)]
+    stale_line_marks_warned: std::sync::atomic::AtomicBool,
+    /// The number of consecutive GCs at the end of which each free chunk was found free, indexed
+    /// by the start address of the chunk.  Only
+    /// used if `ImmixSpaceArgs::return_empty_chunks` is set.
+    empty_chunk_ages: Mutex<HashMap<Address, usize>>,
+    /// The start addresses of the blocks requested by [`ImmixSpace::evacuate_block`] to be
+    /// evacuated in the next defrag GC.
+    blocks_to_evacuate: Mutex<std::collections::HashSet<Address>>,
+    /// The start addresses of the blocks forced to be defrag sources in the current GC.  Taken from
+    /// `blocks_to_evacuate` in [`ImmixSpace::prepare`] of a defrag GC, and read by
+    /// `PrepareBlockState`.
+    forced_defrag_sources: std::collections::HashSet<Address>,
 }
 
+/// The purpose of a copy allocator in [`ImmixHybridCopyContext`] and [`ImmixCopyContext`].  Each
+/// purpose draws clean blocks from its own copy budget, so that one purpose cannot use up all the
+/// headroom of the other.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum CopyPurpose {
+    /// Copying objects outside defrag GCs, such as promoting objects to the mature space, or
+    /// copying nursery objects in a nursery GC of StickyImmix.
+    Promotion = 0,
+    /// Copying objects in defrag GCs.
+    Defrag = 1,
+}
+
+/// The type of the callback invoked after an Immix block is swept.
+/// See [`ImmixSpaceArgs::on_block_swept`].
+pub type BlockSweptCallback = Box<dyn Fn(Block, &BlockState) + Send + Sync>;
+
+/// The type of the callback invoked after each chunk is swept.
+/// See [`ImmixSpaceArgs::on_sweep_progress`].
+pub type SweepProgressCallback = Box<dyn Fn(usize, usize) + Send + Sync>;
+
+/// The type of the callback invoked after an object is moved by an Immix space.
+/// See [`ImmixSpaceArgs::on_object_forwarded`].
+pub type ObjectForwardedCallback = Box<dyn Fn(ObjectReference, ObjectReference) + Send + Sync>;
+
+/// The type of the predicate that decides whether a block is a defrag source.
+/// See [`ImmixSpaceArgs::is_defrag_source`].
+pub type DefragSourcePredicate = Box<dyn Fn(Block, usize, usize) -> bool + Send + Sync>;
+
 /// Some arguments for Immix Space.
 pub struct ImmixSpaceArgs {
     /// Mark an object as unlogged when we trace an object.
@@ -64,6 +149,14 @@ pub struct ImmixSpaceArgs {
     /// In sticky immix, we 'promote' an object to mature when we trace the object
     /// (no matter we copy an object or not). So we have to use `PromoteToMature`, and instead
     /// just set the log bit in the space when an object is traced.
+    ///
+    /// Note that this cannot be delayed until an object has survived a number of nursery GCs.
+    /// Sticky immix does not clear mark bits in nursery GCs, so an object marked in a nursery GC
+    /// will not be traced again until the next full-heap GC.  If such an object were left logged,
+    /// the write barrier would not record the young objects later stored into its fields, and
+    /// those young objects could be reclaimed in the next nursery GC while still reachable.
+    /// Object aging would require survivors to be traced again in later nursery GCs, which this
+    /// space does not support.
     pub unlog_object_when_traced: bool,
     /// Whether this ImmixSpace instance contains both young and old objects.
     /// This affects the updating of valid-object bits.  If some lines or blocks of this ImmixSpace
@@ -75,6 +168,83 @@ pub struct ImmixSpaceArgs {
     pub mixed_age: bool,
     /// Disable copying for this Immix space.
     pub never_move_objects: bool,
+    /// Defer sweeping live blocks until the blocks are taken from the reusable block list for
+    /// allocation.  `SweepChunk` only releases the blocks without marked lines, updates the chunk
+    /// map and the mark histograms, and puts the other blocks in the reusable block list in the
+    /// `Marked` state.  Clearing the free lines and setting the block state are done when a block is
+    /// popped from the list for the first time after the GC (see `Block::sweep_lazily`).  The blocks
+    /// are still swept eagerly in the GCs just before the line mark state wraps around.  This has
+    /// no effect for block-only Immix.  See the option `immix_lazy_sweep`.
+    pub lazy_sweep: bool,
+    /// A callback invoked after each allocated block is swept, with the state of the block after
+    /// sweeping.  A block released to the page resource will have the state
+    /// [`BlockState::Unallocated`].  This can be used to update per-block data structures kept by
+    /// the runtime, such as card tables or region summaries.
+    ///
+    /// Blocks are swept in parallel by multiple GC workers, so the callback may be called
+    /// concurrently from different threads.  Hence it must be `Sync`.
+    pub on_block_swept: Option<BlockSweptCallback>,
+    /// A callback invoked after each chunk is swept, with the number of chunks swept so far in
+    /// this GC and the total number of chunks to sweep.  This can be used to report the progress
+    /// of long GC pauses, e.g. to update a progress bar or to emit heartbeats.
+    ///
+    /// Chunks are swept in parallel by multiple GC workers, so the callback may be called
+    /// concurrently from different threads, and calls may not be observed in the order of the
+    /// swept counts.  It must be `Sync`, and it should be cheap.
+    pub on_sweep_progress: Option<SweepProgressCallback>,
+    /// A callback invoked with the old and the new object reference after an object is copied
+    /// during tracing.  This can be used to update data structures outside the heap that are keyed
+    /// on object addresses.  It is only called for objects that are actually moved, not for objects
+    /// that are marked in place (e.g. pinned objects, or objects in non-defrag-source blocks).
+    ///
+    /// The callback is called on the GC worker that copies the object, after the forwarding
+    /// pointer is installed.  Objects are copied in parallel, so it must be `Sync`.  It is called
+    /// once for every moved object, which is on the hot path of tracing, so it should be cheap.
+    pub on_object_forwarded: Option<ObjectForwardedCallback>,
+    /// Mark lines when scanning objects (in `post_scan_object`).  Otherwise, mark lines when
+    /// tracing (marking or copying) objects.  Marking lines at scan time requires the plan to call
+    /// `post_scan_object`, e.g. by using `PlanProcessEdges`.  This has no effect for block-only
+    /// Immix.
+    pub mark_line_at_scan_time: bool,
+    /// A predicate that decides whether a block is selected as a defrag source in a defrag GC,
+    /// given the block, the number of holes in the block and the defrag threshold of this GC.  If
+    /// it is `None`, a block is a defrag source if it has more holes than the threshold.  This can be
+    /// used to implement other defrag policies, such as evacuating blocks by age or access
+    /// frequency.  Pinned blocks are never defrag sources, regardless of the predicate.
+    ///
+    /// The predicate is called by the GC workers that prepare blocks in parallel, so it must be
+    /// `Sync`.
+    pub is_defrag_source: Option<DefragSourcePredicate>,
+    /// A defrag budget shared with other Immix spaces.  If it is `None`, this space computes its
+    /// own budget for copying objects in defrag GCs from its own headroom.  Otherwise, the headroom
+    /// of all the spaces sharing the budget is pooled, and the spaces draw clean blocks for
+    /// defragmentation from the pool, so that they coordinate rather than each reserving
+    /// independently.  See [`SharedDefragBudget`].
+    pub shared_defrag_budget: Option<Arc<SharedDefragBudget>>,
+    /// Skip sweeping in the `Release` stage.  The space is still prepared and objects are still
+    /// marked (and possibly evacuated), but no block or line is reclaimed, and the VO bits of dead
+    /// objects are not cleared.  All the blocks stay allocated, so the heap leaks and will run out
+    /// of memory quickly.  This is for debugging only, e.g. to tell whether a crash happens in
+    /// marking or in sweeping.
+    pub skip_sweep: bool,
+    /// Return the memory of empty chunks to the operating system after sweeping.  A chunk is only
+    /// returned after it is found empty at the end of a few consecutive GCs, so that chunks that
+    /// are likely to be acquired again soon are not returned.  The chunks stay mapped and reserved
+    /// for this space, and their pages are faulted in again when they are used.  This trades the
+    /// cost of page faults for a lower resident set size.
+    pub return_empty_chunks: bool,
+    /// Skip reusable blocks that have more holes than this limit in
+    /// [`ImmixSpace::get_reusable_block`], so that allocators prefer less fragmented blocks for
+    /// better locality.  The number of holes is recorded when the block is swept.  Skipped blocks
+    /// stay in the reusable block list, and may be used by [`ImmixSpace::get_reusable_block_for_size`]
+    /// or evacuated by the next defrag GC.  At most [`ImmixSpace::MAX_BLOCKS_TO_EXAMINE`] blocks
+    /// are examined for each request.  If it is `None`, any reusable block may be used.
+    ///
+    /// Note that [`ImmixSpace::decide_whether_to_defrag`] triggers a defrag GC if the reusable
+    /// block list is not exhausted when the GC starts.  Skipped blocks keep the list non-empty, so
+    /// a low limit makes defrag GCs more frequent, and those GCs are likely to evacuate the skipped
+    /// blocks because they have many holes.
+    pub max_reusable_block_holes: Option<usize>,
 }
 
 unsafe impl<VM: VMBinding> Sync for ImmixSpace<VM> {}
@@ -123,6 +293,14 @@ impl<VM: VMBinding> SFT for ImmixSpace<VM> {
     fn is_object_pinned(&self, object: ObjectReference) -> bool {
         VM::VMObjectModel::LOCAL_PINNING_BIT_SPEC.is_object_pinned::<VM>(object)
     }
+    #[cfg(feature = "object_pinning")]
+    fn pin_block(&self, object: ObjectReference) -> bool {
+        ImmixSpace::pin_block(self, Block::containing(object))
+    }
+    #[cfg(feature = "object_pinning")]
+    fn unpin_block(&self, object: ObjectReference) -> bool {
+        ImmixSpace::unpin_block(self, Block::containing(object))
+    }
     fn is_movable(&self) -> bool {
         !self.space_args.never_move_objects
     }
@@ -131,9 +309,19 @@ impl<VM: VMBinding> SFT for ImmixSpace<VM> {
     fn is_sane(&self) -> bool {
         true
     }
-    fn initialize_object_metadata(&self, _object: ObjectReference, _alloc: bool) {
+    fn initialize_object_metadata(&self, object: ObjectReference, _alloc: bool) {
+        if !VM::VMObjectModel::LOCAL_MARK_BIT_SPEC.is_on_side() {
+            // With cyclic mark bits, new objects get the current mark state so that they are
+            // unmarked in the next GC, which flips the mark state.  See `ImmixSpace::prepare`.
+            VM::VMObjectModel::LOCAL_MARK_BIT_SPEC.store_atomic::<VM, u8>(
+                object,
+                self.mark_state,
+                None,
+                Ordering::SeqCst,
+            );
+        }
         #[cfg(feature = "vo_bit")]
-        crate::util::metadata::vo_bit::set_vo_bit(_object);
+        crate::util::metadata::vo_bit::set_vo_bit(object);
     }
     #[cfg(feature = "is_mmtk_object")]
     fn is_mmtk_object(&self, addr: Address) -> Option<ObjectReference> {
@@ -221,12 +409,17 @@ impl<VM: VMBinding> crate::policy::gc_work::PolicyTraceObject<VM> for ImmixSpace
         } else if KIND == TRACE_KIND_FAST {
             self.trace_object_without_moving(queue, object)
         } else {
-            unreachable!()
+            panic!(
+                "Unsupported trace kind {} for tracing object {} in space {}",
+                KIND,
+                object,
+                self.get_name()
+            )
         }
     }
 
     fn post_scan_object(&self, object: ObjectReference) {
-        if super::MARK_LINE_AT_SCAN_TIME && !super::BLOCK_ONLY {
+        if self.space_args.mark_line_at_scan_time && !super::BLOCK_ONLY {
             debug_assert!(self.in_space(object));
             self.mark_lines(object);
         }
@@ -238,7 +431,7 @@ impl<VM: VMBinding> crate::policy::gc_work::PolicyTraceObject<VM> for ImmixSpace
         } else if KIND == TRACE_KIND_FAST || KIND == TRACE_KIND_TRANSITIVE_PIN {
             false
         } else {
-            unreachable!()
+            panic!("Unsupported trace kind {} for ImmixSpace", KIND)
         }
     }
 }
@@ -248,12 +441,18 @@ impl<VM: VMBinding> ImmixSpace<VM> {
     const UNMARKED_STATE: u8 = 0;
     const MARKED_STATE: u8 = 1;
 
+    /// The maximum number of blocks [`ImmixSpace::get_reusable_block_for_size`] examines before
+    /// giving up.
+    const MAX_BLOCKS_TO_EXAMINE: usize = 16;
+
     /// Get side metadata specs
     fn side_metadata_specs() -> Vec<SideMetadataSpec> {
         metadata::extract_side_metadata(&if super::BLOCK_ONLY {
             vec![
                 MetadataSpec::OnSide(Block::DEFRAG_STATE_TABLE),
                 MetadataSpec::OnSide(Block::MARK_TABLE),
+                #[cfg(feature = "object_pinning")]
+                MetadataSpec::OnSide(Block::PIN_TABLE),
                 *VM::VMObjectModel::LOCAL_MARK_BIT_SPEC,
                 *VM::VMObjectModel::LOCAL_FORWARDING_BITS_SPEC,
                 *VM::VMObjectModel::LOCAL_FORWARDING_POINTER_SPEC,
@@ -265,6 +464,9 @@ impl<VM: VMBinding> ImmixSpace<VM> {
                 MetadataSpec::OnSide(Line::MARK_TABLE),
                 MetadataSpec::OnSide(Block::DEFRAG_STATE_TABLE),
                 MetadataSpec::OnSide(Block::MARK_TABLE),
+                MetadataSpec::OnSide(Block::MAX_HOLE_TABLE),
+                #[cfg(feature = "object_pinning")]
+                MetadataSpec::OnSide(Block::PIN_TABLE),
                 *VM::VMObjectModel::LOCAL_MARK_BIT_SPEC,
                 *VM::VMObjectModel::LOCAL_FORWARDING_BITS_SPEC,
                 *VM::VMObjectModel::LOCAL_FORWARDING_POINTER_SPEC,
@@ -311,6 +513,16 @@ impl<VM: VMBinding> ImmixSpace<VM> {
         vo_bit::helper::validate_config::<VM>();
         let vm_map = args.vm_map;
         let scheduler = args.scheduler.clone();
+        let prepare_skipped_chunks = args.stats.new_event_counter(
+            &format!("{}.prepare_skipped_chunks", args.name),
+            true,
+            true,
+        );
+        let defrag_source_blocks = args.stats.new_event_counter(
+            &format!("{}.defrag_source_blocks", args.name),
+            true,
+            true,
+        );
         let common =
             CommonSpace::new(args.into_policy_args(true, false, Self::side_metadata_specs()));
         let space_index = common.descriptor.get_index();
@@ -336,12 +548,59 @@ impl<VM: VMBinding> ImmixSpace<VM> {
             line_unavail_state: AtomicU8::new(Line::RESET_MARK_STATE),
             lines_consumed: AtomicUsize::new(0),
             reusable_blocks: ReusableBlockPool::new(scheduler.num_workers()),
-            defrag: Defrag::default(),
+            defrag: Defrag::new(space_args.shared_defrag_budget.clone()),
             // Set to the correct mark state when inititialized. We cannot rely on prepare to set it (prepare may get skipped in nursery GCs).
             mark_state: Self::MARKED_STATE,
             scheduler: scheduler.clone(),
             space_args,
+            prepare_skipped_chunks,
+            defrag_source_blocks,
+            copy_budget_pages: [AtomicUsize::new(usize::MAX), AtomicUsize::new(usize::MAX)],
+            copy_budget_exhausted: Default::default(),
+            #[cfg(feature = "sanity")]
+            defrag_objects_marked_in_place: Mutex::new(std::collections::HashSet::new()),
+            copying_disabled: std::sync::atomic::AtomicBool::new(false),
+            in_nursery_gc: false,
+            line_mark_state_wraps: AtomicUsize::new(0),
+            #[cfg(debug_assertions)]
+            stale_line_marks_warned: std::sync::atomic::AtomicBool::new(false),
+            empty_chunk_ages: Mutex::new(HashMap::new()),
+            blocks_to_evacuate: Mutex::new(std::collections::HashSet::new()),
+            forced_defrag_sources: std::collections::HashSet::new(),
+        }
+    }
+
+    /// The number of consecutive GCs a chunk must be found empty before it is returned to the
+    /// operating system.  See `ImmixSpaceArgs::return_empty_chunks`.
+    const RETURN_EMPTY_CHUNKS_AFTER_GCS: usize = 2;
+
+    /// Return the memory of the chunks that have been empty at the end of the last
+    /// `RETURN_EMPTY_CHUNKS_AFTER_GCS` GCs to the operating system.  This must be called after all
+    /// the chunks are swept, and before any block is allocated.  Only the free chunks owned by this
+    /// space are considered (see `ChunkMap::chunks_in_range`), and each chunk is returned once
+    /// until it is allocated again.
+    ///
+    /// The free blocks of returned chunks may still be cached in the page resource.  That is fine
+    /// because the memory stays mapped.  It is zeroed when it is faulted in again.
+    fn return_empty_chunks(&self) {
+        let mut empty_chunk_ages = self.empty_chunk_ages.lock().unwrap();
+        let mut returned_chunks = 0;
+        for (chunk, allocated) in self.chunk_map.chunks_in_range() {
+            if allocated {
+                empty_chunk_ages.remove(&chunk.start());
+                continue;
+            }
+            let age = empty_chunk_ages.entry(chunk.start()).or_insert(0);
+            *age += 1;
+            // The chunk was returned in an earlier GC if its age is larger.
+            if *age == Self::RETURN_EMPTY_CHUNKS_AFTER_GCS {
+                match crate::util::memory::madvise_dontneed(chunk.start(), Chunk::BYTES) {
+                    Ok(()) => returned_chunks += 1,
+                    Err(e) => warn!("Failed to return chunk {:?} to the OS: {}", chunk, e),
+                }
+            }
         }
+        debug!("Returned {} empty chunks to the OS", returned_chunks);
     }
 
     /// Flush the thread-local queues in BlockPageResource
@@ -356,11 +615,50 @@ impl<VM: VMBinding> ImmixSpace<VM> {
         self.defrag.defrag_headroom_pages(self)
     }
 
+    /// Get a snapshot of the chunks of this space as a list of `(start, allocated, blocks)`, where
+    /// `start` is the start address of a chunk, `allocated` is whether the chunk is currently
+    /// allocated to this space, and `blocks` is the number of allocated blocks in the chunk.  The
+    /// chunks range from the lowest to the highest chunk this space has ever allocated, so free
+    /// chunks of this space in between are included, but chunks of other spaces are not.  This can be used to render a heap map, or to find sparse
+    /// chunks.  It should be called between GCs.
+    pub fn chunk_occupancy(&self) -> Vec<(Address, bool, usize)> {
+        self.chunk_map
+            .chunks_in_range()
+            .map(|(chunk, allocated)| {
+                let blocks = if allocated {
+                    chunk
+                        .iter_region::<Block>()
+                        .filter(|block| block.get_state() != BlockState::Unallocated)
+                        .count()
+                } else {
+                    0
+                };
+                (chunk.start(), allocated, blocks)
+            })
+            .collect()
+    }
+
+    /// Get the block that contains `object`.  The object must be in this space.
+    pub fn block_of(&self, object: ObjectReference) -> Block {
+        debug_assert!(self.in_space(object));
+        Block::containing(object)
+    }
+
     /// Check if current GC is a defrag GC.
     pub fn in_defrag(&self) -> bool {
         self.defrag.in_defrag()
     }
 
+    /// Get the defrag threshold used in the current GC, or `None` if the current GC is not a defrag
+    /// GC.  A block with more holes than the threshold is selected as a defrag source (unless
+    /// [`ImmixSpaceArgs::is_defrag_source`] is set).  The threshold is established when the space
+    /// is prepared, so this should be called after the `Prepare` stage, e.g. at the end of the GC,
+    /// to correlate the threshold with the number of evacuated blocks.
+    pub fn current_defrag_threshold(&self) -> Option<usize> {
+        self.in_defrag()
+            .then(|| self.defrag.defrag_spill_threshold.load(Ordering::Acquire))
+    }
+
     /// check if the current GC should do defragmentation.
     pub fn decide_whether_to_defrag(
         &self,
@@ -388,13 +686,42 @@ impl<VM: VMBinding> ImmixSpace<VM> {
     }
 
     pub fn prepare(&mut self, major_gc: bool, plan_stats: StatsForDefrag) {
+        self.defrag.reset_clean_block_counts();
+        self.in_nursery_gc = !major_gc;
+
+        // Reset the copy budgets.  Nursery GCs of StickyImmix use the promotion budget, so this is
+        // done in every GC.
+        let copy_headroom_pages = plan_stats.copy_headroom_pages();
+        self.reset_copy_budget(
+            CopyPurpose::Promotion,
+            *self.common.options.immix_promotion_copy_budget,
+            copy_headroom_pages,
+        );
+        self.reset_copy_budget(
+            CopyPurpose::Defrag,
+            *self.common.options.immix_defrag_copy_budget,
+            copy_headroom_pages,
+        );
+
         if major_gc {
+            // Before the line mark state wraps around, make sure no stale line mark values remain.
+            #[cfg(debug_assertions)]
+            if !super::BLOCK_ONLY
+                && self.line_mark_state.load(Ordering::Acquire) == Line::MAX_MARK_STATE
+            {
+                self.verify_line_marks_before_wrap();
+            }
+
             // Update mark_state
             if VM::VMObjectModel::LOCAL_MARK_BIT_SPEC.is_on_side() {
+                // Side mark bits are cleared in bulk by `PrepareBlockState`.
                 self.mark_state = Self::MARKED_STATE;
             } else {
-                // For header metadata, we use cyclic mark bits.
-                unimplemented!("cyclic mark bits is not supported at the moment");
+                // For header metadata, we use cyclic mark bits, as we cannot clear the mark bits
+                // of all objects efficiently.  Flip the mark state.  The objects marked in the
+                // last GC, and the objects allocated since then (which are given the mark state
+                // of the last GC in `initialize_object_metadata`), all become unmarked.
+                self.mark_state = Self::MARKED_STATE - self.mark_state;
             }
 
             if self.common.needs_log_bit {
@@ -405,9 +732,21 @@ impl<VM: VMBinding> ImmixSpace<VM> {
                 }
             }
 
+            #[cfg(feature = "sanity")]
+            self.defrag_objects_marked_in_place.lock().unwrap().clear();
+
             // Prepare defrag info
             if self.is_defrag_enabled() {
                 self.defrag.prepare(self, plan_stats);
+                let max_defrag_blocks = *self.common.options.immix_max_defrag_blocks_per_gc;
+                if self.in_defrag() && max_defrag_blocks != usize::MAX {
+                    self.defrag
+                        .limit_defrag_source_blocks(self, max_defrag_blocks);
+                }
+                if self.in_defrag() {
+                    self.forced_defrag_sources =
+                        std::mem::take(self.blocks_to_evacuate.get_mut().unwrap());
+                }
             }
 
             // Prepare each block for GC
@@ -432,10 +771,18 @@ impl<VM: VMBinding> ImmixSpace<VM> {
                 if self.line_mark_state.load(Ordering::Acquire) > Line::MAX_MARK_STATE {
                     self.line_mark_state
                         .store(Line::RESET_MARK_STATE, Ordering::Release);
+                    self.line_mark_state_wraps.fetch_add(1, Ordering::Relaxed);
                 }
             }
         }
 
+        if *self.common.options.immix_prewarm_copy_allocators && self.may_copy_in_current_gc() {
+            self.scheduler().work_buckets[WorkBucketStage::Closure]
+                .add_prioritized(Box::new(PrewarmCopyAllocators));
+        }
+
+        // If the VM binding clears VO bits lazily, this is skipped, and the VO bits of dead objects
+        // are cleared when their lines are reused or their blocks are released.
         #[cfg(feature = "vo_bit")]
         if vo_bit::helper::need_to_clear_vo_bits_before_tracing::<VM>() {
             let maybe_scope = if major_gc {
@@ -465,6 +812,10 @@ impl<VM: VMBinding> ImmixSpace<VM> {
             };
 
             if let Some(scope) = maybe_scope {
+                // Unallocated blocks never have VO bits set, because their VO bits were cleared
+                // before the GC that released them, and no objects have been allocated in them
+                // since then.  Chunks without allocated blocks have already been freed in the chunk
+                // map by `SweepChunk`, so they get no packets.
                 let work_packets = self
                     .chunk_map
                     .generate_tasks(|chunk| Box::new(ClearVOBitsAfterPrepare { chunk, scope }));
@@ -476,6 +827,14 @@ impl<VM: VMBinding> ImmixSpace<VM> {
     /// Release for the immix space.
     pub fn release(&mut self, major_gc: bool) {
         if major_gc {
+            self.forced_defrag_sources.clear();
+            if self.is_defrag_enabled() {
+                self.defrag_source_blocks
+                    .lock()
+                    .unwrap()
+                    .inc_by(self.defrag.defrag_source_blocks() as u64);
+            }
+
             // Update line_unavail_state for hole searching after this GC.
             if !super::BLOCK_ONLY {
                 self.line_unavail_state.store(
@@ -488,6 +847,12 @@ impl<VM: VMBinding> ImmixSpace<VM> {
         if !super::BLOCK_ONLY {
             self.reusable_blocks.reset();
         }
+        if self.space_args.skip_sweep {
+            // Leave all the blocks allocated.  No block will be reusable until the next GC.
+            self.defrag.mark_histograms.lock().clear();
+            self.lines_consumed.store(0, Ordering::Relaxed);
+            return;
+        }
         // Sweep chunks and blocks
         let work_packets = self.generate_sweep_tasks();
         self.scheduler().work_buckets[WorkBucketStage::Release].bulk_add(work_packets);
@@ -498,13 +863,94 @@ impl<VM: VMBinding> ImmixSpace<VM> {
     /// This is called when a GC finished.
     /// Return whether this GC was a defrag GC, as a plan may want to know this.
     pub fn end_of_gc(&mut self) -> bool {
+        self.copying_disabled.store(false, Ordering::Relaxed);
         let did_defrag = self.defrag.in_defrag();
         if self.is_defrag_enabled() {
             self.defrag.reset_in_defrag();
         }
+        if did_defrag {
+            // The request has been fulfilled.
+            self.defrag.clear_defrag_request();
+            // The reserved headroom has been left for the binding in this GC.
+            self.defrag.release_headroom();
+            self.common
+                .global_state
+                .last_gc_was_defrag
+                .store(true, Ordering::Relaxed);
+        }
         did_defrag
     }
 
+    /// Reserve `pages` pages of the defrag headroom, so that the next defrag GC leaves them free
+    /// instead of using them for evacuation.  This is useful if the binding is about to allocate a
+    /// very large object right after a GC.  Reservations accumulate, and they are cleared at the end
+    /// of the next defrag GC, or by [`ImmixSpace::release_headroom`].
+    pub fn reserve_headroom(&self, pages: usize) {
+        self.defrag.reserve_headroom(pages);
+    }
+
+    /// Release the defrag headroom reserved with [`ImmixSpace::reserve_headroom`].
+    pub fn release_headroom(&self) {
+        self.defrag.release_headroom();
+    }
+
+    /// Get the number of clean blocks acquired by mutator allocators and by copy allocators,
+    /// respectively, since this space was last prepared for a GC.  Read before the next GC, the
+    /// copy count covers the blocks used for copying in the last GC, and the mutator count covers
+    /// the blocks used by mutators after the last GC.  This helps understanding the copying
+    /// overhead.  Reusable blocks are not counted.
+    pub fn clean_block_counts(&self) -> (usize, usize) {
+        self.defrag.clean_block_counts()
+    }
+
+    /// Get the number of clean pages that may still be acquired for copying in the current GC before
+    /// the defrag space is exhausted and this space stops evacuating objects.  Plans and bindings
+    /// can poll this during a GC to throttle evacuation.  See
+    /// [`crate::policy::immix::defrag::Defrag::remaining_headroom_pages`].
+    pub fn remaining_defrag_headroom_pages(&self) -> usize {
+        self.defrag.remaining_headroom_pages(self)
+    }
+
+    /// Stop copying objects in this space for the rest of the current GC.  Objects traced after
+    /// this call are marked in place, as if they were pinned, so that the GC can finish without
+    /// acquiring more memory.  Objects that have already been copied stay at their new locations.
+    ///
+    /// This is called when a copy allocator fails to get a clean block because the heap is
+    /// exhausted.  Plans can also call it if they find that copying is no longer viable.  It is
+    /// reset at the end of the GC.
+    pub fn disable_copying_for_rest_of_gc(&self) {
+        if !self.copying_disabled.swap(true, Ordering::Relaxed) {
+            info!(
+                "{}: Copying is disabled for the rest of this GC.",
+                self.get_name()
+            );
+        }
+    }
+
+    /// Return `true` if this space may still copy objects in the current GC, i.e.
+    /// [`ImmixSpace::disable_copying_for_rest_of_gc`] has not been called in this GC.  Note that
+    /// objects may still be left in place for other reasons, such as running out of the defrag
+    /// headroom.
+    pub fn is_copying_viable(&self) -> bool {
+        !self.copying_disabled.load(Ordering::Relaxed)
+    }
+
+    /// Return `true` if objects in this space may be copied within this space in the current GC,
+    /// i.e. this is a defrag GC, or a nursery GC that copies nursery objects.
+    pub(crate) fn may_copy_in_current_gc(&self) -> bool {
+        if self.in_nursery_gc {
+            self.is_nursery_copy_enabled()
+        } else {
+            self.in_defrag()
+        }
+    }
+
+    /// Force the next full-heap GC of this space to be a defrag GC, regardless of the defrag
+    /// heuristics.  This has no effect if defrag is disabled for this space.
+    pub fn request_defrag(&self) {
+        self.defrag.request_defrag();
+    }
+
     /// Generate chunk sweep tasks
     fn generate_sweep_tasks(&self) -> Vec<Box<dyn GCWork<VM>>> {
         self.defrag.mark_histograms.lock().clear();
@@ -513,6 +959,7 @@ impl<VM: VMBinding> ImmixSpace<VM> {
         let epilogue = Arc::new(FlushPageResource {
             space,
             counter: AtomicUsize::new(0),
+            total: AtomicUsize::new(0),
         });
         let tasks = self.chunk_map.generate_tasks(|chunk| {
             Box::new(SweepChunk {
@@ -522,15 +969,287 @@ impl<VM: VMBinding> ImmixSpace<VM> {
             })
         });
         epilogue.counter.store(tasks.len(), Ordering::SeqCst);
+        epilogue.total.store(tasks.len(), Ordering::SeqCst);
+        if tasks.is_empty() && self.space_args.return_empty_chunks {
+            // No chunk is allocated, so there is no epilogue to age the empty chunks.
+            self.return_empty_chunks();
+        }
         tasks
     }
 
+    /// Get statistics of the reusable blocks, i.e. the blocks that have free lines and can be used
+    /// for allocation.  The statistics reflect the result of the last GC sweep, minus the blocks that
+    /// have been taken by allocators since then.
+    pub fn reusable_block_stats(&self) -> ReusableBlockStats {
+        self.reusable_blocks.stats()
+    }
+
+    /// Call `f` for each live object in this space, i.e. each object that has its VO bit set and is
+    /// marked with the current mark state.  This uses VO bits to find the objects in the allocated
+    /// blocks, so it requires the `vo_bit` feature.
+    ///
+    /// This must only be called when the world is stopped and the current GC has finished marking,
+    /// e.g. at the end of a GC before the mutators are resumed, when it can be used for producing
+    /// heap dumps.  Objects allocated after the last GC are not marked, and will not be visited.
+    #[cfg(feature = "vo_bit")]
+    pub fn for_each_live_object(&self, mut f: impl FnMut(ObjectReference)) {
+        use crate::util::object_enum::BlockMayHaveObjects;
+        for chunk in self.chunk_map.all_chunks() {
+            for block in chunk.iter_region::<Block>() {
+                if !block.may_have_objects() {
+                    continue;
+                }
+                vo_bit::VO_BIT_SIDE_METADATA_SPEC.scan_non_zero_values::<u8>(
+                    block.start(),
+                    block.end(),
+                    &mut |address| {
+                        let object = vo_bit::get_object_ref_for_vo_addr(address);
+                        if self.is_marked(object) {
+                            f(object);
+                        }
+                    },
+                );
+            }
+        }
+    }
+
+    /// Return the live objects in this space whose current size is larger than `bytes`.  This can
+    /// be used for finding objects that should arguably be allocated in a large object space.  The
+    /// objects are found with [`ImmixSpace::for_each_live_object`], and the same restrictions apply:
+    /// it must only be called when the world is stopped and the current GC has finished marking.
+    #[cfg(feature = "vo_bit")]
+    pub fn objects_larger_than(&self, bytes: usize) -> Vec<ObjectReference> {
+        let mut objects = vec![];
+        self.for_each_live_object(|object| {
+            if VM::VMObjectModel::get_current_size(object) > bytes {
+                objects.push(object);
+            }
+        });
+        objects
+    }
+
+    /// Return the live objects in this space that have their pinning bits set.  This helps
+    /// debugging pinning leaks, i.e. objects that the VM forgot to unpin.  Objects in pinned blocks
+    /// are not included unless they are pinned themselves.  The objects are found with
+    /// [`ImmixSpace::for_each_live_object`], and the same restrictions apply: it must only be called
+    /// when the world is stopped and the current GC has finished marking.
+    #[cfg(all(feature = "vo_bit", feature = "object_pinning"))]
+    pub fn pinned_objects(&self) -> Vec<ObjectReference> {
+        let mut objects = vec![];
+        self.for_each_live_object(|object| {
+            if self.is_object_pinned(object) {
+                objects.push(object);
+            }
+        });
+        objects
+    }
+
+    /// Return true if the address is in this space.  Unlike [`Space::in_space`], this works on
+    /// arbitrary addresses, such as potential pointers found by conservative stack scanning.  For
+    /// contiguous spaces, this is a range test against the extent of the space.  For discontiguous
+    /// spaces, this checks whether the chunk containing the address is allocated to this space.
+    #[inline(always)]
+    pub fn in_space_fast(&self, addr: Address) -> bool {
+        let common = self.common();
+        if crate::util::rust_util::likely(common.contiguous) {
+            addr >= common.start && addr < common.start + common.extent
+        } else {
+            self.in_allocated_chunks(addr)
+        }
+    }
+
+    /// Return true if the address is in a chunk allocated to this space.
+    #[inline(never)]
+    fn in_allocated_chunks(&self, addr: Address) -> bool {
+        let layout = crate::util::heap::layout::vm_layout::vm_layout();
+        if addr < layout.heap_start || addr >= layout.heap_end {
+            return false;
+        }
+        // The chunk map is not mapped for chunks that have never been allocated.
+        if !ChunkMap::ALLOC_TABLE.is_mapped(addr) {
+            return false;
+        }
+        self.chunk_map
+            .get(Chunk::from_unaligned_address(addr))
+            .is_some()
+    }
+
+    /// Get the fraction of lines that are free but cannot be used for bump-pointer allocation in
+    /// fresh blocks, i.e. the free lines in reusable blocks over all the lines in reusable and marked
+    /// blocks.  The free lines of a reusable block are the lines not marked in the last GC, as
+    /// recorded in the block state when the block was swept.  Blocks that have been taken by
+    /// allocators since the last GC are not counted.  This iterates the chunk map once, and is much
+    /// cheaper than computing defrag statistics.  It returns 0 if there are no such blocks.
+    pub fn fragmentation_ratio(&self) -> f64 {
+        let mut total_lines = 0usize;
+        let mut free_lines = 0usize;
+        for chunk in self.chunk_map.all_chunks() {
+            for block in chunk.iter_region::<Block>() {
+                match block.get_state() {
+                    BlockState::Reusable { unavailable_lines } => {
+                        total_lines += Block::LINES;
+                        free_lines += Block::LINES - unavailable_lines as usize;
+                    }
+                    BlockState::Marked => total_lines += Block::LINES,
+                    _ => {}
+                }
+            }
+        }
+        if total_lines == 0 {
+            0.0
+        } else {
+            free_lines as f64 / total_lines as f64
+        }
+    }
+
+    /// Get the current mark states of this space as a tuple of `(mark_state, line_mark_state,
+    /// line_unavail_state)`.  `mark_state` is the value of the object mark bit for live objects,
+    /// `line_mark_state` is the line mark value for lines marked in the current (or last) GC, and
+    /// `line_unavail_state` is the line mark value of the lines marked in the GC before that.  The
+    /// line mark states are loaded with `Acquire` ordering.
+    pub fn mark_state_snapshot(&self) -> (u8, u8, u8) {
+        (
+            self.mark_state,
+            self.line_mark_state.load(Ordering::Acquire),
+            self.line_unavail_state.load(Ordering::Acquire),
+        )
+    }
+
+    /// Get the number of times the line mark state wrapped around since the space was created.
+    /// Together with the `line_mark_state` in [`ImmixSpace::mark_state_snapshot`], this helps
+    /// diagnose bugs related to recycling line mark values in long-running processes.
+    pub fn line_mark_state_wraps(&self) -> usize {
+        self.line_mark_state_wraps.load(Ordering::Relaxed)
+    }
+
+    /// Check that all the line mark values are either 0 or the current line mark state, which must
+    /// be `Line::MAX_MARK_STATE`.  This is called before the line mark state wraps around, after
+    /// which the values from earlier GCs will be reused.  Sweeping in the last GCs before wrapping
+    /// around clears unmarked lines (see `Block::sweep`), so any other value comes from a block that
+    /// has not been swept since, and its stale line marks may be mistaken as marks of a future GC.
+    ///
+    /// Blocks released before the last sweep and reallocated as clean blocks since then keep their
+    /// old line marks, so this reports the stale lines with a warning instead of panicking.  The
+    /// warning is only given once, and the check is skipped after that.
+    #[cfg(debug_assertions)]
+    fn verify_line_marks_before_wrap(&self) {
+        debug_assert_eq!(
+            self.line_mark_state.load(Ordering::Acquire),
+            Line::MAX_MARK_STATE
+        );
+        if self.stale_line_marks_warned.load(Ordering::Relaxed) {
+            return;
+        }
+        let mut stale_lines = 0;
+        let mut stale_blocks = 0;
+        for chunk in self.chunk_map.all_chunks() {
+            for block in chunk.iter_region::<Block>() {
+                if block.get_state() == BlockState::Unallocated {
+                    continue;
+                }
+                let mark_table = block.line_mark_table();
+                let lines = (0..mark_table.len())
+                    .filter(|i| {
+                        let mark = mark_table.get(*i);
+                        mark != 0 && mark != Line::MAX_MARK_STATE
+                    })
+                    .count();
+                if lines != 0 {
+                    stale_lines += lines;
+                    stale_blocks += 1;
+                }
+            }
+        }
+        if stale_lines != 0 && !self.stale_line_marks_warned.swap(true, Ordering::Relaxed) {
+            warn!(
+                "{}: {} stale line marks found in {} blocks before the line mark state wraps \
+                 around.  Further stale line marks will not be reported.",
+                self.get_name(),
+                stale_lines,
+                stale_blocks,
+            );
+        }
+    }
+
+    /// Set the copy budget of `purpose` to `fraction` of `headroom_pages`.  A fraction of 1.0 means
+    /// the purpose is not limited beyond the space itself.
+    fn reset_copy_budget(&self, purpose: CopyPurpose, fraction: f64, headroom_pages: usize) {
+        let pages = if fraction >= 1.0 {
+            usize::MAX
+        } else {
+            (headroom_pages as f64 * fraction) as usize
+        };
+        self.copy_budget_pages[purpose as usize].store(pages, Ordering::Relaxed);
+        self.copy_budget_exhausted[purpose as usize].store(false, Ordering::Relaxed);
+    }
+
+    /// Charge one clean block to the copy budget of `purpose`.  Return false if the budget is
+    /// already used up, in which case nothing is charged.  Once the budget cannot pay for another
+    /// block, the purpose is marked as exhausted (see [`ImmixSpace::is_copy_budget_exhausted`]).
+    pub(crate) fn try_consume_copy_budget(&self, purpose: CopyPurpose) -> bool {
+        let result = self.copy_budget_pages[purpose as usize].fetch_update(
+            Ordering::SeqCst,
+            Ordering::SeqCst,
+            |pages| pages.checked_sub(Block::PAGES),
+        );
+        let remaining = result.map_or(0, |pages| pages - Block::PAGES);
+        if remaining < Block::PAGES {
+            self.copy_budget_exhausted[purpose as usize].store(true, Ordering::Relaxed);
+        }
+        result.is_ok()
+    }
+
+    /// Give back a block charged by [`ImmixSpace::try_consume_copy_budget`] if the block could
+    /// not be acquired.
+    pub(crate) fn refund_copy_budget(&self, purpose: CopyPurpose) {
+        let _ = self.copy_budget_pages[purpose as usize].fetch_update(
+            Ordering::SeqCst,
+            Ordering::SeqCst,
+            |pages| Some(pages.saturating_add(Block::PAGES)),
+        );
+    }
+
+    /// Return `true` if the copy budget of `purpose` is used up in this GC.  Objects are no longer
+    /// copied opportunistically for the purpose, but marked in place.
+    pub(crate) fn is_copy_budget_exhausted(&self, purpose: CopyPurpose) -> bool {
+        self.copy_budget_exhausted[purpose as usize].load(Ordering::Relaxed)
+    }
+
     /// Release a block.
     pub fn release_block(&self, block: Block) {
         block.deinit();
         self.pr.release_block(block);
     }
 
+    /// Fault in up to `pages` pages of this space ahead of time, so that later allocation does not
+    /// pay for page faults. Returns the number of pages that were pretouched.
+    ///
+    /// Blocks are acquired from the page resource, zeroed, and immediately released back to the
+    /// page resource, so they stay on its free block list and are handed out by the next calls to
+    /// [`ImmixSpace::get_clean_block`]. Pretouching stops early rather than triggering a GC once the
+    /// heap budget would be exceeded. As the blocks are never given to an allocator, they do not
+    /// count towards `lines_consumed`, and they never enter `reusable_blocks`, so pretouching does
+    /// not change the `reusable_blocks.len() == 0` condition used by
+    /// [`ImmixSpace::decide_whether_to_defrag`].
+    pub fn pretouch(&self, tls: VMThread, pages: usize) -> usize {
+        let reserved_per_block = Block::PAGES + self.estimate_side_meta_pages(Block::PAGES);
+        let mut blocks = vec![];
+        for _ in 0..pages.div_ceil(Block::PAGES) {
+            if !self.get_gc_trigger().has_headroom_for(reserved_per_block) {
+                break;
+            }
+            let block_address = self.acquire(tls, Block::PAGES);
+            if block_address.is_zero() {
+                break;
+            }
+            crate::util::memory::zero(block_address, Block::BYTES);
+            blocks.push(Block::from_aligned_address(block_address));
+        }
+        // This is called by a mutator, so the blocks cannot be pushed to the queues of GC workers.
+        self.pr.release_blocks_global(&blocks);
+        blocks.len() * Block::PAGES
+    }
+
     /// Allocate a clean block.
     pub fn get_clean_block(&self, tls: VMThread, copy: bool) -> Option<Block> {
         let block_address = self.acquire(tls, Block::PAGES);
@@ -547,28 +1266,24 @@ impl<VM: VMBinding> ImmixSpace<VM> {
     }
 
     /// Pop a reusable block from the reusable block list.
+    ///
+    /// The free lines of the block still contain the data of dead objects.  The allocator zeroes
+    /// each hole found by [`ImmixSpace::get_next_available_lines`] before allocating into it, so
+    /// stale data is never handed out to the VM.
     pub fn get_reusable_block(&self, copy: bool) -> Option<Block> {
         if super::BLOCK_ONLY {
             return None;
         }
+        if let Some(max_holes) = self.space_args.max_reusable_block_holes {
+            return self.get_reusable_block_with_max_holes(copy, max_holes);
+        }
         loop {
-            if let Some(block) = self.reusable_blocks.pop() {
+            if let Some(block) = self.pop_reusable_block() {
                 // Skip blocks that should be evacuated.
                 if copy && block.is_defrag_source() {
                     continue;
                 }
-
-                // Get available lines. Do this before block.init which will reset block state.
-                let lines_delta = match block.get_state() {
-                    BlockState::Reusable { unavailable_lines } => {
-                        Block::LINES - unavailable_lines as usize
-                    }
-                    BlockState::Unmarked => Block::LINES,
-                    _ => unreachable!("{:?} {:?}", block, block.get_state()),
-                };
-                self.lines_consumed.fetch_add(lines_delta, Ordering::SeqCst);
-
-                block.init(copy);
+                self.init_reusable_block(block, copy);
                 return Some(block);
             } else {
                 return None;
@@ -576,19 +1291,190 @@ impl<VM: VMBinding> ImmixSpace<VM> {
         }
     }
 
+    /// Get a reusable block that has at most `max_holes` holes.  See
+    /// [`ImmixSpaceArgs::max_reusable_block_holes`].
+    fn get_reusable_block_with_max_holes(&self, copy: bool, max_holes: usize) -> Option<Block> {
+        let mut rejected = vec![];
+        let mut result = None;
+        while rejected.len() < Self::MAX_BLOCKS_TO_EXAMINE {
+            let Some(block) = self.pop_reusable_block() else {
+                break;
+            };
+            // Skip blocks that should be evacuated.
+            if copy && block.is_defrag_source() {
+                continue;
+            }
+            if Self::has_few_enough_holes(block, max_holes) {
+                result = Some(block);
+                break;
+            }
+            rejected.push(block);
+        }
+        if !rejected.is_empty() {
+            self.reusable_blocks.push_global(&rejected);
+        }
+        if let Some(block) = result {
+            self.init_reusable_block(block, copy);
+        }
+        result
+    }
+
+    /// Return true if `block`, popped from `reusable_blocks`, has at most `max_holes` holes.
+    fn has_few_enough_holes(block: Block, max_holes: usize) -> bool {
+        match block.get_state() {
+            BlockState::Unmarked => true,
+            _ => block.get_holes() <= max_holes,
+        }
+    }
+
+    /// Remove all the blocks from the reusable block list, so that the following allocations (and
+    /// copying) in this space come from clean blocks until the next GC sweeps the space.  Returns
+    /// the number of blocks removed.
+    ///
+    /// The removed blocks still hold live objects, so they are kept in the space and treated as
+    /// fully occupied.  Their free lines are not counted in `lines_consumed`, so the page
+    /// accounting of this space under-reports the used memory until the next GC.  This is only for
+    /// making tests deterministic, and must not be used in production.
+    #[cfg(feature = "test_private")]
+    pub fn clear_reusable_blocks_for_test(&self) -> usize {
+        self.reusable_blocks.flush_all();
+        let mut cleared = 0;
+        while let Some(block) = self.reusable_blocks.pop() {
+            block.set_state(BlockState::Unmarked);
+            cleared += 1;
+        }
+        cleared
+    }
+
+    /// Get a reusable block that has a hole of at least `bytes` bytes.  Blocks are first filtered
+    /// by the largest hole recorded when the block was swept, and the hole is then located with
+    /// [`ImmixSpace::find_hole`] before the block is taken.  This requires the option
+    /// `immix_track_max_hole`.  At most [`ImmixSpace::MAX_BLOCKS_TO_EXAMINE`] blocks are examined.
+    /// Blocks that are too fragmented are appended to `rejected` instead of being returned to the
+    /// pool, where they would be examined again by the next request.  The caller should use them
+    /// for small objects (see [`ImmixSpace::init_reusable_block`]).  Blocks that have more holes
+    /// than [`ImmixSpaceArgs::max_reusable_block_holes`] are not used for small objects, so they are
+    /// still returned to the pool.
+    pub fn get_reusable_block_for_size(
+        &self,
+        copy: bool,
+        bytes: usize,
+        rejected: &mut Vec<Block>,
+    ) -> Option<Block> {
+        if super::BLOCK_ONLY {
+            return None;
+        }
+        debug_assert!(self.is_max_hole_tracking_enabled());
+        let lines_needed = (bytes + Line::BYTES - 1) >> Line::LOG_BYTES;
+        let mut result = None;
+        for _ in 0..Self::MAX_BLOCKS_TO_EXAMINE {
+            let Some(block) = self.pop_reusable_block() else {
+                break;
+            };
+            // Skip blocks that should be evacuated.
+            if copy && block.is_defrag_source() {
+                continue;
+            }
+            let fits = match block.get_state() {
+                BlockState::Unmarked => true,
+                _ => block.get_max_hole_lines() >= lines_needed,
+            };
+            if fits && self.find_hole(block, bytes).is_some() {
+                result = Some(block);
+                break;
+            }
+            match self.space_args.max_reusable_block_holes {
+                Some(max_holes) if !Self::has_few_enough_holes(block, max_holes) => {
+                    self.reusable_blocks.push_global(&[block])
+                }
+                _ => rejected.push(block),
+            }
+        }
+        if let Some(block) = result {
+            self.init_reusable_block(block, copy);
+        }
+        result
+    }
+
+    /// Find the first hole of at least `bytes` bytes in `block` according to its line marks, and
+    /// return its start and end lines.
+    pub fn find_hole(&self, block: Block, bytes: usize) -> Option<(Line, Line)> {
+        let mut line = block.start_line();
+        while let Some((start_line, end_line)) = self.get_next_available_lines(line) {
+            if end_line.start() - start_line.start() >= bytes {
+                return Some((start_line, end_line));
+            }
+            if end_line == block.end_line() {
+                break;
+            }
+            line = end_line;
+        }
+        None
+    }
+
+    /// Pop a block from `reusable_blocks`.  With lazy sweeping, a block is swept the first time it
+    /// is popped after a GC, and the blocks found to have no free lines are dropped.
+    fn pop_reusable_block(&self) -> Option<Block> {
+        loop {
+            let block = self.reusable_blocks.pop()?;
+            if self.is_lazy_sweep_enabled()
+                && block.get_state() == BlockState::Marked
+                && !block.sweep_lazily(self)
+            {
+                continue;
+            }
+            return Some(block);
+        }
+    }
+
+    /// Prepare a block popped from `reusable_blocks` for allocation.
+    pub(crate) fn init_reusable_block(&self, block: Block, copy: bool) {
+        // Get available lines. Do this before block.init which will reset block state.
+        let lines_delta = match block.get_state() {
+            BlockState::Reusable { unavailable_lines } => Block::LINES - unavailable_lines as usize,
+            BlockState::Unmarked => Block::LINES,
+            _ => unreachable!("{:?} {:?}", block, block.get_state()),
+        };
+        self.lines_consumed.fetch_add(lines_delta, Ordering::SeqCst);
+
+        // A block whose lazy sweeping was cancelled by `PrepareBlockState` still has uncleared
+        // free lines.
+        if self.is_lazy_sweep_enabled() && block.get_state() == BlockState::Unmarked {
+            block.clear_free_lines::<VM>(
+                self.line_unavail_state.load(Ordering::Acquire),
+                self.line_mark_state.load(Ordering::Acquire),
+            );
+        }
+
+        block.init(copy);
+    }
+
     /// Trace and mark objects without evacuation.
     pub fn trace_object_without_moving(
         &self,
         queue: &mut impl ObjectQueue,
         object: ObjectReference,
     ) -> ObjectReference {
+        self.trace_object_without_moving_reporting(queue, object).0
+    }
+
+    /// Trace and mark objects without evacuation, like [`ImmixSpace::trace_object_without_moving`].
+    /// In addition to the object reference, this also returns `true` if this trace is the first
+    /// visit of the object in this GC (i.e. the object is newly marked by this call), or `false`
+    /// if the object has already been marked.
+    pub fn trace_object_without_moving_reporting(
+        &self,
+        queue: &mut impl ObjectQueue,
+        object: ObjectReference,
+    ) -> (ObjectReference, bool) {
         #[cfg(feature = "vo_bit")]
         vo_bit::helper::on_trace_object::<VM>(object);
 
-        if self.attempt_mark(object, self.mark_state) {
+        let newly_marked = self.attempt_mark(object, self.mark_state);
+        if newly_marked {
             // Mark block and lines
             if !super::BLOCK_ONLY {
-                if !super::MARK_LINE_AT_SCAN_TIME {
+                if !self.space_args.mark_line_at_scan_time {
                     self.mark_lines(object);
                 }
             } else {
@@ -598,12 +1484,14 @@ impl<VM: VMBinding> ImmixSpace<VM> {
             #[cfg(feature = "vo_bit")]
             vo_bit::helper::on_object_marked::<VM>(object);
 
+            #[cfg(feature = "sanity")]
+            self.record_defrag_object_marked_in_place(object);
+
             // Visit node
             queue.enqueue(object);
             self.unlog_object_if_needed(object);
-            return object;
         }
-        object
+        (object, newly_marked)
     }
 
     /// Trace object and do evacuation if required.
@@ -628,8 +1516,11 @@ impl<VM: VMBinding> ImmixSpace<VM> {
             // until the object has been forwarded by the winner. Note that the object may not
             // necessarily get forwarded since Immix opportunistically moves objects.
             #[allow(clippy::let_and_return)]
-            let new_object =
-                object_forwarding::spin_and_get_forwarded_object::<VM>(object, forwarding_status);
+            let new_object = object_forwarding::spin_and_get_forwarded_object_with_limit::<VM>(
+                object,
+                forwarding_status,
+                *self.common.options.forwarding_spin_limit,
+            );
             #[cfg(debug_assertions)]
             {
                 if new_object == object {
@@ -656,10 +1547,18 @@ impl<VM: VMBinding> ImmixSpace<VM> {
             object
         } else {
             // We won the forwarding race; actually forward and copy the object if it is not pinned
-            // and we have sufficient space in our copy allocator
+            // and we have sufficient space in our copy allocator.  If a copy allocator fails to get
+            // a clean block, it disables copying for the rest of the GC, and we leave objects in
+            // place from then on rather than aborting the GC.
             let new_object = if self.is_pinned(object)
+                || !VM::VMObjectModel::should_move_object(object)
+                || !self.is_copying_viable()
                 || (!nursery_collection && self.defrag.space_exhausted())
-            {
+                || self.is_copy_budget_exhausted(if self.in_defrag() {
+                    CopyPurpose::Defrag
+                } else {
+                    CopyPurpose::Promotion
+                }) {
                 self.attempt_mark(object, self.mark_state);
                 object_forwarding::clear_forwarding_bits::<VM>(object);
                 Block::containing(object).set_state(BlockState::Marked);
@@ -667,7 +1566,10 @@ impl<VM: VMBinding> ImmixSpace<VM> {
                 #[cfg(feature = "vo_bit")]
                 vo_bit::helper::on_object_marked::<VM>(object);
 
-                if !super::MARK_LINE_AT_SCAN_TIME {
+                #[cfg(feature = "sanity")]
+                self.record_defrag_object_marked_in_place(object);
+
+                if !self.space_args.mark_line_at_scan_time {
                     self.mark_lines(object);
                 }
 
@@ -676,7 +1578,7 @@ impl<VM: VMBinding> ImmixSpace<VM> {
                 // We are forwarding objects. When the copy allocator allocates the block, it should
                 // mark the block. So we do not need to explicitly mark it here.
 
-                object_forwarding::forward_object::<VM>(
+                let new_object = object_forwarding::forward_object::<VM>(
                     object,
                     semantics,
                     copy_context,
@@ -684,7 +1586,11 @@ impl<VM: VMBinding> ImmixSpace<VM> {
                         #[cfg(feature = "vo_bit")]
                         vo_bit::helper::on_object_forwarded::<VM>(_new_object);
                     },
-                )
+                );
+                if let Some(on_object_forwarded) = &self.space_args.on_object_forwarded {
+                    on_object_forwarded(object, new_object);
+                }
+                new_object
             };
             debug_assert_eq!(
                 Block::containing(new_object).get_state(),
@@ -698,6 +1604,50 @@ impl<VM: VMBinding> ImmixSpace<VM> {
         }
     }
 
+    /// Record that a live object is deliberately marked in place in this GC, so that
+    /// [`ImmixSpace::verify_defrag_source_block`] accepts it if it is in a defrag source block.
+    #[cfg(feature = "sanity")]
+    fn record_defrag_object_marked_in_place(&self, object: ObjectReference) {
+        if self.defrag.in_defrag() && Block::containing(object).is_defrag_source() {
+            self.defrag_objects_marked_in_place
+                .lock()
+                .unwrap()
+                .insert(object);
+        }
+    }
+
+    /// Check that a defrag source block does not hold any live object that should have been
+    /// evacuated.  This is called when sweeping the block in a defrag GC, before the defrag state of
+    /// the block is overwritten.  A live object may only remain in a defrag source block if it was
+    /// marked in place, e.g. because it is pinned, it is reachable from pinning roots, or the
+    /// binding does not allow it to move, or if the space ran out of copy space in this GC.  Panic
+    /// with the offending object otherwise.
+    #[cfg(feature = "sanity")]
+    pub(super) fn verify_defrag_source_block(&self, block: Block) {
+        if !self.defrag.in_defrag() || !block.is_defrag_source() {
+            return;
+        }
+        if self.defrag.space_exhausted() || !self.is_copying_viable() {
+            return;
+        }
+        // We can only scan side mark bits.  In-header mark bits are not checked.
+        let MetadataSpec::OnSide(mark_bits) = *VM::VMObjectModel::LOCAL_MARK_BIT_SPEC else {
+            return;
+        };
+        let marked_in_place = self.defrag_objects_marked_in_place.lock().unwrap();
+        mark_bits.scan_non_zero_values::<u8>(block.start(), block.end(), &mut |addr| {
+            let object = ObjectReference::from_raw_address(addr).unwrap();
+            if self.is_marked(object) {
+                assert!(
+                    marked_in_place.contains(&object),
+                    "Live object {} in defrag source block {:?} was not evacuated",
+                    object,
+                    block,
+                );
+            }
+        });
+    }
+
     fn unlog_object_if_needed(&self, object: ObjectReference) {
         if self.space_args.unlog_object_when_traced {
             // Make sure the side metadata for the line can fit into one byte. For smaller line size, we should
@@ -728,33 +1678,61 @@ impl<VM: VMBinding> ImmixSpace<VM> {
         Line::mark_lines_for_object::<VM>(object, self.line_mark_state.load(Ordering::Acquire));
     }
 
-    /// Atomically mark an object.
+    /// Atomically mark an object.  Return true if this call marked the object, or false if the
+    /// object was already marked.
+    ///
+    /// This is on the hottest path of tracing, so the common cases are kept cheap.  An object that
+    /// is already marked is detected with a single relaxed load.  Otherwise, a single CAS usually
+    /// succeeds, and we only retry if another bit in the same metadata byte changed concurrently.
+    /// The mark bit is only used to decide which thread marks (and enqueues) the object.  It does
+    /// not publish any other data, so both the loads and the CAS are relaxed.  The object is
+    /// scanned by the thread that marked it, or by a thread that steals it from a work queue, which
+    /// synchronizes on its own.
     fn attempt_mark(&self, object: ObjectReference, mark_state: u8) -> bool {
+        let mut old_value = VM::VMObjectModel::LOCAL_MARK_BIT_SPEC.load_atomic::<VM, u8>(
+            object,
+            None,
+            Ordering::Relaxed,
+        );
         loop {
-            let old_value = VM::VMObjectModel::LOCAL_MARK_BIT_SPEC.load_atomic::<VM, u8>(
-                object,
-                None,
-                Ordering::SeqCst,
-            );
             if old_value == mark_state {
                 return false;
             }
+            match VM::VMObjectModel::LOCAL_MARK_BIT_SPEC.compare_exchange_metadata::<VM, u8>(
+                object,
+                old_value,
+                mark_state,
+                None,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return true,
+                Err(current) => old_value = current,
+            }
+        }
+    }
 
-            if VM::VMObjectModel::LOCAL_MARK_BIT_SPEC
-                .compare_exchange_metadata::<VM, u8>(
+    /// Return the new location of the object if it has been forwarded, the object itself if it has
+    /// been marked in place, or `None` if the object has not been reached in the current GC.  Unlike
+    /// the `trace_object` methods, this never marks or enqueues the object.  If another worker is
+    /// forwarding the object, this waits until the forwarding finishes.  This can be used during
+    /// the closure phase, e.g. when processing weak references.  The result for an object that is
+    /// not reached is only definitive after the transitive closure has finished.
+    pub fn is_live_or_forwarded(&self, object: ObjectReference) -> Option<ObjectReference> {
+        if self.is_movable() {
+            let forwarding_status = object_forwarding::get_forwarding_status::<VM>(object);
+            if object_forwarding::state_is_forwarded_or_being_forwarded(forwarding_status) {
+                let new_object = object_forwarding::spin_and_get_forwarded_object_with_limit::<VM>(
                     object,
-                    old_value,
-                    mark_state,
-                    None,
-                    Ordering::SeqCst,
-                    Ordering::SeqCst,
-                )
-                .is_ok()
-            {
-                break;
+                    forwarding_status,
+                    *self.common.options.forwarding_spin_limit,
+                );
+                if new_object != object {
+                    return Some(new_object);
+                }
             }
         }
-        true
+        self.is_marked(object).then_some(object)
     }
 
     /// Check if an object is marked.
@@ -771,15 +1749,76 @@ impl<VM: VMBinding> ImmixSpace<VM> {
         self.is_marked_with(object, self.mark_state)
     }
 
-    /// Check if an object is pinned.
+    /// Check if an object is pinned, either individually or because its block is pinned.
     fn is_pinned(&self, _object: ObjectReference) -> bool {
         #[cfg(feature = "object_pinning")]
-        return self.is_object_pinned(_object);
+        return self.is_object_pinned(_object) || Block::containing(_object).is_pinned();
 
         #[cfg(not(feature = "object_pinning"))]
         false
     }
 
+    /// Check if a block is pinned.
+    pub(super) fn is_block_pinned(&self, _block: Block) -> bool {
+        #[cfg(feature = "object_pinning")]
+        return _block.is_pinned();
+
+        #[cfg(not(feature = "object_pinning"))]
+        false
+    }
+
+    /// Pin a block so that no object in the block will be moved by the GC until the block is
+    /// unpinned.  This is coarser than pinning individual objects, but much cheaper if many objects
+    /// in the same block need to be pinned.  A block is unpinned automatically when it is released.
+    /// Return true if the block was not pinned before.
+    #[cfg(feature = "object_pinning")]
+    pub fn pin_block(&self, block: Block) -> bool {
+        debug_assert!(self.chunk_map.get(block.chunk()).is_some());
+        block.set_pinned(true)
+    }
+
+    /// Unpin a block pinned by [`ImmixSpace::pin_block`].
+    /// Return true if the block was pinned before.
+    #[cfg(feature = "object_pinning")]
+    pub fn unpin_block(&self, block: Block) -> bool {
+        debug_assert!(self.chunk_map.get(block.chunk()).is_some());
+        block.set_pinned(false)
+    }
+
+    /// Request a block to be evacuated in the next defrag GC.  The block is made a defrag source
+    /// when the blocks are prepared in that GC, and the live objects in it will be moved (if
+    /// possible) when they are traced, as if the block was selected by the defrag heuristics.  This
+    /// also requests the next GC to be a defrag GC (see [`ImmixSpace::request_defrag`]).
+    ///
+    /// This must be called when no GC is in progress, e.g. by a mutator.  Defrag sources are only
+    /// chosen in the `Prepare` stage, and making a block a defrag source while its objects are
+    /// being traced would move some of its objects but not the others.
+    ///
+    /// Return an error if this space never moves objects, if a GC is in progress, if the block is
+    /// not allocated, or if the block is pinned.
+    pub fn evacuate_block(&self, block: Block) -> Result<(), EvacuateBlockError> {
+        if !self.is_defrag_enabled() {
+            return Err(EvacuateBlockError::DefragDisabled);
+        }
+        if *self.common.global_state.gc_status.lock().unwrap() != GcStatus::NotInGC {
+            return Err(EvacuateBlockError::GCInProgress);
+        }
+        if self.chunk_map.get(block.chunk()).is_none()
+            || block.get_state() == BlockState::Unallocated
+        {
+            return Err(EvacuateBlockError::NotAllocated);
+        }
+        if self.is_block_pinned(block) {
+            return Err(EvacuateBlockError::BlockPinned);
+        }
+        self.blocks_to_evacuate
+            .lock()
+            .unwrap()
+            .insert(block.start());
+        self.request_defrag();
+        Ok(())
+    }
+
     /// Hole searching.
     ///
     /// Linearly scan lines in a block to search for the next
@@ -831,6 +1870,14 @@ impl<VM: VMBinding> ImmixSpace<VM> {
         }
     }
 
+    /// Return the number of lines consumed by allocators in this space since the last GC released
+    /// it.  A line is consumed when a clean block or a free line of a reusable block is taken by an
+    /// allocator (including the copy allocators).  Sampled together with timestamps, this gives the
+    /// allocation rate of the space, which a binding can use to schedule GCs proactively.
+    pub fn lines_consumed_since_gc(&self) -> usize {
+        self.lines_consumed.load(Ordering::SeqCst)
+    }
+
     pub(crate) fn get_pages_allocated(&self) -> usize {
         self.lines_consumed.load(Ordering::SeqCst) >> (LOG_BYTES_IN_PAGE - Line::LOG_BYTES as u8)
     }
@@ -845,7 +1892,7 @@ impl<VM: VMBinding> ImmixSpace<VM> {
             Ordering::SeqCst,
         );
         // Mark the line
-        if !super::MARK_LINE_AT_SCAN_TIME {
+        if !self.space_args.mark_line_at_scan_time {
             self.mark_lines(object);
         }
     }
@@ -861,6 +1908,15 @@ impl<VM: VMBinding> ImmixSpace<VM> {
     pub(crate) fn is_defrag_enabled(&self) -> bool {
         !self.space_args.never_move_objects
     }
+
+    pub(crate) fn is_lazy_sweep_enabled(&self) -> bool {
+        !super::BLOCK_ONLY && self.space_args.lazy_sweep
+    }
+
+    /// Return true if the largest hole of each reusable block is recorded when sweeping.
+    pub(crate) fn is_max_hole_tracking_enabled(&self) -> bool {
+        !super::BLOCK_ONLY && *self.common.options.immix_track_max_hole
+    }
 }
 
 /// A work packet to prepare each block for a major GC.
@@ -875,8 +1931,8 @@ pub struct PrepareBlockState<VM: VMBinding> {
 impl<VM: VMBinding> PrepareBlockState<VM> {
     /// Clear object mark table
     fn reset_object_mark(&self) {
-        // NOTE: We reset the mark bits because cyclic mark bit is currently not supported, yet.
-        // See `ImmixSpace::prepare`.
+        // NOTE: Only side mark bits are reset.  In-header mark bits are cyclic.  See
+        // `ImmixSpace::prepare`.
         if let MetadataSpec::OnSide(side) = *VM::VMObjectModel::LOCAL_MARK_BIT_SPEC {
             side.bzero_metadata(self.chunk.start(), Chunk::BYTES);
         }
@@ -885,29 +1941,61 @@ impl<VM: VMBinding> PrepareBlockState<VM> {
 
 impl<VM: VMBinding> GCWork<VM> for PrepareBlockState<VM> {
     fn do_work(&mut self, _worker: &mut GCWorker<VM>, _mmtk: &'static MMTK<VM>) {
+        // Find all allocated blocks in this chunk
+        let allocated_blocks = self
+            .chunk
+            .iter_region::<Block>()
+            .filter(|block| block.get_state() != BlockState::Unallocated)
+            .collect::<Vec<_>>();
+        if allocated_blocks.is_empty() {
+            // All the blocks have been released since the chunk was swept.  Skip clearing the mark
+            // table, as there is nothing to prepare.
+            self.space.prepare_skipped_chunks.lock().unwrap().inc();
+            return;
+        }
         // Clear object mark table for this chunk
         self.reset_object_mark();
-        // Iterate over all blocks in this chunk
-        for block in self.chunk.iter_region::<Block>() {
-            let state = block.get_state();
-            // Skip unallocated blocks.
-            if state == BlockState::Unallocated {
-                continue;
-            }
+        for block in allocated_blocks {
             // Check if this block needs to be defragmented.
             let is_defrag_source = if !self.space.is_defrag_enabled() {
                 // Do not set any block as defrag source if defrag is disabled.
                 false
+            } else if self.space.is_block_pinned(block) {
+                // Objects in pinned blocks must not move.
+                false
             } else if super::DEFRAG_EVERY_BLOCK {
                 // Set every block as defrag source if so desired.
                 true
+            } else if self.defrag_threshold.is_some()
+                && !self.space.forced_defrag_sources.is_empty()
+                && self.space.forced_defrag_sources.contains(&block.start())
+            {
+                // The block was requested to be evacuated by `ImmixSpace::evacuate_block`.
+                true
             } else if let Some(defrag_threshold) = self.defrag_threshold {
                 // This GC is a defrag GC.
-                block.get_holes() > defrag_threshold
+                let holes = block.get_holes();
+                match &self.space.space_args.is_defrag_source {
+                    Some(is_defrag_source) => is_defrag_source(block, holes, defrag_threshold),
+                    None => {
+                        holes > defrag_threshold
+                            || (holes == defrag_threshold
+                                && self.space.defrag.take_boundary_defrag_source_block())
+                    }
+                }
             } else {
                 // Not a defrag GC.
                 false
             };
+            if is_defrag_source {
+                probe!(
+                    mmtk,
+                    defrag_source,
+                    block.start().as_usize(),
+                    block.get_holes()
+                );
+                self.space.defrag.notify_new_defrag_source_block();
+            }
             block.set_as_defrag_source(is_defrag_source);
             // Clear block mark data.
             block.set_state(BlockState::Unmarked);
@@ -917,6 +2005,32 @@ impl<VM: VMBinding> GCWork<VM> for PrepareBlockState<VM> {
     }
 }
 
+/// A work packet that asks each GC worker to acquire a clean block for its Immix copy allocators
+/// ahead of the first copy.  See the option `immix_prewarm_copy_allocators`.
+///
+/// This is added to the `Closure` bucket rather than done when collectors are prepared, because
+/// `PrepareBlockState` packets in the `Prepare` bucket reset the states of all the blocks,
+/// including the blocks that copy allocators may have acquired.
+pub(crate) struct PrewarmCopyAllocators;
+
+impl<VM: VMBinding> GCWork<VM> for PrewarmCopyAllocators {
+    fn do_work(&mut self, _worker: &mut GCWorker<VM>, mmtk: &'static MMTK<VM>) {
+        for w in &mmtk.scheduler.worker_group.workers_shared {
+            let result = w.designated_work.push(Box::new(PrewarmCopyAllocator));
+            debug_assert!(result.is_ok());
+        }
+    }
+}
+
+/// Acquire a clean block for the Immix copy allocators of this GC worker.
+struct PrewarmCopyAllocator;
+
+impl<VM: VMBinding> GCWork<VM> for PrewarmCopyAllocator {
+    fn do_work(&mut self, worker: &mut GCWorker<VM>, _mmtk: &'static MMTK<VM>) {
+        worker.get_copy_context_mut().prewarm_immix_allocators();
+    }
+}
+
 /// Chunk sweeping work packet.
 struct SweepChunk<VM: VMBinding> {
     space: &'static ImmixSpace<VM>,
@@ -974,6 +2088,9 @@ impl<VM: VMBinding> GCWork<VM> for SweepChunk<VM> {
                 // Block is live. Increment the allocated block count.
                 allocated_blocks += 1;
             }
+            if let Some(on_block_swept) = self.space.space_args.on_block_swept.as_ref() {
+                on_block_swept(block, &block.get_state());
+            }
         }
         probe!(mmtk, sweep_chunk, allocated_blocks);
         // Set this chunk as free if there is not live blocks.
@@ -989,15 +2106,25 @@ impl<VM: VMBinding> GCWork<VM> for SweepChunk<VM> {
 struct FlushPageResource<VM: VMBinding> {
     space: &'static ImmixSpace<VM>,
     counter: AtomicUsize,
+    /// The total number of related work packets.  Used for reporting the sweeping progress.
+    total: AtomicUsize,
 }
 
 impl<VM: VMBinding> FlushPageResource<VM> {
     /// Called after a related work packet is finished.
     fn finish_one_work_packet(&self) {
-        if 1 == self.counter.fetch_sub(1, Ordering::SeqCst) {
+        let remaining = self.counter.fetch_sub(1, Ordering::SeqCst);
+        if let Some(on_sweep_progress) = self.space.space_args.on_sweep_progress.as_ref() {
+            let total = self.total.load(Ordering::Relaxed);
+            on_sweep_progress(total - (remaining - 1), total);
+        }
+        if 1 == remaining {
             // We've finished releasing all the dead blocks to the BlockPageResource's thread-local queues.
             // Now flush the BlockPageResource.
-            self.space.flush_page_resource()
+            self.space.flush_page_resource();
+            if self.space.space_args.return_empty_chunks {
+                self.space.return_empty_chunks();
+            }
         }
     }
 }
@@ -1014,8 +2141,13 @@ use crate::util::alloc::ImmixAllocator;
 
 /// Normal immix copy context. It has one copying Immix allocator.
 /// Most immix plans use this copy context.
+///
+/// The allocator charges the clean blocks it acquires to the defrag copy budget in a defrag GC, and
+/// to the promotion copy budget otherwise (see [`CopyPurpose`]).
 pub struct ImmixCopyContext<VM: VMBinding> {
     allocator: ImmixAllocator<VM>,
+    /// The minimum alignment of copied objects.  See the option `immix_copy_min_alignment`.
+    min_alignment: usize,
 }
 
 impl<VM: VMBinding> PolicyCopyContext for ImmixCopyContext<VM> {
@@ -1023,8 +2155,15 @@ impl<VM: VMBinding> PolicyCopyContext for ImmixCopyContext<VM> {
 
     fn prepare(&mut self) {
         self.allocator.reset();
+        let purpose = if self.get_space().in_defrag() {
+            CopyPurpose::Defrag
+        } else {
+            CopyPurpose::Promotion
+        };
+        self.allocator.set_copy_purpose(purpose);
     }
     fn release(&mut self) {
+        self.allocator.release_unused_prewarmed_block();
         self.allocator.reset();
     }
     fn alloc_copy(
@@ -1034,11 +2173,15 @@ impl<VM: VMBinding> PolicyCopyContext for ImmixCopyContext<VM> {
         align: usize,
         offset: usize,
     ) -> Address {
-        self.allocator.alloc(bytes, align, offset)
+        self.allocator
+            .alloc(bytes, align.max(self.min_alignment), offset)
     }
     fn post_copy(&mut self, obj: ObjectReference, bytes: usize) {
         self.get_space().post_copy(obj, bytes)
     }
+    fn take_alloc_stats(&mut self) -> CopyAllocStats {
+        std::mem::take(&mut self.allocator.alloc_stats)
+    }
 }
 
 impl<VM: VMBinding> ImmixCopyContext<VM> {
@@ -1047,8 +2190,23 @@ impl<VM: VMBinding> ImmixCopyContext<VM> {
         context: Arc<AllocatorContext<VM>>,
         space: &'static ImmixSpace<VM>,
     ) -> Self {
+        let min_alignment = *context.options.immix_copy_min_alignment;
+        assert!(
+            min_alignment <= VM::MAX_ALIGNMENT,
+            "immix_copy_min_alignment ({}) is larger than VMBinding::MAX_ALIGNMENT ({})",
+            min_alignment,
+            VM::MAX_ALIGNMENT
+        );
         ImmixCopyContext {
             allocator: ImmixAllocator::new(tls.0, Some(space), context, true),
+            min_alignment,
+        }
+    }
+
+    /// Acquire a clean block for the allocator.  See [`PrewarmCopyAllocators`].
+    pub(crate) fn prewarm(&mut self) {
+        if self.get_space().may_copy_in_current_gc() {
+            self.allocator.prewarm();
         }
     }
 
@@ -1060,6 +2218,12 @@ impl<VM: VMBinding> ImmixCopyContext<VM> {
 /// Hybrid Immix copy context. It includes two different immix allocators. One with `copy = true`
 /// is used for defrag GCs, and the other is used for other purposes (such as promoting objects from
 /// nursery to Immix mature space). This is used by generational immix.
+///
+/// Each allocator charges the clean blocks it acquires to its own copy budget (see the options
+/// `immix_promotion_copy_budget` and `immix_defrag_copy_budget`).  Once a budget is used up,
+/// objects are no longer copied opportunistically for that purpose, but marked in place.  Objects
+/// that must be moved (e.g. nursery objects being promoted) are always copied, and so is an object
+/// whose copy is already under way when the budget runs out.  They may exceed the budget.
 pub struct ImmixHybridCopyContext<VM: VMBinding> {
     copy_allocator: ImmixAllocator<VM>,
     defrag_allocator: ImmixAllocator<VM>,
@@ -1073,6 +2237,8 @@ impl<VM: VMBinding> PolicyCopyContext for ImmixHybridCopyContext<VM> {
         self.defrag_allocator.reset();
     }
     fn release(&mut self) {
+        self.copy_allocator.release_unused_prewarmed_block();
+        self.defrag_allocator.release_unused_prewarmed_block();
         self.copy_allocator.reset();
         self.defrag_allocator.reset();
     }
@@ -1092,6 +2258,11 @@ impl<VM: VMBinding> PolicyCopyContext for ImmixHybridCopyContext<VM> {
     fn post_copy(&mut self, obj: ObjectReference, bytes: usize) {
         self.get_space().post_copy(obj, bytes)
     }
+    fn take_alloc_stats(&mut self) -> CopyAllocStats {
+        let mut stats = std::mem::take(&mut self.copy_allocator.alloc_stats);
+        stats += std::mem::take(&mut self.defrag_allocator.alloc_stats);
+        stats
+    }
 }
 
 impl<VM: VMBinding> ImmixHybridCopyContext<VM> {
@@ -1101,8 +2272,24 @@ impl<VM: VMBinding> ImmixHybridCopyContext<VM> {
         space: &'static ImmixSpace<VM>,
     ) -> Self {
         ImmixHybridCopyContext {
-            copy_allocator: ImmixAllocator::new(tls.0, Some(space), context.clone(), false),
-            defrag_allocator: ImmixAllocator::new(tls.0, Some(space), context, true),
+            copy_allocator: ImmixAllocator::new(tls.0, Some(space), context.clone(), false)
+                .with_copy_purpose(CopyPurpose::Promotion),
+            defrag_allocator: ImmixAllocator::new(tls.0, Some(space), context, true)
+                .with_copy_purpose(CopyPurpose::Defrag),
+        }
+    }
+
+    /// Acquire a clean block for the allocator that `alloc_copy` will use in the current GC.  See
+    /// [`PrewarmCopyAllocators`].
+    pub(crate) fn prewarm(&mut self) {
+        let space = self.get_space();
+        if !space.may_copy_in_current_gc() {
+            return;
+        }
+        if space.in_defrag() {
+            self.defrag_allocator.prewarm();
+        } else {
+            self.copy_allocator.prewarm();
         }
     }
 
@@ -1128,6 +2315,12 @@ enum VOBitsClearingScope {
     Line { state: u8 },
 }
 
+/// When clearing all VO bits in a chunk in a full-heap GC, if fewer than this number of blocks in
+/// the chunk are allocated, we clear the VO bits of the allocated blocks one by one instead of
+/// clearing the VO bits for the whole chunk.  Unallocated blocks never have VO bits set.
+#[cfg(feature = "vo_bit")]
+const SPARSE_CHUNK_ALLOCATED_BLOCKS_THRESHOLD: usize = Chunk::BYTES / Block::BYTES / 2;
+
 /// A work packet to clear VO bit metadata after Prepare.
 #[cfg(feature = "vo_bit")]
 struct ClearVOBitsAfterPrepare {
@@ -1140,7 +2333,14 @@ impl<VM: VMBinding> GCWork<VM> for ClearVOBitsAfterPrepare {
     fn do_work(&mut self, _worker: &mut GCWorker<VM>, _mmtk: &'static MMTK<VM>) {
         match self.scope {
             VOBitsClearingScope::FullGC => {
-                vo_bit::bzero_vo_bit(self.chunk.start(), Chunk::BYTES);
+                let allocated_blocks = self.allocated_blocks().collect::<Vec<_>>();
+                if allocated_blocks.len() < SPARSE_CHUNK_ALLOCATED_BLOCKS_THRESHOLD {
+                    for block in allocated_blocks {
+                        vo_bit::bzero_vo_bit(block.start(), Block::BYTES);
+                    }
+                } else {
+                    vo_bit::bzero_vo_bit(self.chunk.start(), Chunk::BYTES);
+                }
             }
             VOBitsClearingScope::BlockOnly => {
                 self.clear_blocks(None);
@@ -1154,12 +2354,14 @@ impl<VM: VMBinding> GCWork<VM> for ClearVOBitsAfterPrepare {
 
 #[cfg(feature = "vo_bit")]
 impl ClearVOBitsAfterPrepare {
-    fn clear_blocks(&mut self, line_mark_state: Option<u8>) {
-        for block in self
-            .chunk
+    fn allocated_blocks(&self) -> impl Iterator<Item = Block> {
+        self.chunk
             .iter_region::<Block>()
             .filter(|block| block.get_state() != BlockState::Unallocated)
-        {
+    }
+
+    fn clear_blocks(&mut self, line_mark_state: Option<u8>) {
+        for block in self.allocated_blocks() {
             block.clear_vo_bits_for_unmarked_regions(line_mark_state);
         }
     }
@@ -8,6 +8,40 @@ pub use immixspace::*;
 use crate::policy::immix::block::Block;
 use crate::util::linear_scan::Region;
 
+// BLOCK SIZE: The block size is a compile-time constant, selected from the predefined sizes below
+// with cargo features.
+//
+// | feature               | block size |  lines per block |
+// |-----------------------|------------|------------------|
+// | (default)             | 32KB       | 128              |
+// | `immix_smaller_block` | 8KB        | 32               |
+//
+// The block size cannot be chosen at run time (e.g. in `ImmixSpaceArgs`), nor can `ImmixSpace` be
+// made generic over it, because the following depend on it as constants:
+//
+// -   The per-block side metadata (`IX_BLOCK_DEFRAG`, `IX_BLOCK_MARK`, `IX_BLOCK_PIN` and
+//     `IX_BLOCK_MAX_HOLE` in `spec_defs.rs`) uses `Block::LOG_BYTES` as its region size.  The
+//     offsets of the local side metadata specs after them are computed from it at compile time,
+//     so a different block size changes the side metadata layout of all the spaces.
+// -   `Block` implements `Region`, whose `LOG_BYTES` is an associated constant.  Chunk and block
+//     iteration, `Block::from_unaligned_address` and the page resource (which allocates
+//     `Block::PAGES` at a time) rely on it.
+// -   The line mark table of a block is a `MetadataByteArrayRef<{ Block::LINES }>`.
+//
+// Larger blocks are also limited by the per-block metadata, which is one byte per block.  The
+// block state byte stores the unavailable lines of a reusable block (up to `Block::LINES - 1`)
+// below `BlockState::MARK_MARKED`, and the defrag state byte stores the number of holes (up to
+// `Block::LINES / 2`) below `Block::DEFRAG_SOURCE_STATE`.  So a block can have at most 254 lines,
+// and 32KB is the largest block size with 256-byte lines.  Larger blocks need wider per-block
+// metadata.
+
+/// Log of the block size in bytes.  See the table above.
+pub const LOG_BLOCK_BYTES: usize = if cfg!(feature = "immix_smaller_block") {
+    13
+} else {
+    15
+};
+
 /// The max object size for immix: half of a block
 pub const MAX_IMMIX_OBJECT_SIZE: usize = Block::BYTES >> 1;
 
@@ -42,7 +76,3 @@ pub const DEFRAG_HEADROOM_PERCENT: usize = if cfg!(feature = "immix_stress_copyi
 } else {
     2
 };
-
-/// Mark lines when scanning objects.
-/// Otherwise, do it at mark time.
-pub const MARK_LINE_AT_SCAN_TIME: bool = true;
@@ -155,7 +155,12 @@ impl<VM: VMBinding> crate::policy::gc_work::PolicyTraceObject<VM> for MarkCompac
         } else if KIND == TRACE_KIND_FORWARD {
             self.trace_forward_object(queue, object)
         } else {
-            unreachable!()
+            panic!(
+                "Unsupported trace kind {} for tracing object {} in space {}",
+                KIND,
+                object,
+                self.get_name()
+            )
         }
     }
     fn may_move_objects<const KIND: crate::policy::gc_work::TraceKind>() -> bool {
@@ -164,7 +169,7 @@ impl<VM: VMBinding> crate::policy::gc_work::PolicyTraceObject<VM> for MarkCompac
         } else if KIND == TRACE_KIND_FORWARD {
             true
         } else {
-            unreachable!()
+            panic!("Unsupported trace kind {} for MarkCompactSpace", KIND)
         }
     }
 }
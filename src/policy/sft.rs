@@ -53,6 +53,18 @@ pub trait SFT {
     fn unpin_object(&self, object: ObjectReference) -> bool;
     #[cfg(feature = "object_pinning")]
     fn is_object_pinned(&self, object: ObjectReference) -> bool;
+    /// Pin the block containing the object so that no objects in that block will move.  Only
+    /// policies that allocate objects in blocks and support pinning (eg. Immix) implement this.
+    /// Other policies return false.
+    #[cfg(feature = "object_pinning")]
+    fn pin_block(&self, _object: ObjectReference) -> bool {
+        false
+    }
+    /// Unpin the block containing the object.  See [`SFT::pin_block`].
+    #[cfg(feature = "object_pinning")]
+    fn unpin_block(&self, _object: ObjectReference) -> bool {
+        false
+    }
 
     /// Is the object movable, determined by the policy? E.g. the policy is non-moving,
     /// or the object is pinned.
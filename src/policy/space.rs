@@ -12,6 +12,7 @@ use crate::util::ObjectReference;
 use crate::util::heap::layout::vm_layout::{vm_layout, LOG_BYTES_IN_CHUNK};
 use crate::util::heap::{PageResource, VMRequest};
 use crate::util::options::Options;
+use crate::util::statistics::stats::Stats;
 use crate::vm::{ActivePlan, Collection};
 
 use crate::util::constants::{LOG_BYTES_IN_MBYTE, LOG_BYTES_IN_PAGE};
@@ -519,6 +520,7 @@ pub struct PlanCreateSpaceArgs<'a, VM: VMBinding> {
     pub scheduler: Arc<GCWorkScheduler<VM>>,
     pub options: Arc<Options>,
     pub global_state: Arc<GlobalState>,
+    pub stats: &'a Stats,
 }
 
 impl<'a, VM: VMBinding> PlanCreateSpaceArgs<'a, VM> {
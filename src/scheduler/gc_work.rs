@@ -7,6 +7,7 @@ use crate::util::*;
 use crate::vm::slot::Slot;
 use crate::vm::*;
 use crate::*;
+use std::collections::HashSet;
 use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
 
@@ -162,6 +163,13 @@ impl<C: GCWorkContext + 'static> GCWork<C::VM> for Release<C> {
             *mmtk.state.live_bytes_in_last_gc.borrow_mut() =
                 mmtk.aggregate_live_bytes_in_last_gc(live_bytes);
         }
+
+        let scanned_objects = mmtk
+            .scheduler
+            .worker_group
+            .get_and_clear_worker_scanned_objects();
+        *mmtk.state.scanned_objects_in_last_gc.borrow_mut() =
+            mmtk.aggregate_scanned_objects_in_last_gc(scanned_objects);
     }
 }
 
@@ -193,6 +201,116 @@ impl<VM: VMBinding> GCWork<VM> for ReleaseCollector {
     fn do_work(&mut self, worker: &mut GCWorker<VM>, _mmtk: &'static MMTK<VM>) {
         trace!("Release Collector");
         worker.get_copy_context_mut().release();
+        let copy_alloc_stats = worker.get_copy_context_mut().take_alloc_stats();
+        *worker.shared.copy_alloc_stats.borrow_mut() += copy_alloc_stats;
+        if let Some(copy_breakdown) = worker.get_copy_context_mut().take_copy_breakdown() {
+            let mut shared = worker.shared.copy_breakdown.borrow_mut();
+            for (semantics, bytes) in copy_breakdown {
+                shared[semantics] += bytes;
+            }
+        }
+        #[cfg(feature = "forwarding_log")]
+        {
+            let mut forwarding_log = worker.get_copy_context_mut().take_forwarding_log();
+            worker
+                .shared
+                .forwarding_log
+                .borrow_mut()
+                .append(&mut forwarding_log);
+        }
+    }
+}
+
+/// A debug work packet that checks that the SFT map is consistent with the spaces of the plan.
+///
+/// Each chunk acquired by a space must map to the SFT entry of that space, and the address ranges
+/// of contiguous spaces must not overlap. A mis-registered space would otherwise make
+/// `SFT_MAP.get_unchecked` silently return the wrong SFT during tracing.
+///
+/// A discontiguous space acquires chunks from the VM map, which records the owner of each chunk.
+/// A contiguous space does not record which chunks in its address range it has acquired, and
+/// chunks that it has not acquired have no SFT entry (or the empty SFT) in chunk-based SFT maps.
+/// So for a contiguous space, we only check the mapped chunks in its range that have a non-empty
+/// SFT entry. Spaces that do not manage their own address range (i.e. `MallocSpace`) are skipped.
+#[cfg(feature = "extreme_assertions")]
+pub struct VerifySFT;
+
+#[cfg(feature = "extreme_assertions")]
+impl<VM: VMBinding> GCWork<VM> for VerifySFT {
+    fn do_work(&mut self, _worker: &mut GCWorker<VM>, mmtk: &'static MMTK<VM>) {
+        use crate::mmtk::{MMAPPER, SFT_MAP, VM_MAP};
+        use crate::policy::sft::EMPTY_SFT_NAME;
+        use crate::policy::space::Space;
+        use crate::util::conversions::chunk_align_down;
+        use crate::util::heap::layout::vm_layout::{vm_layout, BYTES_IN_CHUNK};
+
+        let check_chunk = |space: &dyn Space<VM>, chunk: Address| {
+            let name = space.get_name();
+            assert!(
+                SFT_MAP.has_sft_entry(chunk),
+                "Chunk {} of space {} has no SFT entry",
+                chunk,
+                name
+            );
+            let sft = SFT_MAP.get_checked(chunk);
+            assert!(
+                std::ptr::eq(
+                    sft as *const dyn crate::policy::sft::SFT as *const (),
+                    space as *const dyn Space<VM> as *const ()
+                ),
+                "Chunk {} of space {} maps to the SFT of {}",
+                chunk,
+                name,
+                sft.name()
+            );
+        };
+
+        let mut ranges: Vec<(Address, Address, &'static str)> = vec![];
+        mmtk.get_plan().for_each_space(&mut |space| {
+            if space
+                .downcast_ref::<crate::policy::marksweepspace::malloc_ms::MallocSpace<VM>>()
+                .is_some()
+            {
+                return;
+            }
+            let common = space.common();
+            if common.contiguous {
+                let end = common.start + common.extent;
+                let mut chunk = common.start;
+                while chunk < end {
+                    if MMAPPER.is_mapped_address(chunk)
+                        && SFT_MAP.has_sft_entry(chunk)
+                        && SFT_MAP.get_checked(chunk).name() != EMPTY_SFT_NAME
+                    {
+                        check_chunk(space, chunk);
+                    }
+                    chunk += BYTES_IN_CHUNK;
+                }
+                ranges.push((common.start, end, common.name));
+            } else {
+                let mut chunk = chunk_align_down(vm_layout().available_start());
+                while chunk < vm_layout().available_end() {
+                    if VM_MAP.get_descriptor_for_address(chunk) == common.descriptor {
+                        check_chunk(space, chunk);
+                    }
+                    chunk += BYTES_IN_CHUNK;
+                }
+            }
+        });
+
+        ranges.sort_by_key(|(start, _, _)| *start);
+        for pair in ranges.windows(2) {
+            let (_, prev_end, prev_name) = pair[0];
+            let (next_start, _, next_name) = pair[1];
+            assert!(
+                prev_end <= next_start,
+                "Space {} (ending at {}) overlaps with space {} (starting at {})",
+                prev_name,
+                prev_end,
+                next_name,
+                next_start
+            );
+        }
     }
 }
 
@@ -211,14 +329,37 @@ impl<C: GCWorkContext> StopMutators<C> {
 impl<C: GCWorkContext> GCWork<C::VM> for StopMutators<C> {
     fn do_work(&mut self, worker: &mut GCWorker<C::VM>, mmtk: &'static MMTK<C::VM>) {
         trace!("stop_all_mutators start");
+        *mmtk.state.stop_mutators_start_time.borrow_mut() = Some(std::time::Instant::now());
         mmtk.state.prepare_for_stack_scanning();
+        let mut stopped_mutators = HashSet::new();
         <C::VM as VMBinding>::VMCollection::stop_all_mutators(worker.tls, |mutator| {
+            stopped_mutators.insert(mutator as *const Mutator<C::VM>);
             // TODO: The stack scanning work won't start immediately, as the `Prepare` bucket is not opened yet (the bucket is opened in notify_mutators_paused).
             // Should we push to Unconstrained instead?
             mmtk.scheduler.work_buckets[WorkBucketStage::Prepare]
-                .add(ScanMutatorRoots::<C>(mutator));
+                .add(ScanMutatorRoots::<C>::new(mutator));
         });
         trace!("stop_all_mutators end");
+        // Some runtimes may create mutators while we are stopping the mutators, and those mutators
+        // may not be visited by `stop_all_mutators`.  Each `ScanMutatorRoots` packet expects
+        // `number_of_mutators()` packets in total (see `GlobalState::inform_stack_scanned`), so
+        // we need to scan the late mutators, too.  Otherwise stack scanning never completes.  The
+        // counters reset by `prepare_for_stack_scanning` above are not touched until the `Prepare`
+        // bucket is opened, so it is safe to add more packets here.
+        let n_mutators = <C::VM as VMBinding>::VMActivePlan::number_of_mutators();
+        if n_mutators != stopped_mutators.len() {
+            debug!(
+                "{} mutators were stopped, but there are {} mutators now",
+                stopped_mutators.len(),
+                n_mutators
+            );
+            for mutator in <C::VM as VMBinding>::VMActivePlan::mutators() {
+                if !stopped_mutators.contains(&(mutator as *const Mutator<C::VM>)) {
+                    mmtk.scheduler.work_buckets[WorkBucketStage::Prepare]
+                        .add(ScanMutatorRoots::<C>::new(mutator));
+                }
+            }
+        }
         mmtk.scheduler.notify_mutators_paused(mmtk);
         mmtk.scheduler.work_buckets[WorkBucketStage::Prepare].add(ScanVMSpecificRoots::<C>::new());
     }
@@ -242,6 +383,20 @@ impl<E: ProcessEdgesWork> ObjectTracer for ProcessEdgesWorkTracer<E> {
 }
 
 impl<E: ProcessEdgesWork> ProcessEdgesWorkTracer<E> {
+    /// Create a tracer with a new underlying `ProcessEdgesWork`.  Work packets for the traced
+    /// objects will be added to the bucket `stage`.
+    fn new(worker: &mut GCWorker<E::VM>, stage: WorkBucketStage) -> Self {
+        // Prepare the underlying ProcessEdgesWork
+        let mut process_edges_work = E::new(vec![], false, worker.mmtk, stage);
+        // FIXME: This line allows us to omit the borrowing lifetime of worker.
+        // We should refactor ProcessEdgesWork so that it uses `worker` locally, not as a member.
+        process_edges_work.set_worker(worker);
+        Self {
+            process_edges_work,
+            stage,
+        }
+    }
+
     fn flush_if_full(&mut self) {
         if self.process_edges_work.nodes.is_full() {
             self.flush();
@@ -253,9 +408,34 @@ impl<E: ProcessEdgesWork> ProcessEdgesWorkTracer<E> {
             if !self.process_edges_work.nodes.is_empty() {
                 self.flush();
             }
-        } else {
-            if !self.process_edges_work.slots.is_empty() {
-                self.process_edges_work.flush();
+        } else if !self.process_edges_work.slots.is_empty() {
+            self.process_edges_work.flush();
+        }
+    }
+
+    /// Scan the queued objects, and the objects reachable from them, on the current thread instead
+    /// of creating work packets for them.  The underlying `ProcessEdgesWork` must not move objects.
+    fn drain(&mut self) {
+        debug_assert!(!E::OVERWRITE_REFERENCE);
+        let tls = self.process_edges_work.worker().tls;
+        let process_edges_work = &mut self.process_edges_work;
+        while !process_edges_work.nodes.is_empty() {
+            // Not using `pop_nodes` because the objects may have been enqueued by this worker in
+            // the current GC.
+            for object in process_edges_work.nodes.take() {
+                if <E::VM as VMBinding>::VMScanning::support_slot_enqueuing(tls, object) {
+                    <E::VM as VMBinding>::VMScanning::scan_object(
+                        tls,
+                        object,
+                        &mut |slot: SlotOf<E>| process_edges_work.process_slot(slot),
+                    );
+                } else {
+                    <E::VM as VMBinding>::VMScanning::scan_object_and_trace_edges(
+                        tls,
+                        object,
+                        &mut |child: ObjectReference| process_edges_work.trace_object(child),
+                    );
+                }
             }
         }
     }
@@ -269,6 +449,28 @@ impl<E: ProcessEdgesWork> ProcessEdgesWorkTracer<E> {
     }
 }
 
+/// Find all the objects transitively reachable from `roots` on the current thread, using a
+/// temporary [`ProcessEdgesWorkTracerContext`] with [`VisitingProcessEdges`].  See
+/// [`crate::MMTK::reachable_from`].
+pub(crate) fn reachable_from<VM: VMBinding>(
+    worker: &mut GCWorker<VM>,
+    roots: &[ObjectReference],
+) -> Vec<ObjectReference> {
+    let tracer_context = ProcessEdgesWorkTracerContext::<VisitingProcessEdges<VM>> {
+        stage: WorkBucketStage::Unconstrained,
+        phantom_data: PhantomData,
+    };
+    tracer_context.with_tracer(worker, |tracer| {
+        for root in roots.iter().copied() {
+            // Drain after each root, so that the queue never becomes full and is never flushed
+            // as work packets.
+            tracer.process_edges_work.trace_object(root);
+            tracer.drain();
+        }
+        std::mem::take(&mut tracer.process_edges_work.reachable)
+    })
+}
+
 /// This type implements `ObjectTracerContext` by creating a temporary `ProcessEdgesWork` during
 /// the call to `with_tracer`, making use of its `trace_object` method.  It then creates work
 /// packets using the methods of the `ProcessEdgesWork` and add the work packet into the given
@@ -291,19 +493,8 @@ impl<E: ProcessEdgesWork> ObjectTracerContext<E::VM> for ProcessEdgesWorkTracerC
     where
         F: FnOnce(&mut Self::TracerType) -> R,
     {
-        let mmtk = worker.mmtk;
-
-        // Prepare the underlying ProcessEdgesWork
-        let mut process_edges_work = E::new(vec![], false, mmtk, self.stage);
-        // FIXME: This line allows us to omit the borrowing lifetime of worker.
-        // We should refactor ProcessEdgesWork so that it uses `worker` locally, not as a member.
-        process_edges_work.set_worker(worker);
-
         // Cretae the tracer.
-        let mut tracer = ProcessEdgesWorkTracer {
-            process_edges_work,
-            stage: self.stage,
-        };
+        let mut tracer = ProcessEdgesWorkTracer::new(worker, self.stage);
 
         // The caller can use the tracer here.
         let result = func(&mut tracer);
@@ -323,19 +514,27 @@ impl<E: ProcessEdgesWork> ObjectTracerContext<E::VM> for ProcessEdgesWorkTracerC
 ///
 /// NOTE: This will replace `{Soft,Weak,Phantom}RefProcessing` and `Finalization` in the future.
 pub struct VMProcessWeakRefs<E: ProcessEdgesWork> {
+    /// The number of times `process_weak_refs` has been called in this GC before this packet.
+    /// It is carried over to the new sentinel when the binding asks for repeating.
+    iterations: usize,
     phantom_data: PhantomData<E>,
 }
 
 impl<E: ProcessEdgesWork> VMProcessWeakRefs<E> {
     pub fn new() -> Self {
+        Self::with_iterations(0)
+    }
+
+    fn with_iterations(iterations: usize) -> Self {
         Self {
+            iterations,
             phantom_data: PhantomData,
         }
     }
 }
 
 impl<E: ProcessEdgesWork> GCWork<E::VM> for VMProcessWeakRefs<E> {
-    fn do_work(&mut self, worker: &mut GCWorker<E::VM>, _mmtk: &'static MMTK<E::VM>) {
+    fn do_work(&mut self, worker: &mut GCWorker<E::VM>, mmtk: &'static MMTK<E::VM>) {
         trace!("VMProcessWeakRefs");
 
         let stage = WorkBucketStage::VMRefClosure;
@@ -349,9 +548,25 @@ impl<E: ProcessEdgesWork> GCWork<E::VM> for VMProcessWeakRefs<E> {
         };
 
         if need_to_repeat {
+            let iterations = self.iterations + 1;
+            let max_iterations = *mmtk.get_options().max_weak_ref_iterations;
+            if iterations >= max_iterations {
+                // The binding keeps asking for repeating.  This is likely a bug in the binding.
+                let message = format!(
+                    "Scanning::process_weak_refs asked for repeating after {} iterations, reaching \
+                    the limit set by the option max_weak_ref_iterations.",
+                    iterations
+                );
+                if cfg!(debug_assertions) {
+                    panic!("{}", message);
+                }
+                warn!("{} Stop repeating.", message);
+                return;
+            }
+
             // Schedule Self as the new sentinel so we'll call `process_weak_refs` again after the
             // current transitive closure.
-            let new_self = Box::new(Self::new());
+            let new_self = Box::new(Self::with_iterations(iterations));
 
             worker.scheduler().work_buckets[stage].set_sentinel(new_self);
         }
@@ -410,7 +625,20 @@ impl<VM: VMBinding> GCWork<VM> for VMPostForwarding<VM> {
     }
 }
 
-pub struct ScanMutatorRoots<C: GCWorkContext>(pub &'static mut Mutator<C::VM>);
+pub struct ScanMutatorRoots<C: GCWorkContext>(pub &'static mut Mutator<C::VM>, WorkBucketStage);
+
+impl<C: GCWorkContext> ScanMutatorRoots<C> {
+    /// Scan the roots of `mutator`, and process them in the `Closure` stage.
+    pub fn new(mutator: &'static mut Mutator<C::VM>) -> Self {
+        Self::with_stage(mutator, WorkBucketStage::Closure)
+    }
+
+    /// Scan the roots of `mutator`, and process them in `stage` instead of the `Closure` stage.
+    /// See [`ScanVMSpecificRoots::with_stage`].
+    pub fn with_stage(mutator: &'static mut Mutator<C::VM>, stage: WorkBucketStage) -> Self {
+        Self(mutator, stage)
+    }
+}
 
 impl<C: GCWorkContext> GCWork<C::VM> for ScanMutatorRoots<C> {
     fn do_work(&mut self, worker: &mut GCWorker<C::VM>, mmtk: &'static MMTK<C::VM>) {
@@ -420,7 +648,7 @@ impl<C: GCWorkContext> GCWork<C::VM> for ScanMutatorRoots<C> {
             C::VM,
             C::DefaultProcessEdges,
             C::PinningProcessEdges,
-        >::new(mmtk);
+        >::with_stage(mmtk, self.1);
         <C::VM as VMBinding>::VMScanning::scan_roots_in_mutator_thread(
             worker.tls,
             unsafe { &mut *(self.0 as *mut _) },
@@ -428,33 +656,62 @@ impl<C: GCWorkContext> GCWork<C::VM> for ScanMutatorRoots<C> {
         );
         self.0.flush();
 
-        if mmtk.state.inform_stack_scanned(mutators) {
-            <C::VM as VMBinding>::VMScanning::notify_initial_thread_scan_complete(
-                false, worker.tls,
-            );
-            mmtk.set_gc_status(GcStatus::GcProper);
-        }
+        inform_stack_scanned(worker.tls, mmtk, mutators);
     }
 }
 
-#[derive(Default)]
-pub struct ScanVMSpecificRoots<C: GCWorkContext>(PhantomData<C>);
+/// Inform that a stack-scanning task has finished, and notify the binding if all the stacks
+/// have been scanned.
+pub(crate) fn inform_stack_scanned<VM: VMBinding>(
+    tls: VMWorkerThread,
+    mmtk: &'static MMTK<VM>,
+    n_mutators: usize,
+) {
+    if mmtk.state.inform_stack_scanned(n_mutators) {
+        <VM as VMBinding>::VMScanning::notify_initial_thread_scan_complete(false, tls);
+        mmtk.set_gc_status(GcStatus::GcProper);
+    }
+}
+
+pub struct ScanVMSpecificRoots<C: GCWorkContext>(WorkBucketStage, PhantomData<C>);
 
 impl<C: GCWorkContext> ScanVMSpecificRoots<C> {
     pub fn new() -> Self {
-        Self(PhantomData)
+        Self::with_stage(WorkBucketStage::Closure)
+    }
+
+    /// Process the VM-specific roots (and the slots remembered by write barriers) in `stage`
+    /// instead of the `Closure` stage.  This lets plans insert their own phases between root
+    /// scanning and the transitive closure.  Pinning roots are still processed in the
+    /// `PinningRootsTrace` stage, and transitive pinning roots in the `TPinningClosure` stage, but
+    /// the objects reachable from non-transitive pinning roots are traced in `stage`.  `stage` must
+    /// not be opened before the stage the root scanning packet is in.
+    pub fn with_stage(stage: WorkBucketStage) -> Self {
+        Self(stage, PhantomData)
+    }
+}
+
+impl<C: GCWorkContext> Default for ScanVMSpecificRoots<C> {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl<C: GCWorkContext> GCWork<C::VM> for ScanVMSpecificRoots<C> {
     fn do_work(&mut self, worker: &mut GCWorker<C::VM>, mmtk: &'static MMTK<C::VM>) {
         trace!("ScanStaticRoots");
-        let factory = ProcessEdgesWorkRootsWorkFactory::<
+        let mut factory = ProcessEdgesWorkRootsWorkFactory::<
             C::VM,
             C::DefaultProcessEdges,
             C::PinningProcessEdges,
-        >::new(mmtk);
-        <C::VM as VMBinding>::VMScanning::scan_vm_specific_roots(worker.tls, factory);
+        >::with_stage(mmtk, self.0);
+        <C::VM as VMBinding>::VMScanning::scan_vm_specific_roots(worker.tls, factory.clone());
+
+        // Process the slots enqueued by write barriers since the last GC.
+        let barrier_slots = std::mem::take(&mut *mmtk.barrier_slots.lock().unwrap());
+        for slots in barrier_slots {
+            factory.create_process_remembered_slots(slots);
+        }
     }
 }
 
@@ -465,9 +722,14 @@ pub struct ProcessEdgesBase<VM: VMBinding> {
     // Use raw pointer for fast pointer dereferencing, instead of using `Option<&'static mut GCWorker<E::VM>>`.
     // Because a copying gc will dereference this pointer at least once for every object copy.
     worker: *mut GCWorker<VM>,
+    /// Are the slots roots?  Root slots are cached for sanity GC.  Slots from remembered sets (see
+    /// `RootsWorkFactory::create_process_remembered_slots`) are not roots.
     pub roots: bool,
     pub pushes: u32,
     pub bucket: WorkBucketStage,
+    /// The number of slots this packet updated to point to moved objects.
+    #[cfg(feature = "count_updated_slots")]
+    pub updated_slots: usize,
 }
 
 unsafe impl<VM: VMBinding> Send for ProcessEdgesBase<VM> {}
@@ -480,6 +742,20 @@ impl<VM: VMBinding> ProcessEdgesBase<VM> {
         roots: bool,
         mmtk: &'static MMTK<VM>,
         bucket: WorkBucketStage,
+    ) -> Self {
+        Self::with_capacity(slots, roots, mmtk, bucket, 0)
+    }
+
+    /// Like [`ProcessEdgesBase::new`], but reserve space for `nodes_capacity` objects in the node
+    /// queue.  If the number of objects this packet will enqueue is known (e.g. at most one per
+    /// root slot), this avoids reallocating the node queue while tracing.  A `nodes_capacity` of 0
+    /// is the same as [`ProcessEdgesBase::new`].
+    pub fn with_capacity(
+        slots: Vec<VM::VMSlot>,
+        roots: bool,
+        mmtk: &'static MMTK<VM>,
+        bucket: WorkBucketStage,
+        nodes_capacity: usize,
     ) -> Self {
         #[cfg(feature = "extreme_assertions")]
         if crate::util::slot_logger::should_check_duplicate_slots(mmtk.get_plan()) {
@@ -490,12 +766,14 @@ impl<VM: VMBinding> ProcessEdgesBase<VM> {
         }
         Self {
             slots,
-            nodes: VectorObjectQueue::new(),
+            nodes: VectorObjectQueue::with_capacity(nodes_capacity),
             mmtk,
             worker: std::ptr::null_mut(),
             roots,
             pushes: 0,
             bucket,
+            #[cfg(feature = "count_updated_slots")]
+            updated_slots: 0,
         }
     }
     pub fn set_worker(&mut self, worker: &mut GCWorker<VM>) {
@@ -516,12 +794,47 @@ impl<VM: VMBinding> ProcessEdgesBase<VM> {
 
     /// Pop all nodes from nodes, and clear nodes to an empty vector.
     pub fn pop_nodes(&mut self) -> Vec<ObjectReference> {
-        self.nodes.take()
+        let nodes = self.nodes.take();
+        #[cfg(feature = "extreme_assertions")]
+        if crate::util::slot_logger::should_check_duplicate_slots(self.mmtk.get_plan())
+            && !self.worker.is_null()
+        {
+            let worker = self.worker();
+            for object in nodes.iter().copied() {
+                // log object, panic if already enqueued by this worker
+                worker.log_enqueued_object(object);
+            }
+        }
+        nodes
     }
 
     pub fn is_roots(&self) -> bool {
         self.roots
     }
+
+    /// Record that a slot is updated to point to a moved object.  This does nothing unless the
+    /// feature `count_updated_slots` is enabled.
+    #[inline(always)]
+    pub fn count_updated_slot(&mut self) {
+        #[cfg(feature = "count_updated_slots")]
+        {
+            self.updated_slots += 1;
+        }
+    }
+
+    /// The maximum number of objects a scan-objects work packet created by this packet should scan.
+    /// Return `None` if there is no limit.
+    pub fn max_objects_per_scan_packet(&self) -> Option<usize> {
+        let max_objects = *self.mmtk.get_options().max_objects_per_scan_packet;
+        (max_objects != usize::MAX).then_some(max_objects)
+    }
+
+    /// The maximum number of slots this packet should process before yielding to the scheduler.
+    /// Return `None` if there is no limit.
+    pub fn max_slots_per_process_packet(&self) -> Option<usize> {
+        let max_slots = *self.mmtk.get_options().max_slots_per_process_packet;
+        (max_slots != usize::MAX).then_some(max_slots)
+    }
 }
 
 /// A short-hand for `<E::VM as VMBinding>::VMSlot`.
@@ -570,6 +883,22 @@ pub trait ProcessEdgesWork:
     /// If true, we do object scanning in this work packet with the same worker without scheduling overhead.
     /// If false, we will add object scanning work packets to the global queue and allow other workers to work on it.
     const SCAN_OBJECTS_IMMEDIATELY: bool = true;
+    /// If true, [`ProcessEdgesWork::process_slot`] skips slots that point to objects outside the
+    /// MMTk heap (as determined by [`ProcessEdgesWork::in_mmtk_heap`]) without calling
+    /// [`ProcessEdgesWork::trace_object`].  This saves the cost of dispatching to the space (and
+    /// eventually to `ActivePlan::vm_trace_object()`) for bindings that have many slots pointing to
+    /// VM-internal objects that do not need to be traced.  It must not be enabled if some MMTk
+    /// spaces are outside the heap range, i.e. when using the VM space or malloc-based spaces.
+    const FILTER_NON_MMTK: bool = false;
+
+    /// The maximum number of slots that should be put to one of this work packets when the packet
+    /// is in the given `bucket`.  By default, it returns [`ProcessEdgesWork::CAPACITY`] for all
+    /// buckets.  An implementation may override this to use smaller packets for better load
+    /// balancing in some buckets (e.g. `Closure`) and larger packets to amortize the scheduling
+    /// cost in others.
+    fn capacity(_bucket: WorkBucketStage) -> usize {
+        Self::CAPACITY
+    }
 
     /// Create a [`ProcessEdgesWork`].
     ///
@@ -585,28 +914,68 @@ pub trait ProcessEdgesWork:
         bucket: WorkBucketStage,
     ) -> Self;
 
+    /// Create a [`ProcessEdgesWork`] like [`ProcessEdgesWork::new`], and reserve space for
+    /// `nodes_capacity` objects in its node queue.  The capacity is only a hint.  The root work
+    /// factory uses the number of root slots as the hint, because each root slot enqueues at most
+    /// one object.
+    fn with_capacity_hint(
+        slots: Vec<SlotOf<Self>>,
+        roots: bool,
+        mmtk: &'static MMTK<Self::VM>,
+        bucket: WorkBucketStage,
+        nodes_capacity: usize,
+    ) -> Self {
+        let mut work = Self::new(slots, roots, mmtk, bucket);
+        if nodes_capacity > 0 && work.nodes.is_empty() {
+            work.nodes = VectorObjectQueue::with_capacity(nodes_capacity);
+        }
+        work
+    }
+
     /// Trace an MMTk object. The implementation should forward this call to the policy-specific
     /// `trace_object()` methods, depending on which space this object is in.
     /// If the object is not in any MMTk space, the implementation should forward the call to
     /// `ActivePlan::vm_trace_object()` to let the binding handle the tracing.
     fn trace_object(&mut self, object: ObjectReference) -> ObjectReference;
 
+    /// Trace a batch of objects, and push the object references returned from tracing them into
+    /// `out`, in the same order as `objects`.  By default, this calls
+    /// [`ProcessEdgesWork::trace_object`] for each object.  An implementation may override this to
+    /// amortize the per-object dispatching cost over the batch, or to prefetch metadata of the
+    /// objects.
+    fn trace_objects(&mut self, objects: &[ObjectReference], out: &mut Vec<ObjectReference>) {
+        out.reserve(objects.len());
+        for object in objects.iter().copied() {
+            out.push(self.trace_object(object));
+        }
+    }
+
     /// If the work includes roots, we will store the roots somewhere so for sanity GC, we can do another
     /// transitive closure from the roots.
     #[cfg(feature = "sanity")]
     fn cache_roots_for_sanity_gc(&mut self) {
         assert!(self.roots);
+        let slots = self
+            .slots
+            .iter()
+            .copied()
+            .map(<Self::VM as VMBinding>::VMScanning::normalize_root_slot_for_sanity)
+            .collect();
         self.mmtk()
             .sanity_checker
             .lock()
             .unwrap()
-            .add_root_slots(self.slots.clone());
+            .add_root_slots(slots);
     }
 
-    /// Start the a scan work packet. If SCAN_OBJECTS_IMMEDIATELY, the work packet will be executed immediately, in this method.
+    /// Start the a scan work packet. If SCAN_OBJECTS_IMMEDIATELY, the work packet will be executed immediately, in this method,
+    /// unless the worker has executed too many scan work packets immediately (see `Options::immediate_scan_depth_limit`),
+    /// or this packet processes roots and `Options::scan_roots_immediately` is disabled.
     /// Otherwise, the work packet will be added the Closure work bucket and will be dispatched later by the scheduler.
     fn start_or_dispatch_scan_work(&mut self, mut work_packet: impl GCWork<Self::VM>) {
-        if Self::SCAN_OBJECTS_IMMEDIATELY {
+        let scan_immediately = Self::SCAN_OBJECTS_IMMEDIATELY
+            && (!self.roots || *self.mmtk.get_options().scan_roots_immediately);
+        if scan_immediately && self.worker().try_scan_immediately() {
             // We execute this `scan_objects_work` immediately.
             // This is expected to be a useful optimization because,
             // say for _pmd_ with 200M heap, we're likely to have 50000~60000 `ScanObjects` work packets
@@ -634,21 +1003,51 @@ pub trait ProcessEdgesWork:
         }
     }
 
+    /// Return true if the object is in the address range of the MMTk heap.  This is a cheap
+    /// address range test, and does not check whether the address is actually in any space.
+    fn in_mmtk_heap(object: ObjectReference) -> bool {
+        let layout = crate::util::heap::layout::vm_layout::vm_layout();
+        let addr = object.to_raw_address();
+        addr >= layout.heap_start && addr < layout.heap_end
+    }
+
     /// Process a slot, including loading the object reference from the memory slot,
+    /// filtering it with [`crate::vm::Scanning::filter_slot_object`],
     /// trace the object and store back the new object reference if necessary.
     fn process_slot(&mut self, slot: SlotOf<Self>) {
-        let Some(object) = slot.load() else {
-            // Skip slots that are not holding an object reference.
+        let Some(object) = slot
+            .load()
+            .and_then(<Self::VM as VMBinding>::VMScanning::filter_slot_object)
+        else {
+            // Skip slots that are not holding an object reference, or rejected by the filter.
             return;
         };
+        if Self::FILTER_NON_MMTK && !Self::in_mmtk_heap(object) {
+            // Skip slots pointing outside the MMTk heap.
+            return;
+        }
         let new_object = self.trace_object(object);
         if Self::OVERWRITE_REFERENCE && new_object != object {
             slot.store(new_object);
+            self.count_updated_slot();
         }
     }
 
     /// Process all the slots in the work packet.
+    ///
+    /// If the option `max_slots_per_process_packet` is set and this packet has more slots than
+    /// that, only that many slots are processed, and the remaining slots are moved to a new packet
+    /// of the same type in the same bucket.  The new packet keeps the `roots` flag.  As the slots
+    /// are moved out of this packet before processing, each root slot is only cached once for
+    /// sanity GC (see `ProcessEdgesWork::cache_roots_for_sanity_gc`).
     fn process_slots(&mut self) {
+        if let Some(max_slots) = self.max_slots_per_process_packet() {
+            if self.slots.len() > max_slots {
+                let rest = self.slots.split_off(max_slots);
+                let work = Self::new(rest, self.roots, self.mmtk, self.bucket);
+                self.mmtk.scheduler.work_buckets[self.bucket].add(work);
+            }
+        }
         probe!(mmtk, process_slots, self.slots.len(), self.is_roots());
         for i in 0..self.slots.len() {
             self.process_slot(self.slots[i])
@@ -667,6 +1066,11 @@ impl<E: ProcessEdgesWork> GCWork<E::VM> for E {
         if self.roots && !_mmtk.is_in_sanity() {
             self.cache_roots_for_sanity_gc();
         }
+        #[cfg(feature = "count_updated_slots")]
+        worker.shared.updated_slots.fetch_add(
+            std::mem::take(&mut self.updated_slots),
+            std::sync::atomic::Ordering::Relaxed,
+        );
         trace!("ProcessEdgesWork End");
     }
 }
@@ -711,13 +1115,79 @@ impl<VM: VMBinding> ProcessEdgesWork for SFTProcessEdges<VM> {
 
     fn create_scan_work(&self, nodes: Vec<ObjectReference>) -> ScanObjects<Self> {
         if cfg!(not(feature = "edge_enqueuing")) {
-            ScanObjects::<Self>::new(nodes, false, self.bucket)
+            ScanObjects::<Self>::new(
+                nodes,
+                false,
+                self.bucket,
+                self.max_objects_per_scan_packet(),
+            )
         } else {
             unreachable!()
         }
     }
 }
 
+/// A `ProcessEdgesWork` that visits objects without marking or moving them.  It remembers the
+/// visited objects in a hash set, and enqueues an object when it is visited for the first time.
+/// It is used by [`reachable_from`], which scans the enqueued objects on the current thread.
+pub(crate) struct VisitingProcessEdges<VM: VMBinding> {
+    base: ProcessEdgesBase<VM>,
+    visited: HashSet<ObjectReference>,
+    /// The visited objects, in the order they are visited.
+    reachable: Vec<ObjectReference>,
+}
+
+impl<VM: VMBinding> ProcessEdgesWork for VisitingProcessEdges<VM> {
+    type VM = VM;
+    type ScanObjectsWorkType = ScanObjects<Self>;
+
+    const OVERWRITE_REFERENCE: bool = false;
+
+    fn new(
+        slots: Vec<SlotOf<Self>>,
+        roots: bool,
+        mmtk: &'static MMTK<VM>,
+        bucket: WorkBucketStage,
+    ) -> Self {
+        let base = ProcessEdgesBase::new(slots, roots, mmtk, bucket);
+        Self {
+            base,
+            visited: HashSet::new(),
+            reachable: vec![],
+        }
+    }
+
+    fn trace_object(&mut self, object: ObjectReference) -> ObjectReference {
+        if self.visited.insert(object) {
+            self.reachable.push(object);
+            self.base.nodes.enqueue(object);
+        }
+        object
+    }
+
+    fn create_scan_work(&self, nodes: Vec<ObjectReference>) -> ScanObjects<Self> {
+        ScanObjects::<Self>::new(
+            nodes,
+            false,
+            self.bucket,
+            self.max_objects_per_scan_packet(),
+        )
+    }
+}
+
+impl<VM: VMBinding> Deref for VisitingProcessEdges<VM> {
+    type Target = ProcessEdgesBase<VM>;
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl<VM: VMBinding> DerefMut for VisitingProcessEdges<VM> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
 /// An implementation of `RootsWorkFactory` that creates work packets based on `ProcessEdgesWork`
 /// for handling roots.  The `DPE` and the `PPE` type parameters correspond to the
 /// `DefaultProcessEdge` and the `PinningProcessEdges` type members of the [`GCWorkContext`] trait.
@@ -727,6 +1197,8 @@ pub(crate) struct ProcessEdgesWorkRootsWorkFactory<
     PPE: ProcessEdgesWork<VM = VM>,
 > {
     mmtk: &'static MMTK<VM>,
+    /// The stage in which the roots (and the objects reachable from them) are traced.
+    stage: WorkBucketStage,
     phantom: PhantomData<(DPE, PPE)>,
 }
 
@@ -736,6 +1208,7 @@ impl<VM: VMBinding, DPE: ProcessEdgesWork<VM = VM>, PPE: ProcessEdgesWork<VM = V
     fn clone(&self) -> Self {
         Self {
             mmtk: self.mmtk,
+            stage: self.stage,
             phantom: PhantomData,
         }
     }
@@ -763,21 +1236,52 @@ impl<VM: VMBinding, DPE: ProcessEdgesWork<VM = VM>, PPE: ProcessEdgesWork<VM = V
         // different names, and our `capture.bt` mentions all of them, `bpftrace` may complain that
         // it cannot find one or more of those USDT trace points in the binary.
         probe!(mmtk, roots, RootsKind::NORMAL, slots.len());
+        let nodes_capacity = slots.len();
         crate::memory_manager::add_work_packet(
             self.mmtk,
-            WorkBucketStage::Closure,
-            DPE::new(slots, true, self.mmtk, WorkBucketStage::Closure),
+            self.stage,
+            DPE::with_capacity_hint(slots, true, self.mmtk, self.stage, nodes_capacity),
+        );
+    }
+
+    fn create_process_remembered_slots(&mut self, slots: Vec<VM::VMSlot>) {
+        // The slots are not roots, so we set `roots` to false.  This keeps them out of the root set
+        // cached for sanity GC.
+        crate::memory_manager::add_work_packet(
+            self.mmtk,
+            self.stage,
+            DPE::new(slots, false, self.mmtk, self.stage),
         );
     }
 
     fn create_process_pinning_roots_work(&mut self, nodes: Vec<ObjectReference>) {
         probe!(mmtk, roots, RootsKind::PINNING, nodes.len());
         // Will process roots within the PinningRootsTrace bucket
-        // And put work in the Closure bucket
+        // And put work in the target bucket (Closure by default)
+        crate::memory_manager::add_work_packet(
+            self.mmtk,
+            WorkBucketStage::PinningRootsTrace,
+            ProcessRootNode::<VM, PPE, DPE>::new(nodes, self.stage),
+        );
+    }
+
+    fn create_process_depth_limited_pinning_roots_work(
+        &mut self,
+        nodes: Vec<ObjectReference>,
+        depth: usize,
+    ) {
+        probe!(mmtk, roots, RootsKind::PINNING, nodes.len());
+        // Will pin objects down to `depth` within the PinningRootsTrace bucket
+        // And put work for deeper objects in the target bucket (Closure by default)
         crate::memory_manager::add_work_packet(
             self.mmtk,
             WorkBucketStage::PinningRootsTrace,
-            ProcessRootNode::<VM, PPE, DPE>::new(nodes, WorkBucketStage::Closure),
+            ProcessRootNode::<VM, PPE, DPE>::new_depth_limited(
+                nodes,
+                self.stage,
+                WorkBucketStage::PinningRootsTrace,
+                depth,
+            ),
         );
     }
 
@@ -794,9 +1298,12 @@ impl<VM: VMBinding, DPE: ProcessEdgesWork<VM = VM>, PPE: ProcessEdgesWork<VM = V
 impl<VM: VMBinding, DPE: ProcessEdgesWork<VM = VM>, PPE: ProcessEdgesWork<VM = VM>>
     ProcessEdgesWorkRootsWorkFactory<VM, DPE, PPE>
 {
-    fn new(mmtk: &'static MMTK<VM>) -> Self {
+    /// Create a factory that processes roots in `stage`.  Root scanning packets use the `Closure`
+    /// stage unless a plan asks for another stage.
+    fn with_stage(mmtk: &'static MMTK<VM>, stage: WorkBucketStage) -> Self {
         Self {
             mmtk,
+            stage,
             phantom: PhantomData,
         }
     }
@@ -842,23 +1349,24 @@ pub trait ScanObjectsWork<VM: VMBinding>: GCWork<VM> + Sized {
         let mut scan_later = vec![];
         {
             let mut closure = ObjectsClosure::<Self::E>::new(worker, self.get_bucket());
-
-            // For any object we need to scan, we count its live bytes.
-            // Check the option outside the loop for better performance.
-            if crate::util::rust_util::unlikely(*mmtk.get_options().count_live_bytes_in_gc) {
-                // Borrow before the loop.
-                let mut live_bytes_stats = closure.worker.shared.live_bytes_per_space.borrow_mut();
-                for object in objects_to_scan.iter().copied() {
-                    crate::scheduler::worker::GCWorkerShared::<VM>::increase_live_bytes(
-                        &mut live_bytes_stats,
-                        object,
-                    );
-                }
+            let record_heap_edges = *mmtk.get_options().record_heap_edges;
+            if crate::util::rust_util::unlikely(record_heap_edges) {
+                closure.enable_edge_recording();
             }
 
+            // Count the scanned objects.  This is always done, as it is much cheaper than counting
+            // live bytes, which is only done if the option is set.
+            closure
+                .worker
+                .shared
+                .count_scanned_objects(objects_to_scan, *mmtk.get_options().count_live_bytes_in_gc);
+
             for object in objects_to_scan.iter().copied() {
                 if <VM as VMBinding>::VMScanning::support_slot_enqueuing(tls, object) {
                     trace!("Scan object (slot) {}", object);
+                    if record_heap_edges {
+                        closure.set_current_object(object);
+                    }
                     // If an object supports slot-enqueuing, we enqueue its slots.
                     <VM as VMBinding>::VMScanning::scan_object(tls, object, &mut closure);
                     self.post_scan_object(object);
@@ -914,15 +1422,25 @@ pub struct ScanObjects<Edges: ProcessEdgesWork> {
     concurrent: bool,
     phantom: PhantomData<Edges>,
     bucket: WorkBucketStage,
+    max_objects: Option<usize>,
 }
 
 impl<Edges: ProcessEdgesWork> ScanObjects<Edges> {
-    pub fn new(buffer: Vec<ObjectReference>, concurrent: bool, bucket: WorkBucketStage) -> Self {
+    /// Create a `ScanObjects` work packet to scan the objects in `buffer`.  If `max_objects` is
+    /// `Some`, the packet scans at most that many objects, and the rest of the objects are scanned
+    /// by new packets added to the same bucket.
+    pub fn new(
+        buffer: Vec<ObjectReference>,
+        concurrent: bool,
+        bucket: WorkBucketStage,
+        max_objects: Option<usize>,
+    ) -> Self {
         Self {
             buffer,
             concurrent,
             phantom: PhantomData,
             bucket,
+            max_objects,
         }
     }
 }
@@ -942,11 +1460,45 @@ impl<VM: VMBinding, E: ProcessEdgesWork<VM = VM>> ScanObjectsWork<VM> for ScanOb
 impl<E: ProcessEdgesWork> GCWork<E::VM> for ScanObjects<E> {
     fn do_work(&mut self, worker: &mut GCWorker<E::VM>, mmtk: &'static MMTK<E::VM>) {
         trace!("ScanObjects");
+        if *mmtk.get_options().sort_scan_buffer {
+            self.buffer.sort_unstable();
+        }
+        let remainder = split_scan_buffer(&mut self.buffer, self.max_objects);
+        if !remainder.is_empty() {
+            let packets = remainder
+                .into_iter()
+                .map(|buffer| {
+                    Box::new(Self::new(
+                        buffer,
+                        self.concurrent,
+                        self.bucket,
+                        self.max_objects,
+                    )) as Box<dyn GCWork<E::VM>>
+                })
+                .collect();
+            worker.scheduler().work_buckets[self.bucket].bulk_add(packets);
+        }
         self.do_work_common(&self.buffer, worker, mmtk);
         trace!("ScanObjects End");
     }
 }
 
+/// If `buffer` has more than `max_objects` objects, truncate it to `max_objects` objects, and
+/// return the rest of the objects in chunks of at most `max_objects` objects each.
+fn split_scan_buffer(
+    buffer: &mut Vec<ObjectReference>,
+    max_objects: Option<usize>,
+) -> Vec<Vec<ObjectReference>> {
+    match max_objects {
+        Some(max_objects) if buffer.len() > max_objects => buffer
+            .split_off(max_objects)
+            .chunks(max_objects)
+            .map(<[ObjectReference]>::to_vec)
+            .collect(),
+        _ => vec![],
+    }
+}
+
 use crate::mmtk::MMTK;
 use crate::plan::Plan;
 use crate::plan::PlanTraceObject;
@@ -982,7 +1534,13 @@ impl<VM: VMBinding, P: PlanTraceObject<VM> + Plan<VM = VM>, const KIND: TraceKin
 
     fn create_scan_work(&self, nodes: Vec<ObjectReference>) -> Self::ScanObjectsWorkType {
         if cfg!(not(feature = "edge_enqueuing")) {
-            PlanScanObjects::<Self, P>::new(self.plan, nodes, false, self.bucket)
+            PlanScanObjects::<Self, P>::new(
+                self.plan,
+                nodes,
+                false,
+                self.bucket,
+                self.max_objects_per_scan_packet(),
+            )
         } else {
             unreachable!()
         }
@@ -1000,13 +1558,21 @@ impl<VM: VMBinding, P: PlanTraceObject<VM> + Plan<VM = VM>, const KIND: TraceKin
     }
 
     fn process_slot(&mut self, slot: SlotOf<Self>) {
-        let Some(object) = slot.load() else {
-            // Skip slots that are not holding an object reference.
+        let Some(object) = slot
+            .load()
+            .and_then(<Self::VM as VMBinding>::VMScanning::filter_slot_object)
+        else {
+            // Skip slots that are not holding an object reference, or rejected by the filter.
             return;
         };
+        if Self::FILTER_NON_MMTK && !Self::in_mmtk_heap(object) {
+            // Skip slots pointing outside the MMTk heap.
+            return;
+        }
         let new_object = self.trace_object(object);
         if P::may_move_objects::<KIND>() && new_object != object {
             slot.store(new_object);
+            self.count_updated_slot();
         }
     }
 
@@ -1038,7 +1604,8 @@ impl<VM: VMBinding, P: PlanTraceObject<VM> + Plan<VM = VM>, const KIND: TraceKin
             let Some(_) = slot.load() else { return };
             self.slots.push(slot);
             self.pushes += 1;
-            if self.slots.len() >= Self::CAPACITY || self.pushes >= (Self::CAPACITY / 2) as u32 {
+            let capacity = Self::capacity(self.bucket);
+            if self.slots.len() >= capacity || self.pushes >= (capacity / 2) as u32 {
                 self.flush_half();
             }
         };
@@ -1095,14 +1662,17 @@ pub struct PlanScanObjects<E: ProcessEdgesWork, P: Plan<VM = E::VM> + PlanTraceO
     concurrent: bool,
     phantom: PhantomData<E>,
     bucket: WorkBucketStage,
+    max_objects: Option<usize>,
 }
 
 impl<E: ProcessEdgesWork, P: Plan<VM = E::VM> + PlanTraceObject<E::VM>> PlanScanObjects<E, P> {
+    /// Create a `PlanScanObjects` work packet.  See [`ScanObjects::new`] for `max_objects`.
     pub fn new(
         plan: &'static P,
         buffer: Vec<ObjectReference>,
         concurrent: bool,
         bucket: WorkBucketStage,
+        max_objects: Option<usize>,
     ) -> Self {
         Self {
             plan,
@@ -1110,6 +1680,7 @@ impl<E: ProcessEdgesWork, P: Plan<VM = E::VM> + PlanTraceObject<E::VM>> PlanScan
             concurrent,
             phantom: PhantomData,
             bucket,
+            max_objects,
         }
     }
 }
@@ -1133,6 +1704,25 @@ impl<E: ProcessEdgesWork, P: Plan<VM = E::VM> + PlanTraceObject<E::VM>> GCWork<E
 {
     fn do_work(&mut self, worker: &mut GCWorker<E::VM>, mmtk: &'static MMTK<E::VM>) {
         trace!("PlanScanObjects");
+        if *mmtk.get_options().sort_scan_buffer {
+            self.buffer.sort_unstable();
+        }
+        let remainder = split_scan_buffer(&mut self.buffer, self.max_objects);
+        if !remainder.is_empty() {
+            let packets = remainder
+                .into_iter()
+                .map(|buffer| {
+                    Box::new(Self::new(
+                        self.plan,
+                        buffer,
+                        self.concurrent,
+                        self.bucket,
+                        self.max_objects,
+                    )) as Box<dyn GCWork<E::VM>>
+                })
+                .collect();
+            worker.scheduler().work_buckets[self.bucket].bulk_add(packets);
+        }
         self.do_work_common(&self.buffer, worker, mmtk);
         trace!("PlanScanObjects End");
     }
@@ -1157,6 +1747,22 @@ impl<E: ProcessEdgesWork, P: Plan<VM = E::VM> + PlanTraceObject<E::VM>> GCWork<E
 /// -   If `O2OPE` may move objects, then this `ProcessRootsNode<VM, R2OPE, O2OPE>` work packet
 ///     will only pin the objects in `roots` (because `R2OPE` must not move objects anyway), but
 ///     not their descendents.
+///
+/// A packet created with [`ProcessRootNode::new_depth_limited`] sits between the two.  It pins
+/// `roots` and their descendents down to `max_depth` edges away from the roots, and lets deeper
+/// objects move.  The trace kind is a compile-time parameter of `ProcessEdgesWork`, so it cannot
+/// change from object to object.  Instead, the depth is carried by the packets themselves:
+///
+/// -   A packet at `depth < max_depth` traces its objects with `R2OPE`, scans the newly marked
+///     ones, and creates another `ProcessRootNode` at `depth + 1` in `pin_bucket` holding their
+///     children.
+/// -   A packet at `depth == max_depth` traces its objects with `R2OPE`, and hands the newly marked
+///     ones over to `O2OPE`, as a non-transitively pinning root packet does.
+///
+/// All packets at `depth <= max_depth` run in `pin_bucket`, which must be opened before any
+/// object may be moved by `O2OPE`.  An object reachable from several roots is assigned the depth
+/// of whichever packet marks it first, so the depth limit is not exact when roots share
+/// descendents.  Pinning more is always safe.
 pub(crate) struct ProcessRootNode<
     VM: VMBinding,
     R2OPE: ProcessEdgesWork<VM = VM>,
@@ -1165,18 +1771,79 @@ pub(crate) struct ProcessRootNode<
     phantom: PhantomData<(VM, R2OPE, O2OPE)>,
     roots: Vec<ObjectReference>,
     bucket: WorkBucketStage,
+    /// The bucket for the packets that pin the descendents of `roots`.
+    pin_bucket: WorkBucketStage,
+    /// The number of edges between the actual roots and the objects in `roots`.
+    depth: usize,
+    /// The maximum depth of objects that will be pinned.
+    max_depth: usize,
 }
 
 impl<VM: VMBinding, R2OPE: ProcessEdgesWork<VM = VM>, O2OPE: ProcessEdgesWork<VM = VM>>
     ProcessRootNode<VM, R2OPE, O2OPE>
 {
     pub fn new(nodes: Vec<ObjectReference>, bucket: WorkBucketStage) -> Self {
+        Self::new_depth_limited(nodes, bucket, WorkBucketStage::PinningRootsTrace, 0)
+    }
+
+    /// Create a packet that pins `nodes` and their descendents down to `max_depth` edges away
+    /// from `nodes`.  The pinning packets run in `pin_bucket`, and objects deeper than `max_depth`
+    /// are traced with `O2OPE` in `bucket`.
+    pub fn new_depth_limited(
+        nodes: Vec<ObjectReference>,
+        bucket: WorkBucketStage,
+        pin_bucket: WorkBucketStage,
+        max_depth: usize,
+    ) -> Self {
         Self {
             phantom: PhantomData,
             roots: nodes,
             bucket,
+            pin_bucket,
+            depth: 0,
+            max_depth,
         }
     }
+
+    /// Scan `objects` and collect the objects they point to.
+    ///
+    /// All objects found here will be traced by a `R2OPE` which never moves objects, so the
+    /// reference fields do not need to be updated.
+    fn scan_children(
+        &self,
+        objects: &[ObjectReference],
+        worker: &mut GCWorker<VM>,
+        mmtk: &'static MMTK<VM>,
+    ) -> Vec<ObjectReference> {
+        let tls = worker.tls;
+        worker
+            .shared
+            .count_scanned_objects(objects, *mmtk.get_options().count_live_bytes_in_gc);
+
+        // Only used for its policy-specific `post_scan_object` hook.
+        let scan_work = R2OPE::new(vec![], false, mmtk, self.pin_bucket).create_scan_work(vec![]);
+        let mut children = vec![];
+        for object in objects.iter().copied() {
+            if <VM as VMBinding>::VMScanning::support_slot_enqueuing(tls, object) {
+                <VM as VMBinding>::VMScanning::scan_object(tls, object, &mut |slot: VM::VMSlot| {
+                    if let Some(child) = slot.load() {
+                        children.push(child);
+                    }
+                });
+            } else {
+                <VM as VMBinding>::VMScanning::scan_object_and_trace_edges(
+                    tls,
+                    object,
+                    &mut |child| {
+                        children.push(child);
+                        child
+                    },
+                );
+            }
+            scan_work.post_scan_object(object);
+        }
+        children
+    }
 }
 
 impl<VM: VMBinding, R2OPE: ProcessEdgesWork<VM = VM>, O2OPE: ProcessEdgesWork<VM = VM>> GCWork<VM>
@@ -1187,11 +1854,14 @@ impl<VM: VMBinding, R2OPE: ProcessEdgesWork<VM = VM>, O2OPE: ProcessEdgesWork<VM
 
         #[cfg(feature = "sanity")]
         {
-            if !mmtk.is_in_sanity() {
-                mmtk.sanity_checker
-                    .lock()
-                    .unwrap()
-                    .add_root_nodes(self.roots.clone());
+            if self.depth == 0 && !mmtk.is_in_sanity() {
+                let roots = self
+                    .roots
+                    .iter()
+                    .copied()
+                    .map(VM::VMScanning::normalize_root_node_for_sanity)
+                    .collect();
+                mmtk.sanity_checker.lock().unwrap().add_root_nodes(roots);
             }
         }
 
@@ -1205,12 +1875,17 @@ impl<VM: VMBinding, R2OPE: ProcessEdgesWork<VM = VM>, O2OPE: ProcessEdgesWork<VM
         // first time.  We will create a work packet for scanning those roots.
         let scanned_root_objects = {
             // We create an instance of E to use its `trace_object` method and its object queue.
-            let mut process_edges_work =
-                R2OPE::new(vec![], true, mmtk, WorkBucketStage::PinningRootsTrace);
+            let mut process_edges_work = R2OPE::new(
+                vec![],
+                self.depth == 0,
+                mmtk,
+                WorkBucketStage::PinningRootsTrace,
+            );
             process_edges_work.set_worker(worker);
 
-            for object in self.roots.iter().copied() {
-                let new_object = process_edges_work.trace_object(object);
+            let mut new_roots = vec![];
+            process_edges_work.trace_objects(&self.roots, &mut new_roots);
+            for (object, new_object) in self.roots.iter().copied().zip(new_roots) {
                 debug_assert_eq!(
                     object, new_object,
                     "Object moved while tracing root unmovable root object: {} -> {}",
@@ -1220,17 +1895,38 @@ impl<VM: VMBinding, R2OPE: ProcessEdgesWork<VM = VM>, O2OPE: ProcessEdgesWork<VM
 
             // This contains root objects that are visited the first time.
             // It is sufficient to only scan these objects.
-            process_edges_work.nodes.take()
+            process_edges_work.pop_nodes()
         };
 
-        let process_edges_work = O2OPE::new(vec![], false, mmtk, self.bucket);
-        let work = process_edges_work.create_scan_work(scanned_root_objects);
-        crate::memory_manager::add_work_packet(mmtk, self.bucket, work);
+        if self.depth < self.max_depth {
+            // Pin the children, too.  They are one edge further away from the roots.
+            let children = self.scan_children(&scanned_root_objects, worker, mmtk);
+            if !children.is_empty() {
+                let work = ProcessRootNode::<VM, R2OPE, O2OPE> {
+                    phantom: PhantomData,
+                    roots: children,
+                    bucket: self.bucket,
+                    pin_bucket: self.pin_bucket,
+                    depth: self.depth + 1,
+                    max_depth: self.max_depth,
+                };
+                crate::memory_manager::add_work_packet(mmtk, self.pin_bucket, work);
+            }
+        } else {
+            let process_edges_work = O2OPE::new(vec![], false, mmtk, self.bucket);
+            let work = process_edges_work.create_scan_work(scanned_root_objects);
+            crate::memory_manager::add_work_packet(mmtk, self.bucket, work);
+        }
 
         trace!("ProcessRootNode End");
     }
 }
 
+/// The panic message of [`UnsupportedProcessEdges`].
+const UNSUPPORTED_PROCESS_EDGES_MESSAGE: &str = "UnsupportedProcessEdges is used. \
+    The plan does not support this kind of tracing (e.g. transitively pinning roots), \
+    but its GCWorkContext was asked to process slots with it.";
+
 /// A `ProcessEdgesWork` type that panics when any of its method is used.
 /// This is currently used for plans that do not support transitively pinning.
 #[derive(Default)]
@@ -1241,13 +1937,13 @@ pub struct UnsupportedProcessEdges<VM: VMBinding> {
 impl<VM: VMBinding> Deref for UnsupportedProcessEdges<VM> {
     type Target = ProcessEdgesBase<VM>;
     fn deref(&self) -> &Self::Target {
-        panic!("unsupported!")
+        panic!("{}", UNSUPPORTED_PROCESS_EDGES_MESSAGE)
     }
 }
 
 impl<VM: VMBinding> DerefMut for UnsupportedProcessEdges<VM> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        panic!("unsupported!")
+        panic!("{}", UNSUPPORTED_PROCESS_EDGES_MESSAGE)
     }
 }
 
@@ -1262,14 +1958,14 @@ impl<VM: VMBinding> ProcessEdgesWork for UnsupportedProcessEdges<VM> {
         _mmtk: &'static MMTK<Self::VM>,
         _bucket: WorkBucketStage,
     ) -> Self {
-        panic!("unsupported!")
+        panic!("{}", UNSUPPORTED_PROCESS_EDGES_MESSAGE)
     }
 
-    fn trace_object(&mut self, _object: ObjectReference) -> ObjectReference {
-        panic!("unsupported!")
+    fn trace_object(&mut self, object: ObjectReference) -> ObjectReference {
+        panic!("{} Object: {}", UNSUPPORTED_PROCESS_EDGES_MESSAGE, object)
     }
 
     fn create_scan_work(&self, _nodes: Vec<ObjectReference>) -> Self::ScanObjectsWorkType {
-        panic!("unsupported!")
+        panic!("{}", UNSUPPORTED_PROCESS_EDGES_MESSAGE)
     }
 }
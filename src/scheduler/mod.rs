@@ -13,6 +13,8 @@ pub(crate) mod affinity;
 #[allow(clippy::module_inception)]
 mod scheduler;
 pub(crate) use scheduler::GCWorkScheduler;
+#[cfg(feature = "mock_test")]
+pub(crate) use scheduler::PolledPacket;
 
 mod stat;
 mod work_counter;
@@ -22,7 +24,7 @@ pub use work::GCWork;
 pub(crate) use work::GCWorkContext;
 
 mod work_bucket;
-pub use work_bucket::WorkBucketStage;
+pub use work_bucket::{CustomBucketId, WorkBucketStage};
 
 mod worker;
 mod worker_goals;
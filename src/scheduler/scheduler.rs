@@ -9,6 +9,7 @@ use super::worker_monitor::{LastParkedResult, WorkerMonitor};
 use super::*;
 use crate::global_state::GcStatus;
 use crate::mmtk::MMTK;
+use crate::util::conversions;
 use crate::util::opaque_pointer::*;
 use crate::util::options::AffinityKind;
 use crate::util::rust_util::array_from_fn;
@@ -18,18 +19,83 @@ use crate::Plan;
 use crossbeam::deque::Steal;
 use enum_map::{Enum, EnumMap};
 use std::collections::HashMap;
-use std::sync::Arc;
-use std::time::Instant;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, Instant};
+
+/// The maximum number of custom work buckets.
+const MAX_CUSTOM_BUCKETS: usize = 8;
+
+/// A work bucket registered by [`GCWorkScheduler::register_custom_bucket`].
+struct CustomBucket<VM: VMBinding> {
+    /// The stage after which this bucket is opened.
+    after: WorkBucketStage,
+    bucket: WorkBucket<VM>,
+}
+
+/// Identify a work bucket in log messages.
+#[derive(Debug, Copy, Clone)]
+enum BucketId {
+    Stage(WorkBucketStage),
+    Custom(CustomBucketId),
+}
+
+impl BucketId {
+    /// The numerical ID reported by the `bucket_opened` tracepoint.  Custom buckets are numbered
+    /// after all the stages.
+    fn as_usize(self) -> usize {
+        match self {
+            BucketId::Stage(stage) => stage.into_usize(),
+            BucketId::Custom(id) => WorkBucketStage::LENGTH + id.0,
+        }
+    }
+}
 
 pub struct GCWorkScheduler<VM: VMBinding> {
     /// Work buckets
     pub work_buckets: EnumMap<WorkBucketStage, WorkBucket<VM>>,
+    /// Custom work buckets, in the order of registration.  Only the first `num_custom_buckets`
+    /// entries are initialized.
+    custom_buckets: Vec<OnceLock<CustomBucket<VM>>>,
+    /// The number of registered custom buckets.
+    num_custom_buckets: AtomicUsize,
     /// Workers
     pub(crate) worker_group: Arc<WorkerGroup<VM>>,
     /// For synchronized communication between workers and with mutators.
     pub(crate) worker_monitor: Arc<WorkerMonitor>,
     /// How to assign the affinity of each GC thread. Specified by the user.
     affinity: AffinityKind,
+    /// Execute work packets one at a time in a deterministic order. See
+    /// `Options::deterministic_gc_scheduling`.
+    deterministic: bool,
+    /// Held by a worker while executing a work packet if `deterministic` is true.
+    deterministic_execution_lock: Mutex<()>,
+    /// Record the time spent in each stage. See `Options::record_bucket_times`.
+    record_bucket_times: bool,
+    /// Record the time each worker spends executing work packets.  See
+    /// `Options::record_worker_idle_time`.
+    record_worker_idle_time: bool,
+    /// Warn if a bucket holds more packets than this.  See `Options::work_packet_warn_threshold`.
+    packet_warn_threshold: usize,
+    /// The time each stage was opened in the current GC, in the order of opening.
+    bucket_open_times: Mutex<Vec<(WorkBucketStage, Instant)>>,
+    /// The time spent in each stage in the last GC, in the order of opening.
+    last_gc_bucket_times: Mutex<Vec<(WorkBucketStage, Duration)>>,
+    /// The packets taken from the stage buckets, in the order they were taken, with the ordinal of
+    /// the worker that took each of them.  Packets moved to a local queue along with a polled
+    /// packet are not recorded, but that does not happen in deterministic mode or for buckets with
+    /// worker masks.  Only mock tests read this.
+    #[cfg(feature = "mock_test")]
+    pub(crate) polled_packets: Mutex<Vec<PolledPacket>>,
+}
+
+/// A record in [`GCWorkScheduler::polled_packets`].
+#[cfg(feature = "mock_test")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PolledPacket {
+    pub stage: WorkBucketStage,
+    pub ordinal: ThreadId,
+    pub type_name: &'static str,
 }
 
 // FIXME: GCWorkScheduler should be naturally Sync, but we cannot remove this `impl` yet.
@@ -38,8 +104,17 @@ pub struct GCWorkScheduler<VM: VMBinding> {
 unsafe impl<VM: VMBinding> Sync for GCWorkScheduler<VM> {}
 
 impl<VM: VMBinding> GCWorkScheduler<VM> {
-    pub fn new(num_workers: usize, affinity: AffinityKind) -> Arc<Self> {
-        let worker_monitor: Arc<WorkerMonitor> = Arc::new(WorkerMonitor::new(num_workers));
+    pub fn new(
+        num_workers: usize,
+        affinity: AffinityKind,
+        deterministic: bool,
+        record_bucket_times: bool,
+        record_worker_idle_time: bool,
+        worker_masks: Vec<(WorkBucketStage, Vec<bool>)>,
+        packet_warn_threshold: usize,
+    ) -> Arc<Self> {
+        let worker_monitor: Arc<WorkerMonitor> =
+            Arc::new(WorkerMonitor::new(num_workers, deterministic));
         let worker_group = WorkerGroup::new(num_workers);
 
         // Create work buckets for workers.
@@ -47,9 +122,16 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
         let mut work_buckets = EnumMap::from_array(array_from_fn(|stage_num| {
             let stage = WorkBucketStage::from_usize(stage_num);
             let active = stage == WorkBucketStage::Unconstrained;
-            WorkBucket::new(active, worker_monitor.clone())
+            let mut bucket = WorkBucket::new(active, worker_monitor.clone());
+            bucket.set_packet_warn_threshold(packet_warn_threshold);
+            bucket
         }));
 
+        // Restrict buckets to subsets of workers.
+        for (stage, mask) in worker_masks {
+            work_buckets[stage].set_worker_mask(mask);
+        }
+
         // Set the open condition of each bucket.
         {
             let first_stw_stage = WorkBucketStage::first_stw_stage();
@@ -61,11 +143,15 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
                 // (i.e. when all mutators are suspended).
                 if stage != WorkBucketStage::Unconstrained && stage != first_stw_stage {
                     // Other work packets will be opened after previous stages are done
-                    // (i.e their buckets are drained and all workers parked).
+                    // (i.e their buckets are drained and all workers parked), including custom
+                    // buckets registered after previous stages.
                     let cur_stages = open_stages.clone();
                     work_buckets[stage].set_open_condition(
                         move |scheduler: &GCWorkScheduler<VM>| {
                             scheduler.are_buckets_drained(&cur_stages)
+                                && scheduler.are_custom_buckets_drained(|after| {
+                                    after.into_usize() < stage.into_usize()
+                                })
                         },
                     );
                     open_stages.push(stage);
@@ -75,12 +161,121 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
 
         Arc::new(Self {
             work_buckets,
+            custom_buckets: (0..MAX_CUSTOM_BUCKETS).map(|_| OnceLock::new()).collect(),
+            num_custom_buckets: AtomicUsize::new(0),
             worker_group,
             worker_monitor,
             affinity,
+            deterministic,
+            deterministic_execution_lock: Mutex::new(()),
+            record_bucket_times,
+            record_worker_idle_time,
+            packet_warn_threshold,
+            bucket_open_times: Mutex::new(vec![]),
+            last_gc_bucket_times: Mutex::new(vec![]),
+            #[cfg(feature = "mock_test")]
+            polled_packets: Mutex::new(vec![]),
         })
     }
 
+    /// Register a custom work bucket which is opened after the bucket of the stage `after` is
+    /// drained, and before the bucket of the next stage is opened.  If multiple custom buckets are
+    /// registered after the same stage, they are opened in the order of registration.
+    ///
+    /// This must be called before the first GC, usually when creating the plan.
+    pub fn register_custom_bucket(&self, after: WorkBucketStage) -> CustomBucketId {
+        assert!(
+            after != WorkBucketStage::Unconstrained && after != WorkBucketStage::Final,
+            "Cannot register a custom bucket after {:?}",
+            after
+        );
+        let index = self.num_custom_buckets.load(Ordering::SeqCst);
+        assert!(
+            index < MAX_CUSTOM_BUCKETS,
+            "Too many custom buckets. At most {} custom buckets can be registered.",
+            MAX_CUSTOM_BUCKETS
+        );
+
+        // The bucket is opened after all the stages up to `after`, and all the custom buckets
+        // registered before it that are opened no later than `after`, are drained.
+        let prev_stages: Vec<WorkBucketStage> = (WorkBucketStage::first_stw_stage().into_usize()
+            ..=after.into_usize())
+            .map(WorkBucketStage::from_usize)
+            .collect();
+        let mut bucket = WorkBucket::new(false, self.worker_monitor.clone());
+        bucket.set_packet_warn_threshold(self.packet_warn_threshold);
+        bucket.set_open_condition(move |scheduler: &GCWorkScheduler<VM>| {
+            scheduler.are_buckets_drained(&prev_stages)
+                && scheduler
+                    .custom_buckets()
+                    .take(index)
+                    .filter(|(_, custom)| custom.after.into_usize() <= after.into_usize())
+                    .all(|(_, custom)| custom.bucket.is_drained())
+        });
+
+        if self.custom_buckets[index]
+            .set(CustomBucket { after, bucket })
+            .is_err()
+        {
+            panic!("Custom buckets are registered concurrently.");
+        }
+        self.num_custom_buckets.store(index + 1, Ordering::SeqCst);
+        CustomBucketId(index)
+    }
+
+    /// Get a custom work bucket registered by [`GCWorkScheduler::register_custom_bucket`].
+    pub fn custom_bucket(&self, id: CustomBucketId) -> &WorkBucket<VM> {
+        &self.custom_buckets[id.0]
+            .get()
+            .expect("Custom bucket not registered")
+            .bucket
+    }
+
+    /// Iterate over the registered custom buckets in the order of registration.
+    fn custom_buckets(&self) -> impl Iterator<Item = (CustomBucketId, &CustomBucket<VM>)> {
+        self.custom_buckets
+            .iter()
+            .take(self.num_custom_buckets.load(Ordering::SeqCst))
+            .enumerate()
+            .map(|(i, custom)| (CustomBucketId(i), custom.get().unwrap()))
+    }
+
+    /// Return true if all the custom buckets registered after the stages that satisfy `pred` are
+    /// drained.
+    fn are_custom_buckets_drained(&self, pred: impl Fn(WorkBucketStage) -> bool) -> bool {
+        self.custom_buckets()
+            .filter(|(_, custom)| pred(custom.after))
+            .all(|(_, custom)| custom.bucket.is_drained())
+    }
+
+    /// Iterate over all the work buckets, including custom buckets, in the order they are opened.
+    fn all_buckets(&self) -> impl Iterator<Item = (BucketId, &WorkBucket<VM>)> {
+        self.work_buckets.iter().flat_map(move |(stage, bucket)| {
+            std::iter::once((BucketId::Stage(stage), bucket)).chain(
+                self.custom_buckets()
+                    .filter(move |(_, custom)| custom.after == stage)
+                    .map(|(id, custom)| (BucketId::Custom(id), &custom.bucket)),
+            )
+        })
+    }
+
+    /// Return true if work packets are executed one at a time in a deterministic order.
+    pub fn is_deterministic(&self) -> bool {
+        self.deterministic
+    }
+
+    /// Return true if the workers should measure the time spent executing work packets.
+    pub fn is_recording_worker_idle_time(&self) -> bool {
+        self.record_worker_idle_time
+    }
+
+    /// Serialize the execution of work packets in deterministic mode.  The worker should hold the
+    /// returned guard while executing a work packet.
+    pub(crate) fn lock_for_deterministic_execution(&self) -> MutexGuard<()> {
+        debug_assert!(self.deterministic);
+        self.deterministic_execution_lock.lock().unwrap()
+    }
+
     pub fn num_workers(&self) -> usize {
         self.worker_group.as_ref().worker_count()
     }
@@ -147,6 +342,10 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
         // Prepare global/collectors/mutators
         self.work_buckets[WorkBucketStage::Prepare].add(Prepare::<C>::new(plan));
 
+        // Check the SFT map against the spaces before we trace with it
+        #[cfg(feature = "extreme_assertions")]
+        self.work_buckets[WorkBucketStage::Prepare].add(VerifySFT);
+
         // Release global/collectors/mutators
         self.work_buckets[WorkBucketStage::Release].add(Release::<C>::new(plan));
 
@@ -232,18 +431,75 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
         self.work_buckets[WorkBucketStage::Release].add(VMPostForwarding::<VM>::default());
     }
 
+    /// Merge small pending [`ProcessEdgesWork`] packets of type `E` in the given `bucket` so that
+    /// each resulting packet has at most `max_packet_size` slots.  This reduces the scheduling
+    /// overhead when a bucket is filled with many packets that only hold a handful of slots.
+    ///
+    /// Only packets of type `E` with the same `roots` flag are merged together.  Packets of other
+    /// types, and packets of type `E` that already hold `max_packet_size` slots or more, are put
+    /// back into the bucket unchanged.
+    ///
+    /// This must be called before `bucket` is opened, for example, from a custom
+    /// [`Plan::schedule_collection`].  It panics if `bucket` is already open.
+    pub fn coalesce_pending_process_edges<E: ProcessEdgesWork<VM = VM>>(
+        &self,
+        bucket: WorkBucketStage,
+        max_packet_size: usize,
+    ) {
+        assert!(
+            !self.work_buckets[bucket].is_activated(),
+            "Cannot coalesce work packets in an open bucket {:?}",
+            bucket
+        );
+
+        let mut result: Vec<Box<dyn GCWork<VM>>> = vec![];
+        // The packet currently being merged into, one for non-root and one for root packets.
+        let mut merging: [Option<Box<E>>; 2] = [None, None];
+
+        for work in self.work_buckets[bucket].take_all() {
+            let mut packet = match work.downcast::<E>() {
+                Ok(packet) if packet.slots.len() < max_packet_size => packet,
+                Ok(packet) => {
+                    result.push(packet);
+                    continue;
+                }
+                Err(work) => {
+                    result.push(work);
+                    continue;
+                }
+            };
+            let target = &mut merging[packet.roots as usize];
+            match target {
+                Some(current) if current.slots.len() + packet.slots.len() <= max_packet_size => {
+                    let slots = std::mem::take(&mut packet.slots);
+                    current.slots.extend(slots);
+                }
+                _ => {
+                    if let Some(full) = target.replace(packet) {
+                        result.push(full);
+                    }
+                }
+            }
+        }
+        for packet in merging.into_iter().flatten() {
+            result.push(packet);
+        }
+
+        self.work_buckets[bucket].bulk_add(result);
+    }
+
     fn are_buckets_drained(&self, buckets: &[WorkBucketStage]) -> bool {
         buckets.iter().all(|&b| self.work_buckets[b].is_drained())
     }
 
     pub fn all_buckets_empty(&self) -> bool {
-        self.work_buckets.values().all(|bucket| bucket.is_empty())
+        self.all_buckets().all(|(_, bucket)| bucket.is_empty())
     }
 
     /// Schedule "sentinel" work packets for all activated buckets.
     pub(crate) fn schedule_sentinels(&self) -> bool {
         let mut new_packets = false;
-        for (id, work_bucket) in self.work_buckets.iter() {
+        for (id, work_bucket) in self.all_buckets() {
             if work_bucket.is_activated() && work_bucket.maybe_schedule_sentinel() {
                 trace!("Scheduled sentinel packet into {:?}", id);
                 new_packets = true;
@@ -261,16 +517,17 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
     pub(crate) fn update_buckets(&self) -> bool {
         let mut buckets_updated = false;
         let mut new_packets = false;
-        for i in 0..WorkBucketStage::LENGTH {
-            let id = WorkBucketStage::from_usize(i);
-            if id == WorkBucketStage::Unconstrained {
+        for (id, bucket) in self.all_buckets() {
+            if matches!(id, BucketId::Stage(WorkBucketStage::Unconstrained)) {
                 continue;
             }
-            let bucket = &self.work_buckets[id];
             let bucket_opened = bucket.update(self);
             buckets_updated = buckets_updated || bucket_opened;
             if bucket_opened {
-                probe!(mmtk, bucket_opened, id);
+                probe!(mmtk, bucket_opened, id.as_usize());
+                if let BucketId::Stage(stage) = id {
+                    self.record_bucket_opened(stage);
+                }
                 new_packets = new_packets || !bucket.is_drained();
                 if new_packets {
                     // Quit the loop. There are already new packets in the newly opened buckets.
@@ -288,9 +545,46 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
         buckets_updated && new_packets
     }
 
+    /// Record the time when the bucket of a stage is opened if `record_bucket_times` is enabled.
+    fn record_bucket_opened(&self, stage: WorkBucketStage) {
+        if self.record_bucket_times {
+            self.bucket_open_times
+                .lock()
+                .unwrap()
+                .push((stage, Instant::now()));
+        }
+    }
+
+    /// Compute the time spent in each stage from the time the stages were opened.  A stage ends
+    /// when the next stage is opened, or when the GC finishes.
+    fn compute_bucket_times(&self) {
+        if !self.record_bucket_times {
+            return;
+        }
+        let open_times = std::mem::take(&mut *self.bucket_open_times.lock().unwrap());
+        let end_time = Instant::now();
+        let bucket_times = open_times
+            .iter()
+            .enumerate()
+            .map(|(i, &(stage, start))| {
+                let end = open_times.get(i + 1).map_or(end_time, |&(_, next)| next);
+                (stage, end - start)
+            })
+            .collect();
+        *self.last_gc_bucket_times.lock().unwrap() = bucket_times;
+    }
+
+    /// Get the time spent in each stage in the last GC, in the order the stages were opened.  The
+    /// time of a stage is measured from when its bucket was opened until the next bucket was
+    /// opened (i.e. when all the work of the stage was done), or until the GC finished.  This is
+    /// empty if `record_bucket_times` is not enabled.
+    pub(crate) fn last_gc_bucket_times(&self) -> Vec<(WorkBucketStage, Duration)> {
+        self.last_gc_bucket_times.lock().unwrap().clone()
+    }
+
     pub fn deactivate_all(&self) {
-        self.work_buckets.iter().for_each(|(id, bkt)| {
-            if id != WorkBucketStage::Unconstrained {
+        self.all_buckets().for_each(|(id, bkt)| {
+            if !matches!(id, BucketId::Stage(WorkBucketStage::Unconstrained)) {
                 bkt.deactivate();
             }
         });
@@ -298,8 +592,9 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
 
     pub fn reset_state(&self) {
         let first_stw_stage = WorkBucketStage::first_stw_stage();
-        self.work_buckets.iter().for_each(|(id, bkt)| {
-            if id != WorkBucketStage::Unconstrained && id != first_stw_stage {
+        self.all_buckets().for_each(|(id, bkt)| {
+            if !matches!(id, BucketId::Stage(stage) if stage == WorkBucketStage::Unconstrained || stage == first_stw_stage)
+            {
                 bkt.deactivate();
             }
         });
@@ -307,8 +602,8 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
 
     pub fn debug_assert_all_buckets_deactivated(&self) {
         if cfg!(debug_assertions) {
-            self.work_buckets.iter().for_each(|(id, bkt)| {
-                if id != WorkBucketStage::Unconstrained {
+            self.all_buckets().for_each(|(id, bkt)| {
+                if !matches!(id, BucketId::Stage(WorkBucketStage::Unconstrained)) {
                     assert!(!bkt.is_activated());
                 }
             });
@@ -318,7 +613,7 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
     /// Check if all the work buckets are empty
     pub(crate) fn assert_all_activated_buckets_are_empty(&self) {
         let mut error_example = None;
-        for (id, bucket) in self.work_buckets.iter() {
+        for (id, bucket) in self.all_buckets() {
             if bucket.is_activated() && !bucket.is_empty() {
                 error!("Work bucket {:?} is active but not empty!", id);
                 // This error can be hard to reproduce.
@@ -333,6 +628,31 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
         }
     }
 
+    /// Get the number of work packets pending in the bucket of the given stage.  See
+    /// [`WorkBucket::len`].
+    pub fn pending_work_count(&self, stage: WorkBucketStage) -> usize {
+        self.work_buckets[stage].len()
+    }
+
+    /// Get the total number of work packets pending in all the work buckets, including custom
+    /// buckets.  See [`WorkBucket::len`].
+    pub fn total_pending_work_count(&self) -> usize {
+        self.all_buckets().map(|(_, bucket)| bucket.len()).sum()
+    }
+
+    /// Return true if some activated buckets are not empty, but `worker` is not allowed to poll
+    /// packets from them, either because of deterministic scheduling or worker masks.
+    fn has_work_reserved_for_other_workers(&self, worker: &GCWorker<VM>) -> bool {
+        if self.deterministic && worker.ordinal != 0 {
+            return self
+                .all_buckets()
+                .any(|(_, bucket)| bucket.is_activated() && !bucket.is_empty());
+        }
+        self.work_buckets.values().any(|bucket| {
+            bucket.is_activated() && !bucket.is_empty() && !bucket.is_worker_allowed(worker.ordinal)
+        })
+    }
+
     /// Get a schedulable work packet without retry.
     fn poll_schedulable_work_once(&self, worker: &GCWorker<VM>) -> Steal<Box<dyn GCWork<VM>>> {
         let mut should_retry = false;
@@ -340,9 +660,41 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
         if let Some(w) = worker.shared.designated_work.pop() {
             return Steal::Success(w);
         }
-        // Try get a packet from a work bucket.
-        for work_bucket in self.work_buckets.values() {
-            match work_bucket.poll(&worker.local_work_buffer) {
+        // In deterministic mode, only the first worker executes packets from work buckets.
+        if self.deterministic && worker.ordinal != 0 {
+            return Steal::Empty;
+        }
+        // Try get a packet from a work bucket.  In deterministic mode, take one packet at a time
+        // so that the first worker executes them in the order of the buckets.
+        let poll_bucket = |bucket: &WorkBucket<VM>| {
+            if self.deterministic {
+                bucket.poll_one()
+            } else {
+                bucket.poll(&worker.local_work_buffer)
+            }
+        };
+        // `stage` is only used by mock tests.
+        #[allow(unused_variables)]
+        for (stage, work_bucket) in self.work_buckets.iter() {
+            if !work_bucket.is_worker_allowed(worker.ordinal) {
+                continue;
+            }
+            match poll_bucket(work_bucket) {
+                Steal::Success(w) => {
+                    #[cfg(feature = "mock_test")]
+                    self.polled_packets.lock().unwrap().push(PolledPacket {
+                        stage,
+                        ordinal: worker.ordinal,
+                        type_name: w.get_type_name(),
+                    });
+                    return Steal::Success(w);
+                }
+                Steal::Retry => should_retry = true,
+                _ => {}
+            }
+        }
+        for (_, custom) in self.custom_buckets() {
+            match poll_bucket(&custom.bucket) {
                 Steal::Success(w) => return Steal::Success(w),
                 Steal::Retry => should_retry = true,
                 _ => {}
@@ -429,6 +781,15 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
                 // We are in the middle of GC, and the last GC worker parked.
                 trace!("The last worker parked during GC.  Try to find more work to do...");
 
+                // In deterministic mode or with worker masks, some packets can only be executed by
+                // other workers.  The workers that can execute them may have parked before the
+                // packets were added.  Wake them up instead of concluding that the buckets have
+                // been drained.
+                if self.has_work_reserved_for_other_workers(worker) {
+                    trace!("Some packets can only be executed by other workers.  Wake them up.");
+                    return LastParkedResult::WakeOthers;
+                }
+
                 // During GC, if all workers parked, all open buckets must have been drained.
                 self.assert_all_activated_buckets_are_empty();
 
@@ -483,7 +844,12 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
                 }
 
                 self.add_schedule_collection_packet();
-                LastParkedResult::WakeSelf
+                if self.deterministic {
+                    // This worker may not be the one that can execute the packet.
+                    LastParkedResult::WakeAll
+                } else {
+                    LastParkedResult::WakeSelf
+                }
             }
             WorkerGoal::StopForFork => {
                 trace!("A mutator wanted to fork.");
@@ -525,14 +891,33 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
         self.deactivate_all();
         self.debug_assert_all_buckets_deactivated();
 
+        self.compute_bucket_times();
+
         let mmtk = worker.mmtk;
 
         // Tell GC trigger that GC ended - this happens before we resume mutators.
+        let old_heap_pages = mmtk.gc_trigger.policy.get_current_heap_size_in_pages();
         mmtk.gc_trigger.policy.on_gc_end(mmtk);
+        let new_heap_pages = mmtk.gc_trigger.policy.get_current_heap_size_in_pages();
+        if new_heap_pages != old_heap_pages {
+            debug!(
+                "Heap size changed from {} pages to {} pages",
+                old_heap_pages, new_heap_pages
+            );
+            <VM as VMBinding>::VMCollection::on_heap_resize(
+                worker.tls,
+                conversions::pages_to_bytes(old_heap_pages),
+                conversions::pages_to_bytes(new_heap_pages),
+            );
+        }
 
         // All other workers are parked, so it is safe to access the Plan instance mutably.
         probe!(mmtk, plan_end_of_gc_begin);
         let plan_mut: &mut dyn Plan<VM = VM> = unsafe { mmtk.get_plan_mut() };
+        // Spaces that did defrag in this GC will set it in `end_of_gc`.
+        mmtk.state
+            .last_gc_was_defrag
+            .store(false, Ordering::Relaxed);
         plan_mut.end_of_gc(worker.tls);
         probe!(mmtk, plan_end_of_gc_end);
 
@@ -572,6 +957,82 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
             }
         }
 
+        // Aggregate the busy time of the workers.  The rest of the GC time of each worker is idle.
+        if self.record_worker_idle_time {
+            let busy_time = mmtk.scheduler.worker_group.get_and_clear_worker_busy_time();
+            let total_time = elapsed * self.num_workers() as u32;
+            let idle_time = total_time.saturating_sub(busy_time);
+            let efficiency = if total_time.is_zero() {
+                1.0
+            } else {
+                busy_time.min(total_time).as_secs_f64() / total_time.as_secs_f64()
+            };
+            debug!(
+                "Workers idled for {:?} ({:.1}% parallel efficiency)",
+                idle_time,
+                efficiency * 100.0
+            );
+            *mmtk.state.worker_idle_time_in_last_gc.lock().unwrap() = (idle_time, efficiency);
+            mmtk.stats
+                .worker_idle_time
+                .lock()
+                .unwrap()
+                .inc_by(idle_time.as_nanos() as u64);
+        }
+
+        // Aggregate the copy allocation statistics
+        let copy_alloc_stats = mmtk
+            .scheduler
+            .worker_group
+            .get_and_clear_worker_copy_stats();
+        debug!(
+            "Copy allocators acquired {} blocks, failed {} times",
+            copy_alloc_stats.blocks_acquired, copy_alloc_stats.block_acquisition_failures
+        );
+        *mmtk.state.copy_alloc_stats_in_last_gc.borrow_mut() = copy_alloc_stats;
+
+        if *mmtk.get_options().record_copy_breakdown {
+            let copy_breakdown = mmtk
+                .scheduler
+                .worker_group
+                .get_and_clear_worker_copy_breakdown();
+            *mmtk.state.copy_breakdown_in_last_gc.borrow_mut() = copy_breakdown
+                .into_iter()
+                .filter(|(_, bytes)| *bytes > 0)
+                .collect();
+        }
+
+        #[cfg(feature = "count_updated_slots")]
+        {
+            let updated_slots = mmtk
+                .scheduler
+                .worker_group
+                .get_and_clear_worker_updated_slots();
+            debug!("Updated {} slots to moved objects", updated_slots);
+            mmtk.state
+                .updated_slots_in_last_gc
+                .store(updated_slots, Ordering::Relaxed);
+        }
+
+        if *mmtk.get_options().record_heap_edges {
+            let heap_edges = mmtk
+                .scheduler
+                .worker_group
+                .get_and_clear_worker_heap_edges();
+            debug!("Recorded {} heap edges", heap_edges.len());
+            *mmtk.state.heap_edges_in_last_gc.borrow_mut() = heap_edges;
+        }
+
+        #[cfg(feature = "forwarding_log")]
+        {
+            let forwarding_log = mmtk
+                .scheduler
+                .worker_group
+                .get_and_clear_worker_forwarding_log();
+            debug!("Recorded {} forwarded objects", forwarding_log.len());
+            *mmtk.state.forwarding_log_in_last_gc.borrow_mut() = forwarding_log;
+        }
+
         #[cfg(feature = "extreme_assertions")]
         if crate::util::slot_logger::should_check_duplicate_slots(mmtk.get_plan()) {
             // reset the logging info at the end of each GC
@@ -603,6 +1064,16 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
     }
 
     pub fn notify_mutators_paused(&self, mmtk: &'static MMTK<VM>) {
+        if let Some(start) = mmtk.state.stop_mutators_start_time.borrow_mut().take() {
+            let time_to_safepoint = start.elapsed();
+            trace!("Mutators paused in {:?}", time_to_safepoint);
+            *mmtk.state.time_to_safepoint_in_last_gc.lock().unwrap() = time_to_safepoint;
+            mmtk.stats
+                .time_to_safepoint
+                .lock()
+                .unwrap()
+                .inc_by(time_to_safepoint.as_nanos() as u64);
+        }
         mmtk.gc_requester.clear_request();
         let first_stw_bucket = &self.work_buckets[WorkBucketStage::first_stw_stage()];
         debug_assert!(!first_stw_bucket.is_activated());
@@ -614,6 +1085,7 @@ impl<VM: VMBinding> GCWorkScheduler<VM> {
         // opening the first STW bucket.  In the future, we should redesign the opening condition
         // of work buckets to make the synchronization more robust,
         first_stw_bucket.activate();
+        self.record_bucket_opened(WorkBucketStage::first_stw_stage());
         self.worker_monitor.notify_work_available(true);
     }
 }
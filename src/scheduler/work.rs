@@ -1,6 +1,7 @@
 use super::worker::*;
 use crate::mmtk::MMTK;
 use crate::vm::VMBinding;
+use downcast_rs::{impl_downcast, Downcast};
 #[cfg(feature = "work_packet_stats")]
 use std::any::{type_name, TypeId};
 
@@ -11,7 +12,7 @@ use std::any::{type_name, TypeId};
 /// of the work packet will need to consider at least two points of tension: the work packet must be large
 /// enough to ensure that the costs of managing the work packets do not dominate, and the packet must be
 /// small enough that good load balancing is achieved.
-pub trait GCWork<VM: VMBinding>: 'static + Send {
+pub trait GCWork<VM: VMBinding>: 'static + Send + Downcast {
     /// Define the work for this packet. However, this is not supposed to be called directly.
     /// Usually `do_work_with_stat()` should be used.
     ///
@@ -58,6 +59,8 @@ pub trait GCWork<VM: VMBinding>: 'static + Send {
     }
 }
 
+impl_downcast!(GCWork<VM> where VM: VMBinding);
+
 use super::gc_work::ProcessEdgesWork;
 use crate::plan::Plan;
 
@@ -99,3 +102,30 @@ pub trait GCWorkContext: Send + 'static {
     /// type member.
     type PinningProcessEdges: ProcessEdgesWork<VM = Self::VM>;
 }
+
+// Running several closures in one GC
+// ----------------------------------
+//
+// A plan may run more than one transitive closure in the same GC, each with its own tracing
+// semantics (e.g. a pinning closure and a moving closure over disjoint object sets).  Each closure
+// is keyed by the pair of its `ProcessEdgesWork` type and the `WorkBucketStage` its packets are
+// added to.  `DefaultProcessEdges` and `PinningProcessEdges` are the two closures every context
+// provides, but a plan can schedule root packets of any other `ProcessEdgesWork` type `E` with
+// `E::new(slots, true, mmtk, stage)` and add them to `stage`.
+//
+// Packets never change closure.  `ProcessEdgesWork::create_scan_work` creates `ScanObjects<E>`
+// (or `PlanScanObjects<E, P>`), whose `ObjectsClosure<E>` flushes newly found slots as packets of
+// the same type `E` into the same bucket stage (`ProcessEdgesBase::bucket`).  Therefore the
+// packets of each closure stay with their own type until that closure quiesces.
+//
+// A bucket is only considered drained when none of its packets are left and all workers are
+// parked, regardless of which closure the packets belong to.  Later stages, including `Release`,
+// are not opened until every earlier stage is drained (see `GCWorkScheduler::update_buckets`).
+// Consequently, closures that share a stage reach their fixpoint together, and closures placed in
+// earlier stages (e.g. `PinningRootsTrace` and `TPinningClosure`) reach theirs before `Closure` is
+// opened.  Either way, every closure has reached its fixpoint before `Release`.
+//
+// Closures cannot yet be scheduled into custom buckets (see
+// `GCWorkScheduler::register_custom_bucket`), because `ProcessEdgesBase::bucket` is a
+// `WorkBucketStage` rather than a `BucketId`.  Plans that need more than one closure should use
+// distinct stages, or share one stage as described above.
@@ -21,6 +21,10 @@ impl<VM: VMBinding> BucketQueue<VM> {
         self.queue.is_empty()
     }
 
+    fn len(&self) -> usize {
+        self.queue.len()
+    }
+
     fn steal_batch_and_pop(
         &self,
         dest: &Worker<Box<dyn GCWork<VM>>>,
@@ -37,6 +41,22 @@ impl<VM: VMBinding> BucketQueue<VM> {
             self.queue.push(w);
         }
     }
+
+    fn steal(&self) -> Steal<Box<dyn GCWork<VM>>> {
+        self.queue.steal()
+    }
+
+    fn take_all(&self) -> Vec<Box<dyn GCWork<VM>>> {
+        let mut ws = vec![];
+        loop {
+            match self.queue.steal() {
+                Steal::Success(w) => ws.push(w),
+                Steal::Empty => break,
+                Steal::Retry => continue,
+            }
+        }
+        ws
+    }
 }
 
 pub type BucketOpenCondition<VM> = Box<dyn (Fn(&GCWorkScheduler<VM>) -> bool) + Send>;
@@ -44,7 +64,12 @@ pub type BucketOpenCondition<VM> = Box<dyn (Fn(&GCWorkScheduler<VM>) -> bool) +
 pub struct WorkBucket<VM: VMBinding> {
     active: AtomicBool,
     queue: BucketQueue<VM>,
-    prioritized_queue: Option<BucketQueue<VM>>,
+    /// Packets in this queue are taken before the packets in `queue`.  Packets are added to it
+    /// with [`WorkBucket::add_prioritized`] and [`WorkBucket::bulk_add_prioritized`].
+    prioritized_queue: BucketQueue<VM>,
+    /// Whether any packet has ever been added to `prioritized_queue`.  Until then, the bucket is a
+    /// plain FIFO queue, and polling does not look at `prioritized_queue`.
+    has_prioritized_work: AtomicBool,
     monitor: Arc<WorkerMonitor>,
     can_open: Option<BucketOpenCondition<VM>>,
     /// After this bucket is activated and all pending work packets (including the packets in this
@@ -59,6 +84,17 @@ pub struct WorkBucket<VM: VMBinding> {
     /// recursively, such as ephemerons and Java-style SoftReference and finalizers.  Sentinels
     /// can be used repeatedly to discover and process more such objects.
     sentinel: Mutex<Option<Box<dyn GCWork<VM>>>>,
+    /// If set, only the workers whose ordinals are set to true in the mask poll packets from this
+    /// bucket.  Packets are then taken one at a time instead of in batches, so that they are not
+    /// moved to the local queues of the allowed workers and stolen by other workers.  Note that
+    /// packets added to a local queue by a packet executed from this bucket can still be stolen by
+    /// any worker.
+    worker_mask: Option<Vec<bool>>,
+    /// Warn if the number of packets in this bucket exceeds this value.  0 means no limit.  See
+    /// [`WorkBucket::set_packet_warn_threshold`].
+    packet_warn_threshold: usize,
+    /// Whether we have warned about the number of packets since the bucket was last deactivated.
+    packet_warned: AtomicBool,
 }
 
 impl<VM: VMBinding> WorkBucket<VM> {
@@ -66,10 +102,69 @@ impl<VM: VMBinding> WorkBucket<VM> {
         Self {
             active: AtomicBool::new(active),
             queue: BucketQueue::new(),
-            prioritized_queue: None,
+            prioritized_queue: BucketQueue::new(),
+            has_prioritized_work: AtomicBool::new(false),
             monitor,
             can_open: None,
             sentinel: Mutex::new(None),
+            worker_mask: None,
+            packet_warn_threshold: 0,
+            packet_warned: AtomicBool::new(false),
+        }
+    }
+
+    /// Warn (once per GC) if the number of packets in this bucket exceeds `threshold`.  0 means
+    /// no limit.  See the option `work_packet_warn_threshold`.
+    ///
+    /// This is only a diagnostic safety valve.  We do not coalesce packets because they are stored
+    /// as type-erased `Box<dyn GCWork>`, and the bucket does not know how to merge two packets.
+    /// Coalescing would need a method on `GCWork`, such as `try_merge(&mut self, other: &mut dyn
+    /// GCWork<VM>) -> bool`, which a packet type implements by downcasting `other` to its own type
+    /// (`GCWork` is `Downcast`) and moving its payload (e.g. root slots) into `self`.  The bucket
+    /// could then pop a batch of packets when the threshold is exceeded, merge adjacent packets
+    /// that accept each other, and push the merged ones back.  Packets that do not override the
+    /// method would be left as they are.
+    pub(crate) fn set_packet_warn_threshold(&mut self, threshold: usize) {
+        self.packet_warn_threshold = threshold;
+    }
+
+    /// Warn if the number of packets exceeds `packet_warn_threshold`.
+    fn check_packet_count(&self) {
+        if self.packet_warn_threshold == 0 {
+            return;
+        }
+        let len = self.len();
+        if len > self.packet_warn_threshold && !self.packet_warned.swap(true, Ordering::Relaxed) {
+            warn!(
+                "A work bucket has {} packets, exceeding work_packet_warn_threshold ({}).",
+                len, self.packet_warn_threshold
+            );
+        }
+    }
+
+    /// Restrict this bucket to the workers whose ordinals are set to true in `mask`.  Workers
+    /// whose ordinals are out of the range of `mask` are not allowed.
+    pub(crate) fn set_worker_mask(&mut self, mask: Vec<bool>) {
+        assert!(
+            mask.iter().any(|allowed| *allowed),
+            "The worker mask of a bucket must allow at least one worker"
+        );
+        self.worker_mask = Some(mask);
+    }
+
+    /// Return true if the worker with the given ordinal can poll packets from this bucket.
+    pub fn is_worker_allowed(&self, ordinal: usize) -> bool {
+        self.worker_mask
+            .as_ref()
+            .map_or(true, |mask| mask.get(ordinal).copied().unwrap_or(false))
+    }
+
+    /// Return the prioritized queue if any packet has been added to it.
+    fn prioritized_queue(&self) -> Option<&BucketQueue<VM>> {
+        if self.has_prioritized_work.load(Ordering::SeqCst) {
+            Some(&self.prioritized_queue)
+        } else {
+            None
         }
     }
 
@@ -78,8 +173,10 @@ impl<VM: VMBinding> WorkBucket<VM> {
         if !self.is_activated() {
             return;
         }
-        // Notify one if there're any parked workers.
-        self.monitor.notify_work_available(false);
+        // Notify one if there're any parked workers.  If only some workers can take packets from
+        // this bucket, notify all of them, as the notified worker may not be allowed.
+        self.monitor
+            .notify_work_available(self.worker_mask.is_some());
     }
 
     pub fn notify_all_workers(&self) {
@@ -104,38 +201,51 @@ impl<VM: VMBinding> WorkBucket<VM> {
     pub fn is_empty(&self) -> bool {
         self.queue.is_empty()
             && self
-                .prioritized_queue
-                .as_ref()
+                .prioritized_queue()
                 .map(|q| q.is_empty())
                 .unwrap_or(true)
     }
 
+    /// Get the number of work packets in this bucket.  This does not include the packets that
+    /// have been moved to the local queues of workers.  This only reads the length of the queues
+    /// without locking, so the result may be stale if packets are being added or removed
+    /// concurrently.
+    pub fn len(&self) -> usize {
+        self.queue.len() + self.prioritized_queue().map_or(0, |q| q.len())
+    }
+
     pub fn is_drained(&self) -> bool {
         self.is_activated() && self.is_empty()
     }
 
     /// Disable the bucket
     pub fn deactivate(&self) {
-        debug_assert!(self.queue.is_empty(), "Bucket not drained before close");
+        debug_assert!(self.is_empty(), "Bucket not drained before close");
         self.active.store(false, Ordering::Relaxed);
+        self.packet_warned.store(false, Ordering::Relaxed);
     }
 
-    /// Add a work packet to this bucket
-    /// Panic if this bucket cannot receive prioritized packets.
+    /// Add a work packet to this bucket with a higher priority.  Prioritized packets are taken
+    /// before the other packets in this bucket, in FIFO order among themselves.  This is useful for
+    /// packets on the critical path of a GC, such as latency-critical sub-closures.
     pub fn add_prioritized(&self, work: Box<dyn GCWork<VM>>) {
-        self.prioritized_queue.as_ref().unwrap().push(work);
+        self.has_prioritized_work.store(true, Ordering::SeqCst);
+        self.prioritized_queue.push(work);
+        self.check_packet_count();
         self.notify_one_worker();
     }
 
     /// Add a work packet to this bucket
     pub fn add<W: GCWork<VM>>(&self, work: W) {
         self.queue.push(Box::new(work));
+        self.check_packet_count();
         self.notify_one_worker();
     }
 
     /// Add a work packet to this bucket
     pub fn add_boxed(&self, work: Box<dyn GCWork<VM>>) {
         self.queue.push(work);
+        self.check_packet_count();
         self.notify_one_worker();
     }
 
@@ -152,10 +262,14 @@ impl<VM: VMBinding> WorkBucket<VM> {
         self.queue.push(work);
     }
 
-    /// Add multiple packets with a higher priority.
-    /// Panic if this bucket cannot receive prioritized packets.
+    /// Add multiple packets with a higher priority.  See [`WorkBucket::add_prioritized`].
     pub fn bulk_add_prioritized(&self, work_vec: Vec<Box<dyn GCWork<VM>>>) {
-        self.prioritized_queue.as_ref().unwrap().push_all(work_vec);
+        if work_vec.is_empty() {
+            return;
+        }
+        self.has_prioritized_work.store(true, Ordering::SeqCst);
+        self.prioritized_queue.push_all(work_vec);
+        self.check_packet_count();
         if self.is_activated() {
             self.notify_all_workers();
         }
@@ -167,17 +281,51 @@ impl<VM: VMBinding> WorkBucket<VM> {
             return;
         }
         self.queue.push_all(work_vec);
+        self.check_packet_count();
         if self.is_activated() {
             self.notify_all_workers();
         }
     }
 
+    /// Remove all the (non-prioritized) work packets from this bucket and return them.  The bucket
+    /// must not be activated, otherwise GC workers may be polling packets from it concurrently.
+    pub(crate) fn take_all(&self) -> Vec<Box<dyn GCWork<VM>>> {
+        debug_assert!(
+            !self.is_activated(),
+            "Attempted to take work packets from an open bucket"
+        );
+        self.queue.take_all()
+    }
+
+    /// Take one work packet from this bucket, trying the prioritized queue first.  Unlike
+    /// [`WorkBucket::poll`], this does not check whether the bucket is activated.  Return `None`
+    /// if the bucket is empty.
+    pub(crate) fn steal_one(&self) -> Option<Box<dyn GCWork<VM>>> {
+        let queues = self
+            .prioritized_queue()
+            .into_iter()
+            .chain(std::iter::once(&self.queue));
+        for queue in queues {
+            loop {
+                match queue.steal() {
+                    Steal::Success(w) => return Some(w),
+                    Steal::Empty => break,
+                    Steal::Retry => continue,
+                }
+            }
+        }
+        None
+    }
+
     /// Get a work packet from this bucket
     pub fn poll(&self, worker: &Worker<Box<dyn GCWork<VM>>>) -> Steal<Box<dyn GCWork<VM>>> {
+        if self.worker_mask.is_some() {
+            return self.poll_one();
+        }
         if !self.is_activated() || self.is_empty() {
             return Steal::Empty;
         }
-        if let Some(prioritized_queue) = self.prioritized_queue.as_ref() {
+        if let Some(prioritized_queue) = self.prioritized_queue() {
             prioritized_queue
                 .steal_batch_and_pop(worker)
                 .or_else(|| self.queue.steal_batch_and_pop(worker))
@@ -186,6 +334,18 @@ impl<VM: VMBinding> WorkBucket<VM> {
         }
     }
 
+    /// Take one packet from this bucket, without moving other packets to the local queue of the
+    /// polling worker.  Buckets with worker masks are always polled this way.
+    pub fn poll_one(&self) -> Steal<Box<dyn GCWork<VM>>> {
+        if !self.is_activated() || self.is_empty() {
+            return Steal::Empty;
+        }
+        match self.prioritized_queue().map(BucketQueue::steal) {
+            Some(Steal::Success(w)) => Steal::Success(w),
+            _ => self.queue.steal(),
+        }
+    }
+
     pub fn set_open_condition(
         &mut self,
         pred: impl Fn(&GCWorkScheduler<VM>) -> bool + Send + 'static,
@@ -234,6 +394,12 @@ impl<VM: VMBinding> WorkBucket<VM> {
     }
 }
 
+/// A handle of a custom work bucket registered with
+/// [`crate::memory_manager::register_custom_work_bucket`].  A custom bucket is opened after the
+/// stage it is registered after, and before the next stage.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct CustomBucketId(pub(crate) usize);
+
 /// This enum defines all the work bucket types. The scheduler
 /// will instantiate a work bucket for each stage defined here.
 #[derive(Debug, Enum, Copy, Clone, Eq, PartialEq)]
@@ -2,15 +2,16 @@ use super::stat::WorkerLocalStat;
 use super::work_bucket::*;
 use super::*;
 use crate::mmtk::MMTK;
-use crate::util::copy::GCWorkerCopyContext;
+use crate::util::copy::{CopyAllocStats, CopySemantics, GCWorkerCopyContext};
 use crate::util::heap::layout::heap_parameters::MAX_SPACES;
 use crate::util::opaque_pointer::*;
-use crate::util::ObjectReference;
+use crate::util::{Address, ObjectReference};
 use crate::vm::{Collection, GCThreadContext, VMBinding};
 use atomic::Atomic;
 use atomic_refcell::{AtomicRef, AtomicRefCell, AtomicRefMut};
 use crossbeam::deque::{self, Stealer};
 use crossbeam::queue::ArrayQueue;
+use enum_map::EnumMap;
 use std::sync::atomic::Ordering;
 use std::sync::{Arc, Mutex};
 
@@ -44,6 +45,32 @@ pub struct GCWorkerShared<VM: VMBinding> {
     /// at the end of a GC, and reset this counter.
     /// The live bytes are stored in an array. The index is the index from the space descriptor.
     pub live_bytes_per_space: AtomicRefCell<[usize; MAX_SPACES]>,
+    /// The number of objects scanned by this worker in this GC.  Unlike `live_bytes_per_space`,
+    /// this is always counted.  The index is the index from the space descriptor.
+    pub scanned_objects_per_space: AtomicRefCell<[usize; MAX_SPACES]>,
+    /// Accumulated block acquisition statistics of the copy allocators of this worker in this GC.
+    /// It is updated when the copy context is released, and we get this value from each worker at
+    /// the end of a GC, and reset this counter.
+    pub copy_alloc_stats: AtomicRefCell<CopyAllocStats>,
+    /// The bytes copied into Immix spaces by this worker for each copy semantics in this GC if the
+    /// option `record_copy_breakdown` is enabled.  It is updated when the copy context is released,
+    /// and we get this value from each worker at the end of a GC, and reset this counter.
+    pub copy_breakdown: AtomicRefCell<EnumMap<CopySemantics, usize>>,
+    /// The `(parent, child)` edges found by this worker in this GC if the option
+    /// `record_heap_edges` is enabled.  We take the edges from each worker at the end of a GC.
+    pub heap_edges: AtomicRefCell<Vec<(ObjectReference, ObjectReference)>>,
+    /// The objects forwarded by this worker in this GC.  It is updated when the copy context is
+    /// released, and we take the entries from each worker at the end of a GC.
+    #[cfg(feature = "forwarding_log")]
+    pub forwarding_log: AtomicRefCell<Vec<crate::util::copy::ForwardingLogEntry>>,
+    /// The number of slots updated to point to moved objects by this worker in this GC.
+    #[cfg(feature = "count_updated_slots")]
+    pub updated_slots: std::sync::atomic::AtomicUsize,
+    /// The time (in nanoseconds) this worker spent executing work packets in this GC.  The rest of
+    /// the GC time is the idle time of this worker.  We get this value from each worker at the
+    /// end of a GC, and reset this counter.  Only updated if the option `record_worker_idle_time`
+    /// is enabled.
+    pub busy_nanos: std::sync::atomic::AtomicU64,
     /// A queue of GCWork that can only be processed by the owned thread.
     pub designated_work: ArrayQueue<Box<dyn GCWork<VM>>>,
     /// Handle for stealing packets from the current worker
@@ -55,22 +82,26 @@ impl<VM: VMBinding> GCWorkerShared<VM> {
         Self {
             stat: Default::default(),
             live_bytes_per_space: AtomicRefCell::new([0; MAX_SPACES]),
+            scanned_objects_per_space: AtomicRefCell::new([0; MAX_SPACES]),
+            copy_alloc_stats: AtomicRefCell::new(CopyAllocStats::default()),
+            copy_breakdown: AtomicRefCell::new(EnumMap::default()),
+            heap_edges: AtomicRefCell::new(vec![]),
+            #[cfg(feature = "forwarding_log")]
+            forwarding_log: AtomicRefCell::new(vec![]),
+            #[cfg(feature = "count_updated_slots")]
+            updated_slots: std::sync::atomic::AtomicUsize::new(0),
+            busy_nanos: std::sync::atomic::AtomicU64::new(0),
             designated_work: ArrayQueue::new(16),
             stealer,
         }
     }
 
-    pub(crate) fn increase_live_bytes(
-        live_bytes_per_space: &mut [usize; MAX_SPACES],
-        object: ObjectReference,
-    ) {
+    /// Get the index of the space the address is in, or `None` if the address is not in any space.
+    fn space_index_of(address: Address) -> Option<usize> {
         use crate::mmtk::VM_MAP;
-        use crate::vm::object_model::ObjectModel;
 
-        // The live bytes of the object
-        let bytes = VM::VMObjectModel::get_current_size(object);
         // Get the space index from descriptor
-        let space_descriptor = VM_MAP.get_descriptor_for_address(object.to_raw_address());
+        let space_descriptor = VM_MAP.get_descriptor_for_address(address);
         if space_descriptor != crate::util::heap::space_descriptor::SpaceDescriptor::UNINITIALIZED {
             let space_index = space_descriptor.get_index();
             debug_assert!(
@@ -79,8 +110,42 @@ impl<VM: VMBinding> GCWorkerShared<VM> {
                 space_index,
                 MAX_SPACES
             );
-            // Accumulate the live bytes for the index
-            live_bytes_per_space[space_index] += bytes;
+            Some(space_index)
+        } else {
+            None
+        }
+    }
+
+    /// Count `objects` as scanned objects of the spaces they are in, and also accumulate their live
+    /// bytes if `count_live_bytes` is true.  Spaces are recorded per chunk in the VM map, so the
+    /// space is only looked up when an object is in a different chunk from the previous one.
+    pub(crate) fn count_scanned_objects(
+        &self,
+        objects: &[ObjectReference],
+        count_live_bytes: bool,
+    ) {
+        use crate::util::conversions::chunk_align_down;
+        use crate::vm::object_model::ObjectModel;
+
+        let mut scanned_objects_per_space = self.scanned_objects_per_space.borrow_mut();
+        let mut live_bytes_per_space =
+            count_live_bytes.then(|| self.live_bytes_per_space.borrow_mut());
+        let mut last_chunk = Address::ZERO;
+        let mut last_space_index = None;
+        for object in objects.iter().copied() {
+            let chunk = chunk_align_down(object.to_raw_address());
+            if chunk != last_chunk {
+                last_chunk = chunk;
+                last_space_index = Self::space_index_of(chunk);
+            }
+            if let Some(space_index) = last_space_index {
+                scanned_objects_per_space[space_index] += 1;
+                if let Some(live_bytes_per_space) = live_bytes_per_space.as_mut() {
+                    // The live bytes of the object
+                    live_bytes_per_space[space_index] +=
+                        VM::VMObjectModel::get_current_size(object);
+                }
+            }
         }
     }
 }
@@ -101,6 +166,19 @@ pub struct GCWorker<VM: VMBinding> {
     pub shared: Arc<GCWorkerShared<VM>>,
     /// Local work packet queue.
     pub local_work_buffer: deque::Worker<Box<dyn GCWork<VM>>>,
+    /// The number of object-scanning work packets executed immediately since this worker got a
+    /// work packet from the scheduler.
+    immediate_scans: usize,
+    /// The maximum value of `immediate_scans`.  See `Options::immediate_scan_depth_limit`.
+    immediate_scan_depth_limit: usize,
+    /// The bucket being drained by [`GCWorker::drain_bucket`], if any.  Packets added to this
+    /// bucket are pushed to the global bucket instead of the local queue so that they are drained,
+    /// too.
+    draining_bucket: Option<WorkBucketStage>,
+    /// The objects enqueued by this worker in the current round of tracing, and the epoch of the
+    /// slot logger when it was last cleared.  See [`GCWorker::log_enqueued_object`].
+    #[cfg(feature = "extreme_assertions")]
+    enqueued_objects: (usize, std::collections::HashSet<ObjectReference>),
 }
 
 unsafe impl<VM: VMBinding> Sync for GCWorkerShared<VM> {}
@@ -148,16 +226,48 @@ impl<VM: VMBinding> GCWorker<VM> {
             mmtk,
             shared,
             local_work_buffer,
+            immediate_scans: 0,
+            immediate_scan_depth_limit: *mmtk.options.immediate_scan_depth_limit,
+            draining_bucket: None,
+            #[cfg(feature = "extreme_assertions")]
+            enqueued_objects: (0, Default::default()),
         }
     }
 
     const LOCALLY_CACHED_WORK_PACKETS: usize = 16;
 
+    /// Record an object enqueued for scanning by this worker, and panic if this worker has already
+    /// enqueued it in the current round of tracing.  Objects should only be enqueued when they are
+    /// marked for the first time, so a duplicate usually means a race in marking.
+    ///
+    /// This is only a debugging check for the `extreme_assertions` feature.  It keeps a hash set
+    /// of every object this worker enqueued since the last reset of the slot logger, so it costs
+    /// roughly a hash set entry per traced object on each worker, and the memory is not returned
+    /// until the worker exits.  Duplicates enqueued by different workers are not detected.
+    #[cfg(feature = "extreme_assertions")]
+    pub(crate) fn log_enqueued_object(&mut self, object: ObjectReference) {
+        let epoch = self.mmtk.slot_logger.epoch();
+        let (logged_epoch, enqueued_objects) = &mut self.enqueued_objects;
+        if *logged_epoch != epoch {
+            enqueued_objects.clear();
+            *logged_epoch = epoch;
+        }
+        assert!(
+            enqueued_objects.insert(object),
+            "duplicate enqueued object ({}) detected on GC worker {}",
+            object,
+            self.ordinal
+        );
+    }
+
     /// Add a work packet to the work queue and mark it with a higher priority.
     /// If the bucket is activated, the packet will be pushed to the local queue, otherwise it will be
-    /// pushed to the global bucket with a higher priority.
+    /// pushed to the global bucket with a higher priority.  Packets are always pushed to the global
+    /// bucket if the scheduler is deterministic or the bucket is being drained by this worker.
     pub fn add_work_prioritized(&mut self, bucket: WorkBucketStage, work: impl GCWork<VM>) {
         if !self.scheduler().work_buckets[bucket].is_activated()
+            || self.scheduler().is_deterministic()
+            || self.draining_bucket == Some(bucket)
             || self.local_work_buffer.len() >= Self::LOCALLY_CACHED_WORK_PACKETS
         {
             self.scheduler.work_buckets[bucket].add_prioritized(Box::new(work));
@@ -168,9 +278,12 @@ impl<VM: VMBinding> GCWorker<VM> {
 
     /// Add a work packet to the work queue.
     /// If the bucket is activated, the packet will be pushed to the local queue, otherwise it will be
-    /// pushed to the global bucket.
+    /// pushed to the global bucket.  Packets are always pushed to the global bucket if the scheduler
+    /// is deterministic or the bucket is being drained by this worker.
     pub fn add_work(&mut self, bucket: WorkBucketStage, work: impl GCWork<VM>) {
         if !self.scheduler().work_buckets[bucket].is_activated()
+            || self.scheduler().is_deterministic()
+            || self.draining_bucket == Some(bucket)
             || self.local_work_buffer.len() >= Self::LOCALLY_CACHED_WORK_PACKETS
         {
             self.scheduler.work_buckets[bucket].add(work);
@@ -179,6 +292,45 @@ impl<VM: VMBinding> GCWorker<VM> {
         self.local_work_buffer.push(Box::new(work));
     }
 
+    /// Execute all the work packets in the bucket `stage` on the current worker before returning,
+    /// including the packets added to the bucket while draining it.  The bucket does not need to be
+    /// activated.  This is intended for plans that compose custom phases which must fully complete
+    /// before the next phase begins, without relying on the scheduler to open buckets.  Return the
+    /// number of work packets executed.
+    ///
+    /// Packets added to `stage` via [`GCWorker::add_work`] or [`GCWorker::add_work_prioritized`]
+    /// by this worker are pushed to the global bucket so that they are drained, too.  But packets in
+    /// the local queues of other workers, or packets being executed by other workers, are not
+    /// waited for.  If the bucket is activated, other workers may take packets from it
+    /// concurrently, so this function returning does not mean those packets have completed.
+    ///
+    /// Be careful about deadlocks.  The packets are executed while the current packet is still
+    /// being executed, so they must not wait for the current packet or for other workers, e.g. by
+    /// blocking on a lock held by the caller or on a barrier among workers.  If multiple workers
+    /// drain the same bucket and the packets wait for each other, they may deadlock, as no worker
+    /// returns to the scheduler to execute the packets being waited for.
+    pub fn drain_bucket(&mut self, stage: WorkBucketStage) -> usize {
+        let prev_draining_bucket = self.draining_bucket.replace(stage);
+        let mut executed = 0;
+        while let Some(mut work) = self.scheduler.work_buckets[stage].steal_one() {
+            work.do_work_with_stat(self, self.mmtk);
+            executed += 1;
+        }
+        self.draining_bucket = prev_draining_bucket;
+        executed
+    }
+
+    /// Record that an object-scanning work packet is about to be executed immediately by this
+    /// worker.  Return `false` if this worker has reached `Options::immediate_scan_depth_limit`, in
+    /// which case the caller should dispatch the packet to a work bucket instead.
+    pub(crate) fn try_scan_immediately(&mut self) -> bool {
+        if self.immediate_scans >= self.immediate_scan_depth_limit {
+            return false;
+        }
+        self.immediate_scans += 1;
+        true
+    }
+
     /// Get the scheduler. There is only one scheduler per MMTk instance.
     pub fn scheduler(&self) -> &GCWorkScheduler<VM> {
         &self.scheduler
@@ -204,6 +356,7 @@ impl<VM: VMBinding> GCWorker<VM> {
             return Ok(work);
         }
 
+        self.immediate_scans = 0;
         self.scheduler().poll(self)
     }
 
@@ -254,7 +407,23 @@ impl<VM: VMBinding> GCWorker<VM> {
             std::hint::black_box(unsafe { *(typename.as_ptr()) });
 
             probe!(mmtk, work, typename.as_ptr(), typename.len());
-            work.do_work_with_stat(&mut self, mmtk);
+            let start = self
+                .scheduler
+                .is_recording_worker_idle_time()
+                .then(std::time::Instant::now);
+            if self.scheduler.is_deterministic() {
+                let scheduler = self.scheduler.clone();
+                let _guard = scheduler.lock_for_deterministic_execution();
+                work.do_work_with_stat(&mut self, mmtk);
+            } else {
+                work.do_work_with_stat(&mut self, mmtk);
+            }
+            // Time spent polling or parked is idle time.  See `GCWorkerShared::busy_nanos`.
+            if let Some(start) = start {
+                self.shared
+                    .busy_nanos
+                    .fetch_add(start.elapsed().as_nanos() as u64, Ordering::Relaxed);
+            }
         }
         debug!(
             "Worker exiting. ordinal: {}, {}",
@@ -454,4 +623,79 @@ impl<VM: VMBinding> WorkerGroup<VM> {
         });
         ret
     }
+
+    /// Get the numbers of scanned objects from the workers, and clear the local data.
+    pub fn get_and_clear_worker_scanned_objects(&self) -> [usize; MAX_SPACES] {
+        let mut ret = [0; MAX_SPACES];
+        self.workers_shared.iter().for_each(|w| {
+            let mut scanned_objects_per_space = w.scanned_objects_per_space.borrow_mut();
+            for (idx, val) in scanned_objects_per_space.iter_mut().enumerate() {
+                ret[idx] += *val;
+                *val = 0;
+            }
+        });
+        ret
+    }
+
+    /// Get the copy allocation statistics from the workers, and clear the local data.
+    pub fn get_and_clear_worker_copy_stats(&self) -> CopyAllocStats {
+        let mut ret = CopyAllocStats::default();
+        self.workers_shared.iter().for_each(|w| {
+            ret += std::mem::take(&mut *w.copy_alloc_stats.borrow_mut());
+        });
+        ret
+    }
+
+    /// Get the bytes copied for each copy semantics from the workers, and clear the local data.
+    pub fn get_and_clear_worker_copy_breakdown(&self) -> EnumMap<CopySemantics, usize> {
+        let mut ret = EnumMap::<CopySemantics, usize>::default();
+        self.workers_shared.iter().for_each(|w| {
+            let breakdown = std::mem::take(&mut *w.copy_breakdown.borrow_mut());
+            for (semantics, bytes) in breakdown {
+                ret[semantics] += bytes;
+            }
+        });
+        ret
+    }
+
+    /// Get the total time the workers spent executing work packets, and clear the local data.
+    pub fn get_and_clear_worker_busy_time(&self) -> std::time::Duration {
+        let nanos = self
+            .workers_shared
+            .iter()
+            .map(|w| w.busy_nanos.swap(0, Ordering::Relaxed))
+            .sum();
+        std::time::Duration::from_nanos(nanos)
+    }
+
+    /// Get the number of slots updated to point to moved objects from the workers, and clear the
+    /// local data.
+    #[cfg(feature = "count_updated_slots")]
+    pub fn get_and_clear_worker_updated_slots(&self) -> usize {
+        self.workers_shared
+            .iter()
+            .map(|w| w.updated_slots.swap(0, Ordering::Relaxed))
+            .sum()
+    }
+
+    /// Get the recorded heap edges from the workers, and clear the local data.
+    pub fn get_and_clear_worker_heap_edges(&self) -> Vec<(ObjectReference, ObjectReference)> {
+        let mut ret = vec![];
+        self.workers_shared.iter().for_each(|w| {
+            ret.append(&mut w.heap_edges.borrow_mut());
+        });
+        ret
+    }
+
+    /// Get the forwarding log entries from the workers, and clear the local data.
+    #[cfg(feature = "forwarding_log")]
+    pub fn get_and_clear_worker_forwarding_log(
+        &self,
+    ) -> Vec<crate::util::copy::ForwardingLogEntry> {
+        let mut ret = vec![];
+        self.workers_shared.iter().for_each(|w| {
+            ret.append(&mut w.forwarding_log.borrow_mut());
+        });
+        ret
+    }
 }
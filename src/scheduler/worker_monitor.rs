@@ -20,6 +20,9 @@ pub(crate) enum LastParkedResult {
     WakeSelf,
     /// Wake up all parked GC workers.
     WakeAll,
+    /// Wake up all other parked GC workers, but the last parked worker should wait.  Used when
+    /// the remaining work packets can only be executed by other workers.
+    WakeOthers,
 }
 
 /// A data structure for synchronizing workers with each other and with mutators.
@@ -35,6 +38,9 @@ pub(crate) struct WorkerMonitor {
     /// -   any work packets available, and
     /// -   any field in `sync.goals.requests` set to true.
     workers_have_anything_to_do: Condvar,
+    /// Always wake up all workers if work packets are executed in a deterministic order, because
+    /// only some of the workers can execute packets from work buckets.
+    wake_all: bool,
 }
 
 /// The synchronized part of `WorkerMonitor`.
@@ -85,13 +91,14 @@ impl WorkerParker {
 }
 
 impl WorkerMonitor {
-    pub fn new(worker_count: usize) -> Self {
+    pub fn new(worker_count: usize, wake_all: bool) -> Self {
         Self {
             sync: Mutex::new(WorkerMonitorSync {
                 parker: WorkerParker::new(worker_count),
                 goals: Default::default(),
             }),
             workers_have_anything_to_do: Default::default(),
+            wake_all,
         }
     }
 
@@ -108,7 +115,7 @@ impl WorkerMonitor {
     /// Wake up workers when more work packets are made available for workers,
     /// or a mutator has requested the GC workers to schedule a GC.
     pub fn notify_work_available(&self, all: bool) {
-        if all {
+        if all || self.wake_all {
             self.workers_have_anything_to_do.notify_all();
         } else {
             self.workers_have_anything_to_do.notify_one();
@@ -159,6 +166,10 @@ impl WorkerMonitor {
                 LastParkedResult::WakeAll => {
                     self.notify_work_available(true);
                 }
+                LastParkedResult::WakeOthers => {
+                    self.notify_work_available(true);
+                    should_wait = true;
+                }
             }
         } else {
             should_wait = true;
@@ -259,7 +270,7 @@ mod tests {
     #[test]
     fn test_last_worker_park_wake_all() {
         let number_threads = 4;
-        let worker_monitor = Arc::new(WorkerMonitor::new(number_threads));
+        let worker_monitor = Arc::new(WorkerMonitor::new(number_threads, false));
         let on_last_parked_called = AtomicUsize::new(0);
         let should_unpark = AtomicBool::new(false);
 
@@ -296,7 +307,7 @@ mod tests {
     #[test]
     fn test_last_worker_park_wake_self() {
         let number_threads = 4;
-        let worker_monitor = Arc::new(WorkerMonitor::new(number_threads));
+        let worker_monitor = Arc::new(WorkerMonitor::new(number_threads, false));
         let on_last_parked_called = AtomicUsize::new(0);
         let threads_running = AtomicUsize::new(0);
         let should_unpark = AtomicBool::new(false);
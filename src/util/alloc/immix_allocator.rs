@@ -2,11 +2,14 @@ use std::sync::Arc;
 
 use super::allocator::{align_allocation_no_fill, fill_alignment_gap, AllocatorContext};
 use super::BumpPointer;
+use crate::policy::immix::block::Block;
+use crate::policy::immix::immixspace::CopyPurpose;
 use crate::policy::immix::line::*;
 use crate::policy::immix::ImmixSpace;
 use crate::policy::space::Space;
 use crate::util::alloc::allocator::get_maximum_aligned_size;
 use crate::util::alloc::Allocator;
+use crate::util::copy::CopyAllocStats;
 use crate::util::linear_scan::Region;
 use crate::util::opaque_pointer::VMThread;
 use crate::util::rust_util::unlikely;
@@ -33,6 +36,18 @@ pub struct ImmixAllocator<VM: VMBinding> {
     request_for_large: bool,
     /// Hole-searching cursor
     line: Option<Line>,
+    /// Block acquisition statistics.  They are only reported for copy allocators.
+    pub(crate) alloc_stats: CopyAllocStats,
+    /// The copy budget this allocator charges the clean blocks it acquires to, if any.
+    copy_purpose: Option<CopyPurpose>,
+    /// The clean block acquired by [`ImmixAllocator::prewarm`] in the current GC, if any.
+    prewarmed_block: Option<Block>,
+    /// Reusable blocks taken from the space whose holes were too small for a large object (see
+    /// [`ImmixSpace::get_reusable_block_for_size`]).  They are kept here until the allocator is
+    /// reset, so that later large objects do not search them again, and small objects are
+    /// allocated into them before more reusable blocks are taken from the space.  Blocks dropped by
+    /// a reset are found again by the next sweep.
+    rejected_blocks: Vec<Block>,
 }
 
 impl<VM: VMBinding> ImmixAllocator<VM> {
@@ -41,6 +56,7 @@ impl<VM: VMBinding> ImmixAllocator<VM> {
         self.large_bump_pointer.reset(Address::ZERO, Address::ZERO);
         self.request_for_large = false;
         self.line = None;
+        self.rejected_blocks.clear();
     }
 }
 
@@ -182,13 +198,80 @@ impl<VM: VMBinding> ImmixAllocator<VM> {
             large_bump_pointer: BumpPointer::default(),
             request_for_large: false,
             line: None,
+            alloc_stats: CopyAllocStats::default(),
+            copy_purpose: None,
+            prewarmed_block: None,
+            rejected_blocks: vec![],
         }
     }
 
+    /// Make this allocator draw clean blocks from the copy budget of `purpose` (see
+    /// [`ImmixAllocator::acquire_clean_block`]).
+    pub(crate) fn with_copy_purpose(mut self, purpose: CopyPurpose) -> Self {
+        self.copy_purpose = Some(purpose);
+        self
+    }
+
+    /// Change the copy budget this allocator draws clean blocks from.  See
+    /// [`ImmixAllocator::with_copy_purpose`].
+    pub(crate) fn set_copy_purpose(&mut self, purpose: CopyPurpose) {
+        self.copy_purpose = Some(purpose);
+    }
+
     pub(crate) fn immix_space(&self) -> &'static ImmixSpace<VM> {
         self.space
     }
 
+    /// Acquire a clean block ahead of the first copy of this GC, so that the first copies can be
+    /// served from the thread local buffer.  This is only used by copy allocators.  It must be
+    /// called after the blocks are prepared for the GC (see `PrewarmCopyAllocators`), and does
+    /// nothing if the allocator already has a buffer.  The block is taken from the copy budget (if
+    /// any) and counted by [`ImmixSpace::get_clean_block`] like any other clean block.  Return
+    /// false if no block was acquired.
+    pub(crate) fn prewarm(&mut self) -> bool {
+        debug_assert!(self.copy || self.copy_purpose.is_some());
+        if self.prewarmed_block.is_some() || !self.bump_pointer.cursor.is_zero() {
+            return false;
+        }
+        if !self.space.is_copying_viable() {
+            return false;
+        }
+        if let Some(purpose) = self.copy_purpose {
+            if !self.space.try_consume_copy_budget(purpose) {
+                return false;
+            }
+        }
+        let Some(block) = self.get_clean_block() else {
+            if let Some(purpose) = self.copy_purpose {
+                self.space.refund_copy_budget(purpose);
+            }
+            return false;
+        };
+        Line::MARK_TABLE.bzero_metadata(block.start(), Block::BYTES);
+        self.bump_pointer.cursor = block.start();
+        self.bump_pointer.limit = block.end();
+        self.prewarmed_block = Some(block);
+        true
+    }
+
+    /// Release the block acquired by [`ImmixAllocator::prewarm`] if nothing has been copied into
+    /// it.  This should be called before the allocator is reset at the end of a GC.
+    ///
+    /// With line marking, a block without marked lines is released by the sweep, so there is
+    /// nothing to do here.  Releasing it here as well would race with the sweep.  In block-only
+    /// mode, a block acquired by a copy allocator is marked when it is initialized, so the sweep
+    /// would keep it until the next GC.  We release it here instead.
+    pub(crate) fn release_unused_prewarmed_block(&mut self) {
+        let Some(block) = self.prewarmed_block.take() else {
+            return;
+        };
+        let unused = self.bump_pointer.cursor == block.start();
+        if crate::policy::immix::BLOCK_ONLY && unused {
+            self.reset();
+            self.space.release_block(block);
+        }
+    }
+
     /// Large-object (larger than a line) bump allocation.
     fn overflow_alloc(&mut self, size: usize, align: usize, offset: usize) -> Address {
         trace!("{:?}: overflow_alloc", self.tls);
@@ -249,10 +332,18 @@ impl<VM: VMBinding> ImmixAllocator<VM> {
                     end_line,
                     self.tls
                 );
+                // Zero the hole before allocating into it, so stale data of dead objects in the
+                // reused lines is never exposed.  Only the hole [start_line, end_line) is zeroed.
+                // Lines still holding live objects are never touched.
                 crate::util::memory::zero(
                     self.bump_pointer.cursor,
                     self.bump_pointer.limit - self.bump_pointer.cursor,
                 );
+                #[cfg(feature = "vo_bit")]
+                crate::util::metadata::vo_bit::helper::on_lines_reused::<VM>(
+                    self.bump_pointer.cursor,
+                    self.bump_pointer.limit - self.bump_pointer.cursor,
+                );
                 debug_assert!(
                     align_allocation_no_fill::<VM>(self.bump_pointer.cursor, align, offset) + size
                         <= self.bump_pointer.limit
@@ -276,9 +367,17 @@ impl<VM: VMBinding> ImmixAllocator<VM> {
 
     /// Get a recyclable block from ImmixSpace.
     fn acquire_recyclable_block(&mut self) -> bool {
-        match self.immix_space().get_reusable_block(self.copy) {
+        let block = match self.rejected_blocks.pop() {
+            Some(block) => {
+                self.space.init_reusable_block(block, self.copy);
+                Some(block)
+            }
+            None => self.space.get_reusable_block(self.copy),
+        };
+        match block {
             Some(block) => {
                 trace!("{:?}: acquire_recyclable_block -> {:?}", self.tls, block);
+                self.alloc_stats.blocks_acquired += 1;
                 // Set the hole-searching cursor to the start of this block.
                 self.line = Some(block.start_line());
                 true
@@ -287,10 +386,75 @@ impl<VM: VMBinding> ImmixAllocator<VM> {
         }
     }
 
+    /// Try to serve a large allocation from a hole of a reusable block found by
+    /// [`ImmixSpace::get_reusable_block_for_size`].  On success, the large bump pointer is set to
+    /// the hole.
+    fn acquire_large_hole(&mut self, size: usize, align: usize) -> bool {
+        let bytes = get_maximum_aligned_size::<VM>(size, align);
+        let Some(block) = self.immix_space().get_reusable_block_for_size(
+            self.copy,
+            bytes,
+            &mut self.rejected_blocks,
+        ) else {
+            return false;
+        };
+        trace!("{:?}: acquire_large_hole -> {:?}", self.tls, block);
+        self.alloc_stats.blocks_acquired += 1;
+        // The hole has been found by `get_reusable_block_for_size`, and nobody else can allocate in
+        // the block, so it is still there.
+        let (start_line, end_line) = self.immix_space().find_hole(block, bytes).unwrap();
+        self.large_bump_pointer.cursor = start_line.start();
+        self.large_bump_pointer.limit = end_line.start();
+        crate::util::memory::zero(start_line.start(), end_line.start() - start_line.start());
+        #[cfg(feature = "vo_bit")]
+        crate::util::metadata::vo_bit::helper::on_lines_reused::<VM>(
+            start_line.start(),
+            end_line.start() - start_line.start(),
+        );
+        // Let small objects use the remaining holes of the block if we are not searching another
+        // block for holes.
+        if self.line.is_none() && end_line != block.end_line() {
+            self.line = Some(end_line);
+        }
+        true
+    }
+
+    /// Get a clean block from ImmixSpace, and update the block acquisition statistics.
+    fn get_clean_block(&mut self) -> Option<crate::policy::immix::block::Block> {
+        let block = self.immix_space().get_clean_block(self.tls, self.copy);
+        if block.is_some() {
+            self.alloc_stats.blocks_acquired += 1;
+        } else {
+            self.alloc_stats.block_acquisition_failures += 1;
+        }
+        block
+    }
+
     // Get a clean block from ImmixSpace.
     fn acquire_clean_block(&mut self, size: usize, align: usize, offset: usize) -> Address {
-        match self.immix_space().get_clean_block(self.tls, self.copy) {
-            None => Address::ZERO,
+        if self.request_for_large
+            && self.immix_space().is_max_hole_tracking_enabled()
+            && self.acquire_large_hole(size, align)
+        {
+            return self.alloc(size, align, offset);
+        }
+        // Charge the block to the copy budget.  If the budget is used up, the object being copied
+        // still gets the block, as it is too late to leave it in place.
+        let charged = self
+            .copy_purpose
+            .is_some_and(|purpose| self.space.try_consume_copy_budget(purpose));
+        match self.get_clean_block() {
+            None => {
+                if charged {
+                    self.space.refund_copy_budget(self.copy_purpose.unwrap());
+                }
+                if self.copy {
+                    // A GC thread cannot trigger a GC to get more memory.  Stop copying, so that
+                    // the objects traced from now on are marked in place.
+                    self.immix_space().disable_copying_for_rest_of_gc();
+                }
+                Address::ZERO
+            }
             Some(block) => {
                 trace!(
                     "{:?}: Acquired a new block {:?} -> {:?}",
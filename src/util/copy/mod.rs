@@ -50,6 +50,69 @@ impl<VM: VMBinding> Default for CopyConfig<VM> {
     }
 }
 
+/// Statistics of the block acquisitions of the copy allocators of a GC worker.  Copy allocators
+/// acquire blocks from the space when their thread local buffers are exhausted.  Those statistics can
+/// help tuning the size of the copy reserve.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CopyAllocStats {
+    /// The number of blocks acquired by the copy allocators, including clean blocks and reusable blocks.
+    pub blocks_acquired: usize,
+    /// The number of times the copy allocators failed to acquire a clean block from the space.
+    pub block_acquisition_failures: usize,
+}
+
+impl std::ops::AddAssign for CopyAllocStats {
+    fn add_assign(&mut self, other: Self) {
+        self.blocks_acquired += other.blocks_acquired;
+        self.block_acquisition_failures += other.block_acquisition_failures;
+    }
+}
+
+/// An entry of the forwarding log, recording that an object is copied by a GC worker.
+/// See [`crate::MMTK::dump_forwarding_log`].
+#[cfg(feature = "forwarding_log")]
+#[derive(Debug, Clone, Copy)]
+pub struct ForwardingLogEntry {
+    /// The object before it is copied.
+    pub old: ObjectReference,
+    /// The new copy of the object.
+    pub new: ObjectReference,
+    /// The copy semantics used for copying the object.
+    pub semantics: CopySemantics,
+    /// The GC worker that copied the object.
+    pub worker: VMWorkerThread,
+}
+
+/// A ring buffer of the objects forwarded by a GC worker in the current GC.  When it is full, the
+/// oldest entries are overwritten.
+#[cfg(feature = "forwarding_log")]
+struct ForwardingLog {
+    entries: std::collections::VecDeque<ForwardingLogEntry>,
+}
+
+#[cfg(feature = "forwarding_log")]
+impl ForwardingLog {
+    /// The maximum number of entries each GC worker keeps in a GC.
+    const CAPACITY: usize = 1 << 16;
+
+    fn new() -> Self {
+        Self {
+            entries: std::collections::VecDeque::new(),
+        }
+    }
+
+    fn record(&mut self, entry: ForwardingLogEntry) {
+        if self.entries.len() == Self::CAPACITY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    fn take(&mut self) -> Vec<ForwardingLogEntry> {
+        std::mem::take(&mut self.entries).into()
+    }
+}
+
 /// The thread local struct for each GC worker for copying. Each GC worker should include
 /// one instance of this struct for copying operations.
 pub struct GCWorkerCopyContext<VM: VMBinding> {
@@ -61,6 +124,15 @@ pub struct GCWorkerCopyContext<VM: VMBinding> {
     pub immix_hybrid: [MaybeUninit<ImmixHybridCopyContext<VM>>; MAX_IMMIX_HYBRID_COPY_ALLOCATORS],
     /// The config for the plan
     config: CopyConfig<VM>,
+    /// The worker thread for this copy context.
+    #[cfg(feature = "forwarding_log")]
+    worker_tls: VMWorkerThread,
+    /// The objects forwarded by this worker in the current GC.
+    #[cfg(feature = "forwarding_log")]
+    forwarding_log: ForwardingLog,
+    /// The bytes copied into Immix spaces by this worker for each copy semantics in the current
+    /// GC.  This is `None` unless the option `record_copy_breakdown` is enabled.
+    copy_breakdown: Option<EnumMap<CopySemantics, usize>>,
 }
 
 impl<VM: VMBinding> GCWorkerCopyContext<VM> {
@@ -131,16 +203,32 @@ impl<VM: VMBinding> GCWorkerCopyContext<VM> {
                 unsafe { self.copy[index as usize].assume_init_mut() }.post_copy(object, bytes)
             }
             CopySelector::Immix(index) => {
-                unsafe { self.immix[index as usize].assume_init_mut() }.post_copy(object, bytes)
+                unsafe { self.immix[index as usize].assume_init_mut() }.post_copy(object, bytes);
+                self.record_copy_breakdown(semantics, bytes);
             }
             CopySelector::ImmixHybrid(index) => {
                 unsafe { self.immix_hybrid[index as usize].assume_init_mut() }
-                    .post_copy(object, bytes)
+                    .post_copy(object, bytes);
+                self.record_copy_breakdown(semantics, bytes);
             }
             CopySelector::Unused => unreachable!(),
         }
     }
 
+    /// Tally the bytes copied with the given copy semantics if the option `record_copy_breakdown`
+    /// is enabled.
+    fn record_copy_breakdown(&mut self, semantics: CopySemantics, bytes: usize) {
+        if let Some(breakdown) = self.copy_breakdown.as_mut() {
+            breakdown[semantics] += bytes;
+        }
+    }
+
+    /// Take the bytes copied for each copy semantics by this copy context since the last call, or
+    /// `None` if the option `record_copy_breakdown` is not enabled.
+    pub(crate) fn take_copy_breakdown(&mut self) -> Option<EnumMap<CopySemantics, usize>> {
+        self.copy_breakdown.as_mut().map(std::mem::take)
+    }
+
     /// Prepare the copying allocators.
     pub fn prepare(&mut self) {
         // Delegate to prepare() for each policy copy context
@@ -160,6 +248,24 @@ impl<VM: VMBinding> GCWorkerCopyContext<VM> {
         }
     }
 
+    /// Acquire a clean block for each Immix copying allocator that may copy in the current GC.
+    /// See the option `immix_prewarm_copy_allocators`.
+    pub(crate) fn prewarm_immix_allocators(&mut self) {
+        // Each allocator appears once in `space_mapping`, but may appear more than once in
+        // `copy_mapping`.
+        for &(selector, _) in self.config.space_mapping.iter() {
+            match selector {
+                CopySelector::Immix(index) => {
+                    unsafe { self.immix[index as usize].assume_init_mut() }.prewarm()
+                }
+                CopySelector::ImmixHybrid(index) => {
+                    unsafe { self.immix_hybrid[index as usize].assume_init_mut() }.prewarm()
+                }
+                CopySelector::CopySpace(_) | CopySelector::Unused => {}
+            }
+        }
+    }
+
     /// Release the copying allocators.
     pub fn release(&mut self) {
         // Delegate to release() for each policy copy context
@@ -179,6 +285,51 @@ impl<VM: VMBinding> GCWorkerCopyContext<VM> {
         }
     }
 
+    /// Get the block acquisition statistics of the copying allocators since the last call, and
+    /// clear the statistics.
+    pub fn take_alloc_stats(&mut self) -> CopyAllocStats {
+        let mut stats = CopyAllocStats::default();
+        for &(selector, _) in self.config.space_mapping.iter() {
+            stats += match selector {
+                CopySelector::CopySpace(index) => {
+                    unsafe { self.copy[index as usize].assume_init_mut() }.take_alloc_stats()
+                }
+                CopySelector::Immix(index) => {
+                    unsafe { self.immix[index as usize].assume_init_mut() }.take_alloc_stats()
+                }
+                CopySelector::ImmixHybrid(index) => {
+                    unsafe { self.immix_hybrid[index as usize].assume_init_mut() }
+                        .take_alloc_stats()
+                }
+                CopySelector::Unused => unreachable!(),
+            };
+        }
+        stats
+    }
+
+    /// Record that `old` is copied to `new` with `semantics` in the forwarding log.
+    #[cfg(feature = "forwarding_log")]
+    pub(crate) fn record_forwarding(
+        &mut self,
+        old: ObjectReference,
+        new: ObjectReference,
+        semantics: CopySemantics,
+    ) {
+        let worker = self.worker_tls;
+        self.forwarding_log.record(ForwardingLogEntry {
+            old,
+            new,
+            semantics,
+            worker,
+        });
+    }
+
+    /// Get the entries of the forwarding log since the last call, and clear the log.
+    #[cfg(feature = "forwarding_log")]
+    pub(crate) fn take_forwarding_log(&mut self) -> Vec<ForwardingLogEntry> {
+        self.forwarding_log.take()
+    }
+
     /// Create a GCWorkerCopyContext based on the configuration for a copying plan.
     ///
     /// Arguments:
@@ -191,6 +342,15 @@ impl<VM: VMBinding> GCWorkerCopyContext<VM> {
             immix: unsafe { MaybeUninit::uninit().assume_init() },
             immix_hybrid: unsafe { MaybeUninit::uninit().assume_init() },
             config,
+            #[cfg(feature = "forwarding_log")]
+            worker_tls,
+            #[cfg(feature = "forwarding_log")]
+            forwarding_log: ForwardingLog::new(),
+            copy_breakdown: if *mmtk.get_options().record_copy_breakdown {
+                Some(EnumMap::default())
+            } else {
+                None
+            },
         };
         let context = Arc::new(AllocatorContext::new(mmtk));
 
@@ -232,6 +392,11 @@ impl<VM: VMBinding> GCWorkerCopyContext<VM> {
             immix: unsafe { MaybeUninit::uninit().assume_init() },
             immix_hybrid: unsafe { MaybeUninit::uninit().assume_init() },
             config: CopyConfig::default(),
+            #[cfg(feature = "forwarding_log")]
+            worker_tls: VMWorkerThread(crate::util::opaque_pointer::VMThread::UNINITIALIZED),
+            #[cfg(feature = "forwarding_log")]
+            forwarding_log: ForwardingLog::new(),
+            copy_breakdown: None,
         }
     }
 }
@@ -5,7 +5,7 @@ use crate::util::reference_processor::RescanReferences;
 use crate::util::ObjectReference;
 use crate::util::VMWorkerThread;
 use crate::vm::Finalizable;
-use crate::vm::{Collection, VMBinding};
+use crate::vm::{Collection, ReferenceGlue, VMBinding};
 use crate::MMTK;
 use std::marker::PhantomData;
 
@@ -41,7 +41,10 @@ impl<F: Finalizable> FinalizableProcessor<F> {
         finalizable.keep_alive::<E>(e);
     }
 
-    pub fn scan<E: ProcessEdgesWork>(&mut self, tls: VMWorkerThread, e: &mut E, nursery: bool) {
+    pub fn scan<E: ProcessEdgesWork>(&mut self, tls: VMWorkerThread, e: &mut E, nursery: bool)
+    where
+        <E::VM as VMBinding>::VMReferenceGlue: ReferenceGlue<E::VM, FinalizableType = F>,
+    {
         let start = if nursery { self.nursery_index } else { 0 };
 
         // We should go through ready_for_finalize objects and keep them alive.
@@ -73,6 +76,14 @@ impl<F: Finalizable> FinalizableProcessor<F> {
         // Keep the finalizable objects alive.
         self.forward_finalizable(e, nursery);
 
+        // Sort them after they are kept alive so that the comparator may read the objects.
+        if <E::VM as VMBinding>::VMReferenceGlue::SORT_READY_FINALIZERS {
+            // `get_ready_object` pops from the end, so we sort them in the descending order.
+            self.ready_for_finalize.sort_by(|a, b| {
+                <E::VM as VMBinding>::VMReferenceGlue::compare_ready_finalizers(b, a)
+            });
+        }
+
         // Set nursery_index to the end of the candidates (the candidates before the index are scanned)
         self.nursery_index = self.candidates.len();
 
@@ -173,6 +173,15 @@ impl<VM: VMBinding, B: Region> BlockPageResource<VM, B> {
         self.block_queue.push(block)
     }
 
+    /// Release blocks directly to the global pool.  Unlike [`BlockPageResource::release_block`],
+    /// this can be called by any thread, including mutators.
+    pub fn release_blocks_global(&self, blocks: &[B]) {
+        let pages = blocks.len() << Self::LOG_PAGES;
+        debug_assert!(pages <= self.common().accounting.get_committed_pages());
+        self.common().accounting.release(pages);
+        self.block_queue.push_global(blocks)
+    }
+
     pub fn flush_all(&self) {
         self.block_queue.flush_all()
         // TODO: For 32-bit space, we may want to free some contiguous chunks.
@@ -343,6 +352,20 @@ impl<B: Region> BlockPool<B> {
         }
     }
 
+    /// Push blocks directly to the global pool.  Unlike [`BlockPool::push`], this can be called by
+    /// any thread, including mutators.
+    pub fn push_global(&self, blocks: &[B]) {
+        for chunk in blocks.chunks(BlockQueue::<B>::CAPACITY) {
+            let queue = BlockQueue::new();
+            for block in chunk {
+                // The queue is local to this thread until it is added to the global pool.
+                let result = unsafe { queue.push_relaxed(*block) };
+                debug_assert!(result.is_ok());
+            }
+            self.add_global_array(queue);
+        }
+    }
+
     /// Pop a block from the global pool
     pub fn pop(&self) -> Option<B> {
         if self.len() == 0 {
@@ -181,6 +181,29 @@ impl ChunkMap {
             .filter(|c| self.get(*c).is_some())
     }
 
+    /// Iterate over the chunks in the range of chunks this space has ever allocated, and return
+    /// whether each of them is currently allocated to this space.  The range may include chunks of
+    /// other spaces.  A chunk that is free in the chunk map is only included if the VM map still
+    /// records it as owned by this space, so chunks of other spaces are skipped, including those
+    /// of spaces that do not use a chunk map.
+    pub fn chunks_in_range(&self) -> impl Iterator<Item = (Chunk, bool)> + '_ {
+        let chunk_range = self.chunk_range.lock();
+        RegionIterator::<Chunk>::new(chunk_range.start, chunk_range.end).filter_map(|c| {
+            let state = self.get_internal(c);
+            if state.is_free() {
+                self.is_owned_by_space(c).then_some((c, false))
+            } else {
+                (state.get_space_index() == self.space_index).then_some((c, true))
+            }
+        })
+    }
+
+    /// Is the chunk owned by the space according to the VM map?
+    fn is_owned_by_space(&self, chunk: Chunk) -> bool {
+        let descriptor = crate::mmtk::VM_MAP.get_descriptor_for_address(chunk.start());
+        !descriptor.is_empty() && descriptor.get_index() == self.space_index
+    }
+
     /// Helper function to create per-chunk processing work packets for each allocated chunks.
     pub fn generate_tasks<VM: VMBinding>(
         &self,
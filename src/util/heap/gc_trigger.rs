@@ -118,6 +118,13 @@ impl<VM: VMBinding> GCTrigger<VM> {
         self.policy.is_heap_full(self.plan())
     }
 
+    /// Check if `pages` more pages can be reserved without exceeding the current heap size. Unlike
+    /// [`GCTrigger::poll`], this never requests a GC.
+    pub fn has_headroom_for(&self, pages: usize) -> bool {
+        let plan = self.plan();
+        plan.get_reserved_pages() + pages <= self.policy.get_current_heap_size_in_pages()
+    }
+
     /// Return upper bound of the nursery size (in number of bytes)
     pub fn get_max_nursery_bytes(&self) -> usize {
         use crate::util::options::NurserySize;
@@ -297,6 +297,16 @@ fn mmap_fixed(
     }
 }
 
+/// Tell the operating system that the given memory (in page granularity) is not needed, so that
+/// the physical pages can be reclaimed.  The memory stays mapped, and it reads as zero when it is
+/// accessed again.  This wraps the unsafe libc madvise call with `MADV_DONTNEED`.
+pub fn madvise_dontneed(start: Address, size: usize) -> Result<()> {
+    wrap_libc_call(
+        &|| unsafe { libc::madvise(start.to_mut_ptr(), size, libc::MADV_DONTNEED) },
+        0,
+    )
+}
+
 /// Unmap the given memory (in page granularity). This wraps the unsafe libc munmap call.
 pub fn munmap(start: Address, size: usize) -> Result<()> {
     wrap_libc_call(&|| unsafe { libc::munmap(start.to_mut_ptr(), size) }, 0)
@@ -19,6 +19,10 @@
 //! We implement several strategies depending on whether mmtk-core or the VM binding also requires
 //! the VO bits to also be available during tracing.
 //!
+//! Independent of the strategy, the VM binding may choose to clear the VO bits of dead objects
+//! lazily (see [`VOBitClearingStrategy`]).  In that case, we skip the bulk clearing, and clear the
+//! VO bits of a region when it is reused by an allocator, or when it is released.
+//!
 //! The handling is very sensitive to `VOBitUpdateStrategy`, and may be a bit verbose.
 //! We abstract VO-bit-related code out of the main GC algorithms (such as Immix) to make it more
 //! readable.
@@ -28,8 +32,8 @@ use atomic::Ordering;
 use crate::{
     util::{
         linear_scan::Region,
-        metadata::{vo_bit, MetadataSpec},
-        ObjectReference,
+        metadata::{vo_bit, vo_bit::VOBitClearingStrategy, MetadataSpec},
+        Address, ObjectReference,
     },
     vm::{ObjectModel, VMBinding},
 };
@@ -86,14 +90,20 @@ const fn strategy<VM: VMBinding>() -> VOBitUpdateStrategy {
     // TODO: Revisit this choice in the future if non-trivial changes are made and the performance
     // characterestics may change for the strategies.
     match VM::VMObjectModel::LOCAL_MARK_BIT_SPEC.as_spec() {
-        // Note that currently ImmixSpace doesn't support in-header mark bits,
-        // but the DummyVM for testing declares mark bits to be "in header" as a place holder
-        // because it never runs GC.
+        // In-header mark bits are cyclic in ImmixSpace, so they cannot be copied to VO bits.
         MetadataSpec::InHeader(_) => VOBitUpdateStrategy::ClearAndReconstruct,
         MetadataSpec::OnSide(_) => VOBitUpdateStrategy::CopyFromMarkBits,
     }
 }
 
+/// Return `true` if the VM binding chooses to clear the VO bits lazily.
+const fn is_lazy_clearing<VM: VMBinding>() -> bool {
+    matches!(
+        VM::VMObjectModel::VO_BIT_CLEARING_STRATEGY,
+        VOBitClearingStrategy::Lazy
+    )
+}
+
 pub(crate) fn validate_config<VM: VMBinding>() {
     assert!(
         !(VM::VMObjectModel::NEED_VO_BITS_DURING_TRACING
@@ -104,6 +114,12 @@ pub(crate) fn validate_config<VM: VMBinding>() {
 We currently don't have an appropriate strategy for this case."
     );
 
+    assert!(
+        !(VM::VMObjectModel::NEED_VO_BITS_DURING_TRACING && is_lazy_clearing::<VM>()),
+        "The VM binding needs VO bits during tracing but also clears VO bits lazily.  \
+The VO bits of dead objects may still be set during tracing with lazy clearing."
+    );
+
     let s = strategy::<VM>();
     match s {
         VOBitUpdateStrategy::ClearAndReconstruct => {
@@ -131,6 +147,10 @@ We currently don't have an appropriate strategy for this case."
 }
 
 pub(crate) fn need_to_clear_vo_bits_before_tracing<VM: VMBinding>() -> bool {
+    if is_lazy_clearing::<VM>() {
+        // The VO bits of dead objects are cleared when the regions are reused.
+        return false;
+    }
     match strategy::<VM>() {
         VOBitUpdateStrategy::ClearAndReconstruct => true,
         VOBitUpdateStrategy::CopyFromMarkBits => false,
@@ -150,6 +170,10 @@ pub(crate) fn on_trace_object<VM: VMBinding>(object: ObjectReference) {
 }
 
 pub(crate) fn on_object_marked<VM: VMBinding>(object: ObjectReference) {
+    if is_lazy_clearing::<VM>() {
+        // The VO bit was not cleared before tracing.  It is still set since the allocation.
+        return;
+    }
     match strategy::<VM>() {
         VOBitUpdateStrategy::ClearAndReconstruct => {
             // In this strategy, we set the VO bit when an object is marked.
@@ -184,6 +208,15 @@ pub(crate) fn on_object_forwarded<VM: VMBinding>(new_object: ObjectReference) {
 }
 
 pub(crate) fn on_region_swept<VM: VMBinding, R: Region>(region: &R, is_occupied: bool) {
+    if is_lazy_clearing::<VM>() {
+        // The VO bits of an occupied region are cleared line by line when its free lines are
+        // reused (see `on_lines_reused`).  A region without live objects is released, and its
+        // memory may be reused by other spaces, so we clear its VO bits now.
+        if !is_occupied {
+            vo_bit::bzero_vo_bit(region.start(), R::BYTES);
+        }
+        return;
+    }
     match strategy::<VM>() {
         VOBitUpdateStrategy::ClearAndReconstruct => {
             // Do nothing.  The VO bit metadata is already reconstructed.
@@ -200,3 +233,12 @@ pub(crate) fn on_region_swept<VM: VMBinding, R: Region>(region: &R, is_occupied:
         }
     }
 }
+
+/// Called when an allocator acquires the free lines `[start, start + size)` to allocate into.  If
+/// the VO bits are cleared lazily, the lines may still hold the VO bits of dead objects, and we
+/// clear them here.
+pub(crate) fn on_lines_reused<VM: VMBinding>(start: Address, size: usize) {
+    if is_lazy_clearing::<VM>() {
+        vo_bit::bzero_vo_bit(start, size);
+    }
+}
@@ -68,6 +68,29 @@ cfg_if::cfg_if! {
     }
 }
 
+/// When the VO bits of dead objects in ImmixSpace are cleared.  A VM binding selects the strategy
+/// with [`crate::vm::ObjectModel::VO_BIT_CLEARING_STRATEGY`].  Other policies always clear the VO
+/// bits of dead objects eagerly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VOBitClearingStrategy {
+    /// Clear the VO bits of dead objects during each GC, in bulk passes over the lines and blocks
+    /// (in the `ClearVOBits` bucket or when sweeping).  After a GC, the VO bits are set exactly for
+    /// the live objects.
+    Eager,
+    /// Do not clear the VO bits of dead objects in bulk.  The VO bits of a line are cleared when
+    /// the line is reused by an allocator, and the VO bits of a block are cleared when the block
+    /// is released.  This avoids the bulk passes, but the VO bits of dead objects in lines that are
+    /// not reused yet stay set.
+    ///
+    /// With this strategy, the VO bits are a superset of the live objects in ImmixSpace.
+    /// `is_mmtk_object` and the internal pointer queries may report a dead object that has not
+    /// been reclaimed yet, and iterating objects with the VO bits may visit such dead objects.
+    /// The fields of those dead objects may point to objects that have been reclaimed.  The VM
+    /// binding must not use this strategy if it uses the VO bits to find roots or references
+    /// conservatively, because a dead object found that way would be traced as if it were live.
+    Lazy,
+}
+
 /// The base address for VO bit side metadata on 64 bits platforms.
 #[cfg(target_pointer_width = "64")]
 pub const VO_BIT_SIDE_METADATA_ADDR: Address = VO_BIT_SIDE_METADATA_SPEC.get_absolute_offset();
@@ -40,7 +40,13 @@ pub fn attempt_to_forward<VM: VMBinding>(object: ObjectReference) -> u8 {
     }
 }
 
+/// The default number of times [`spin_and_get_forwarded_object`] spins before it starts yielding.
+/// It is the default value of the option `forwarding_spin_limit`.
+pub const DEFAULT_FORWARDING_SPIN_LIMIT: usize = 1000;
+
 /// Spin-wait for the object's forwarding to become complete and then read the forwarding pointer to the new object.
+/// This spins at most [`DEFAULT_FORWARDING_SPIN_LIMIT`] times before yielding.  See
+/// [`spin_and_get_forwarded_object_with_limit`].
 ///
 /// # Arguments:
 ///
@@ -52,9 +58,43 @@ pub fn attempt_to_forward<VM: VMBinding>(object: ObjectReference) -> u8 {
 pub fn spin_and_get_forwarded_object<VM: VMBinding>(
     object: ObjectReference,
     forwarding_bits: u8,
+) -> ObjectReference {
+    spin_and_get_forwarded_object_with_limit::<VM>(
+        object,
+        forwarding_bits,
+        DEFAULT_FORWARDING_SPIN_LIMIT,
+    )
+}
+
+/// Wait for the object's forwarding to become complete and then read the forwarding pointer to the new object.
+///
+/// The current thread busy-spins for at most `spin_limit` checks of the forwarding bits.  After
+/// that, it yields the CPU with `std::thread::yield_now()` between checks, so that the thread that
+/// is forwarding the object can make progress if the system is oversubscribed.  A `spin_limit` of
+/// `usize::MAX` means never yielding.
+///
+/// # Arguments:
+///
+/// * `object`: the forwarded/being_forwarded object.
+/// * `forwarding_bits`: the last state of the forwarding bits before calling this function.
+/// * `spin_limit`: the number of checks before yielding.
+///
+/// Returns a reference to the new object.
+///
+pub fn spin_and_get_forwarded_object_with_limit<VM: VMBinding>(
+    object: ObjectReference,
+    forwarding_bits: u8,
+    spin_limit: usize,
 ) -> ObjectReference {
     let mut forwarding_bits = forwarding_bits;
+    let mut spins = 0;
     while forwarding_bits == BEING_FORWARDED {
+        if spins < spin_limit {
+            spins += 1;
+            std::hint::spin_loop();
+        } else {
+            std::thread::yield_now();
+        }
         forwarding_bits = get_forwarding_status::<VM>(object);
     }
 
@@ -97,6 +137,8 @@ pub fn forward_object<VM: VMBinding>(
     on_after_forwarding: impl FnOnce(ObjectReference),
 ) -> ObjectReference {
     let new_object = VM::VMObjectModel::copy(object, semantics, copy_context);
+    #[cfg(feature = "forwarding_log")]
+    copy_context.record_forwarding(object, new_object, semantics);
     on_after_forwarding(new_object);
     if let Some(shift) = forwarding_bits_offset_in_forwarding_pointer::<VM>() {
         VM::VMObjectModel::LOCAL_FORWARDING_POINTER_SPEC.store_atomic::<VM, usize>(
@@ -866,7 +866,123 @@ options! {
     /// This only affects the memory for MMTk spaces.
     transparent_hugepages: bool                  [env_var: true, command_line: true]  [|v: &bool| !v || cfg!(target_os = "linux")] = false,
     /// Count live bytes for objects in each space during a GC.
-    count_live_bytes_in_gc: bool                 [env_var: true, command_line: true] [always_valid] = false
+    count_live_bytes_in_gc: bool                 [env_var: true, command_line: true] [always_valid] = false,
+    /// The maximum number of object-scanning work packets a GC worker executes immediately (see
+    /// `ProcessEdgesWork::SCAN_OBJECTS_IMMEDIATELY`) before it starts dispatching them to work buckets
+    /// so that other workers can steal them.  The count is reset when the worker gets a work packet
+    /// from the scheduler.  By default, there is no limit.
+    immediate_scan_depth_limit: usize            [env_var: true, command_line: true] [|v: &usize| *v > 0] = usize::MAX,
+    /// Execute the object-scanning work packets created by root-processing work packets immediately
+    /// (see `ProcessEdgesWork::SCAN_OBJECTS_IMMEDIATELY`).  If disabled, those packets are dispatched
+    /// to work buckets so that the objects directly reachable from roots are scanned by all workers
+    /// instead of the worker that processed the roots.  Other packets are not affected.
+    scan_roots_immediately: bool                 [env_var: true, command_line: true] [always_valid] = true,
+    /// The maximum number of objects scanned by one scan-objects work packet.  If more objects are
+    /// enqueued by a process-edges work packet, the rest are scanned by separate packets in the same
+    /// bucket so that no single packet runs for too long.  By default, there is no limit.
+    max_objects_per_scan_packet: usize           [env_var: true, command_line: true] [|v: &usize| *v > 0] = usize::MAX,
+    /// The maximum number of slots processed by one process-edges work packet.  If a packet has
+    /// more slots (e.g. a large root set), the rest are processed by a new packet in the same bucket
+    /// so that the worker can pick up higher-priority work in between.  By default, there is no limit.
+    max_slots_per_process_packet: usize          [env_var: true, command_line: true] [|v: &usize| *v > 0] = usize::MAX,
+    /// The fraction of the free pages at the start of a GC that the promotion copy allocator of the
+    /// Immix space may use for clean blocks in a nursery GC of StickyImmix.  Once the budget is used
+    /// up, the remaining nursery objects are marked in place instead of being copied.  This has no
+    /// effect on GenImmix, whose nursery objects must always be copied out of the nursery.  1.0
+    /// means no separate limit.
+    immix_promotion_copy_budget: f64             [env_var: true, command_line: true] [|v: &f64| *v > 0.0 && *v <= 1.0] = 1.0,
+    /// The fraction of the free pages at the start of a defrag GC that the defrag copy allocator of
+    /// the Immix space may use for clean blocks.  Once the budget is used up, the remaining live
+    /// objects in defrag source blocks are marked in place.  1.0 means no separate limit.
+    immix_defrag_copy_budget: f64                [env_var: true, command_line: true] [|v: &f64| *v > 0.0 && *v <= 1.0] = 1.0,
+    /// The maximum number of times `Scanning::process_weak_refs` may be called in one GC.  If the
+    /// binding still asks for repeating after that, MMTk panics in debug builds, and logs a warning
+    /// and stops repeating in release builds.  This turns an endless loop caused by a buggy binding
+    /// into a diagnosable error.  By default, there is no limit.
+    max_weak_ref_iterations: usize               [env_var: true, command_line: true] [|v: &usize| *v > 0] = usize::MAX,
+    /// Record the length of the largest hole of each reusable Immix block when sweeping, and let
+    /// allocation requests larger than a line try a reusable block with a large enough hole before
+    /// acquiring a clean block.
+    immix_track_max_hole: bool                   [env_var: true, command_line: true] [always_valid] = false,
+    /// The maximum number of blocks selected as defrag sources in each defrag GC of Immix.  If more
+    /// blocks exceed the defrag threshold, the blocks with the most holes are selected.  This bounds
+    /// the pause time of defrag GCs at the cost of recovering from fragmentation more slowly.  By
+    /// default, there is no limit.
+    immix_max_defrag_blocks_per_gc: usize        [env_var: true, command_line: true] [|v: &usize| *v > 0] = usize::MAX,
+    /// Skip sweeping Immix spaces in the `Release` stage, leaving all the blocks allocated.  Marking
+    /// is still done, so its correctness can be checked in isolation from sweeping.  The heap leaks
+    /// and will run out of memory quickly.  This is for debugging only.
+    immix_skip_sweep: bool                       [env_var: true, command_line: true] [always_valid] = false,
+    /// Return the memory of the chunks of Immix spaces that have stayed empty for a few GCs to the
+    /// operating system, reducing the resident set size at the cost of page faults when the chunks
+    /// are used again.
+    immix_return_empty_chunks: bool              [env_var: true, command_line: true] [always_valid] = false,
+    /// Defer sweeping the live blocks of Immix spaces until the blocks are taken for allocation
+    /// after a GC, spreading the sweeping work over the mutator time instead of doing it all in the
+    /// `Release` stage.  See `ImmixSpaceArgs::lazy_sweep`.
+    immix_lazy_sweep: bool                       [env_var: true, command_line: true] [always_valid] = false,
+    /// The number of GC workers that execute the packets in the `Prepare` bucket.  If it is not 0,
+    /// only the workers with ordinals less than this number poll packets from the bucket, which can
+    /// reduce memory bandwidth contention on NUMA systems.  0 means all the workers.
+    prepare_bucket_workers: usize                [env_var: true, command_line: true] [always_valid] = 0,
+    /// The number of GC workers that execute the packets in the `Release` bucket, including
+    /// sweeping.  See `prepare_bucket_workers`.  0 means all the workers.
+    release_bucket_workers: usize                [env_var: true, command_line: true] [always_valid] = 0,
+    /// Execute GC work packets one at a time in a deterministic order.  All the work packets in
+    /// work buckets are executed by the worker with ordinal 0, in FIFO order within each bucket and
+    /// in stage order across buckets.  Other workers are still created, but they only execute work
+    /// packets designated to them, and never concurrently with other packets.  This is for debugging
+    /// only (e.g. to make logs reproducible).  It is slow, and it may deadlock if some work relies on
+    /// other work being executed concurrently.
+    deterministic_gc_scheduling: bool           [env_var: true, command_line: true] [always_valid] = false,
+    /// Mark Immix lines when scanning objects.  Otherwise, lines are marked when objects are
+    /// traced.  This is used by the Immix-based plans (Immix, GenImmix and StickyImmix).
+    immix_mark_line_at_scan_time: bool          [env_var: true, command_line: true] [always_valid] = true,
+    /// Record the wall-clock time spent in each work bucket stage in every GC, which can be queried
+    /// with `MMTK::last_gc_bucket_times`.
+    record_bucket_times: bool                   [env_var: true, command_line: true] [always_valid] = false,
+    /// Measure the time each GC worker spends executing work packets, so that the idle time of the
+    /// workers and the parallel efficiency of every GC can be queried with
+    /// `memory_manager::worker_idle_time_in_last_gc` and
+    /// `memory_manager::parallel_efficiency_in_last_gc`.  This reads the clock twice for every work
+    /// packet.
+    record_worker_idle_time: bool               [env_var: true, command_line: true] [always_valid] = false,
+    /// Record every `(parent, child)` edge found when scanning objects with slot-enqueuing in a GC,
+    /// which can be retrieved with `memory_manager::take_heap_edges_in_last_gc`.  This is for
+    /// debugging retention (e.g. building a dominator tree offline), and it is expensive.
+    record_heap_edges: bool                     [env_var: true, command_line: true] [always_valid] = false,
+    /// The number of times a GC worker busy-spins while waiting for another worker to finish
+    /// forwarding an object.  After that, the worker yields the CPU between checks, which avoids
+    /// wasting CPU (or livelocking) under heavy contention on oversubscribed systems.  Setting it to
+    /// `usize::MAX` disables yielding.
+    forwarding_spin_limit: usize                [env_var: true, command_line: true] [always_valid] = crate::util::object_forwarding::DEFAULT_FORWARDING_SPIN_LIMIT,
+    /// Record the bytes copied into Immix spaces for each copy semantics in every GC, which can be
+    /// queried with `MMTK::last_gc_copy_breakdown`.  This is for diagnosing copy amplification.
+    record_copy_breakdown: bool                 [env_var: true, command_line: true] [always_valid] = false,
+    /// The minimum alignment of objects copied by the copy context of non-generational Immix plans
+    /// (Immix and StickyImmix).  If it is larger than the alignment requested for an object, the
+    /// object is copied to an address aligned to this value instead, e.g. to align copied objects
+    /// to cache lines.  It must be 0 (no override) or a power of two, and must not be larger than
+    /// `VMBinding::MAX_ALIGNMENT`.  Over-aligning wastes the space between objects (up to this value
+    /// minus the requested alignment for each copied object), which makes the heap fill up and
+    /// defragment faster.
+    immix_copy_min_alignment: usize             [env_var: true, command_line: true] [|v: &usize| *v == 0 || v.is_power_of_two()] = 0,
+    /// Warn if the number of work packets in a work bucket exceeds this value, which may indicate
+    /// runaway memory use, e.g. a binding creating millions of tiny root packets.  It warns at most
+    /// once per bucket in each GC.  Packets in the local queues of workers are not counted.  0
+    /// disables the check.
+    work_packet_warn_threshold: usize           [env_var: true, command_line: true] [always_valid] = 0,
+    /// Sort the objects in each object-scanning work packet by address before scanning them, so
+    /// that memory is accessed more sequentially.  Sorting costs O(n log n) for each packet, and
+    /// may or may not pay off with fewer cache and TLB misses depending on the workload and the
+    /// heap size.  Measure before enabling it.
+    sort_scan_buffer: bool                      [env_var: true, command_line: true] [always_valid] = false,
+    /// Acquire a clean block for each Immix copy allocator before tracing starts, so that the first
+    /// copies in a GC do not need to take the slow path.  A block is only acquired for a copy
+    /// allocator that may copy in the current GC, and it is taken from the copy budget like any
+    /// other clean block.  If nothing is copied into the block, it is released at the end of the
+    /// GC.
+    immix_prewarm_copy_allocators: bool         [env_var: true, command_line: true] [always_valid] = false
 }
 
 #[cfg(test)]
@@ -225,6 +225,6 @@ impl<VM: VMBinding> ProcessEdgesWork for SanityGCProcessEdges<VM> {
     }
 
     fn create_scan_work(&self, nodes: Vec<ObjectReference>) -> Self::ScanObjectsWorkType {
-        ScanObjects::<Self>::new(nodes, false, WorkBucketStage::Closure)
+        ScanObjects::<Self>::new(nodes, false, WorkBucketStage::Closure, None)
     }
 }
@@ -8,11 +8,15 @@ use crate::plan::Plan;
 use crate::vm::slot::Slot;
 use crate::vm::VMBinding;
 use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::RwLock;
 
 pub struct SlotLogger<SL: Slot> {
     // A private hash-set to keep track of slots.
     slot_log: RwLock<HashSet<SL>>,
+    // The number of times this logger has been reset.  GC workers use it to know when to clear
+    // their own logs of enqueued objects.  See `GCWorker::log_enqueued_object`.
+    epoch: AtomicUsize,
 }
 
 unsafe impl<SL: Slot> Sync for SlotLogger<SL> {}
@@ -21,6 +25,7 @@ impl<SL: Slot> SlotLogger<SL> {
     pub fn new() -> Self {
         Self {
             slot_log: Default::default(),
+            epoch: AtomicUsize::new(0),
         }
     }
 
@@ -47,6 +52,13 @@ impl<SL: Slot> SlotLogger<SL> {
     pub fn reset(&self) {
         let mut slot_log = self.slot_log.write().unwrap();
         slot_log.clear();
+        self.epoch.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Get the number of times this logger has been reset.  Each reset starts a new round of
+    /// tracing, in which every object may be enqueued once again.
+    pub fn epoch(&self) -> usize {
+        self.epoch.load(Ordering::SeqCst)
     }
 }
 
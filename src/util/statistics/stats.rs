@@ -46,6 +46,12 @@ impl SharedStats {
 pub struct Stats {
     gc_count: AtomicUsize,
     total_time: Arc<Mutex<Timer>>,
+    /// The time (in nanoseconds) between starting to stop the mutators and all the mutators
+    /// being paused, i.e. the time the VM takes to reach safepoints.
+    pub(crate) time_to_safepoint: Arc<Mutex<EventCounter>>,
+    /// The total time (in nanoseconds) GC workers were idle during GCs, i.e. the GC time of all
+    /// workers minus the time they spent executing work packets.
+    pub(crate) worker_idle_time: Arc<Mutex<EventCounter>>,
     // crate `pfm` uses libpfm4 under the hood for parsing perf event names
     // Initialization of libpfm4 is required before we can use `PerfEvent` types
     #[cfg(feature = "perf_counter")]
@@ -79,6 +85,20 @@ impl Stats {
             MonotoneNanoTime {},
         )));
         counters.push(t.clone());
+        let time_to_safepoint = Arc::new(Mutex::new(EventCounter::new(
+            "time_to_safepoint.ns".to_string(),
+            shared.clone(),
+            true,
+            true,
+        )));
+        counters.push(time_to_safepoint.clone());
+        let worker_idle_time = Arc::new(Mutex::new(EventCounter::new(
+            "worker_idle.ns".to_string(),
+            shared.clone(),
+            true,
+            true,
+        )));
+        counters.push(worker_idle_time.clone());
         // Read from the MMTK option for a list of perf events we want to
         // measure, and create corresponding counters
         #[cfg(feature = "perf_counter")]
@@ -94,6 +114,8 @@ impl Stats {
         Stats {
             gc_count: AtomicUsize::new(0),
             total_time: t,
+            time_to_safepoint,
+            worker_idle_time,
             #[cfg(feature = "perf_counter")]
             perfmon,
             shared,
@@ -34,3 +34,17 @@ pub fn set_meta_bits(
 ) {
     SideMetadataSpec::set_meta_bits(meta_start_addr, meta_start_bit, meta_end_addr, meta_end_bit)
 }
+
+/// Remove all the blocks from the reusable block list of the Immix space of the current plan, so
+/// that the following allocations come from clean blocks.  Returns the number of blocks removed, or
+/// 0 if the plan does not have an Immix space.  See `ImmixSpace::clear_reusable_blocks_for_test`.
+pub fn immix_clear_reusable_blocks<VM: crate::vm::VMBinding>(mmtk: &crate::MMTK<VM>) -> usize {
+    use crate::policy::immix::ImmixSpace;
+    let mut cleared = 0;
+    mmtk.get_plan().for_each_space(&mut |space| {
+        if let Some(immix_space) = space.downcast_ref::<ImmixSpace<VM>>() {
+            cleared += immix_space.clear_reusable_blocks_for_test();
+        }
+    });
+    cleared
+}
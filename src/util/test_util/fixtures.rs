@@ -6,8 +6,13 @@ use std::sync::Mutex;
 use std::sync::Once;
 
 use crate::memory_manager;
-use crate::util::test_util::mock_vm::MockVM;
+use crate::scheduler::PolledPacket;
+use crate::util::constants::BYTES_IN_WORD;
+use crate::util::test_util::mock_method::{MockAnyDefault, MockMethod};
+use crate::util::test_util::mock_vm::{write_mockvm, MockVM, DEFAULT_OBJECT_REF_OFFSET};
+use crate::util::{Address, OpaquePointer, VMWorkerThread};
 use crate::util::{ObjectReference, VMMutatorThread, VMThread};
+use crate::vm::GCThreadContext;
 use crate::AllocationSemantics;
 use crate::MMTKBuilder;
 use crate::MMTK;
@@ -280,3 +285,293 @@ impl FixtureContent for TwoObjects {
         }
     }
 }
+
+/// The state shared between a [`GCFixture`] and the mock methods it installs.
+struct GCFixtureState {
+    /// The only mutator.  Set after the mutator is bound.
+    mutator: std::sync::atomic::AtomicPtr<Mutator<MockVM>>,
+    /// The roots.  The root scanning mock reports the address of each element as a slot, so the
+    /// GC updates the roots in place if the objects are moved.
+    roots: Mutex<Vec<ObjectReference>>,
+    /// The pinning roots.  The objects they point to are not moved.
+    pinning_roots: Mutex<Vec<ObjectReference>>,
+    /// The transitively pinning roots.  The objects reachable from them are not moved.
+    tpinning_roots: Mutex<Vec<ObjectReference>>,
+    /// The synchronization between the mutator and the GC workers.
+    sync: Mutex<GCFixtureSync>,
+    cond: std::sync::Condvar,
+}
+
+struct GCFixtureSync {
+    /// Is the mutator blocked in `block_for_gc`?
+    mutator_blocked: bool,
+    /// The number of GCs that have finished.
+    gc_count: usize,
+}
+
+/// A fixture that runs GCs with `MockVM`.  It spawns real GC worker threads, and mocks the VM
+/// methods needed for GC:
+///
+/// -   There is only one mutator.  `stop_all_mutators` waits until the mutator blocks in
+///     `block_for_gc`, and `block_for_gc` waits until the GC finishes.
+/// -   Objects allocated with [`GCFixture::alloc`] have no reference fields.  The object size is
+///     stored in the second word of the object so that objects can be copied.
+/// -   The roots are the objects added with [`GCFixture::add_root`].  They are reported as slots
+///     by `scan_vm_specific_roots`, and are updated if the objects are moved.
+///
+/// As the GC workers keep running after the test finishes, the MMTk instance is never dropped.
+/// The fixture must be created within [`with_mockvm`], and the test must not overwrite the mock
+/// methods installed by the fixture.
+pub struct GCFixture {
+    mutator: std::mem::ManuallyDrop<MutatorFixture>,
+    state: std::sync::Arc<GCFixtureState>,
+}
+
+impl GCFixture {
+    /// The smallest object that can be allocated with [`GCFixture::alloc`].  It is the offset of
+    /// the object reference, the header word, and the size word.
+    pub const MIN_OBJECT_SIZE: usize = DEFAULT_OBJECT_REF_OFFSET + 2 * BYTES_IN_WORD;
+
+    pub fn create_with_builder<F>(with_builder: F) -> Self
+    where
+        F: FnOnce(&mut MMTKBuilder),
+    {
+        let state = std::sync::Arc::new(GCFixtureState {
+            mutator: std::sync::atomic::AtomicPtr::new(std::ptr::null_mut()),
+            roots: Mutex::new(vec![]),
+            pinning_roots: Mutex::new(vec![]),
+            tpinning_roots: Mutex::new(vec![]),
+            sync: Mutex::new(GCFixtureSync {
+                mutator_blocked: false,
+                gc_count: 0,
+            }),
+            cond: std::sync::Condvar::new(),
+        });
+        write_mockvm(|mock| Self::install_mock_methods(mock, &state));
+
+        let mutator = MutatorFixture::create_with_builder(with_builder);
+        let mutator_ptr = &*mutator.mutator as *const Mutator<MockVM> as *mut Mutator<MockVM>;
+        state
+            .mutator
+            .store(mutator_ptr, std::sync::atomic::Ordering::SeqCst);
+
+        GCFixture {
+            mutator: std::mem::ManuallyDrop::new(mutator),
+            state,
+        }
+    }
+
+    fn install_mock_methods(mock: &mut MockVM, state: &std::sync::Arc<GCFixtureState>) {
+        fn the_mutator(state: &GCFixtureState) -> &'static mut Mutator<MockVM> {
+            let mutator = state.mutator.load(std::sync::atomic::Ordering::SeqCst);
+            assert!(!mutator.is_null(), "The mutator is not bound yet");
+            unsafe { &mut *mutator }
+        }
+
+        // Active plan
+        mock.number_of_mutators = MockMethod::new_fixed(Box::new(|()| 1));
+        mock.is_mutator = MockMethod::new_fixed(Box::new(|tls| tls == VMThread::UNINITIALIZED));
+        let s = state.clone();
+        mock.mutator = MockMethod::new_fixed(Box::new(move |_| the_mutator(&s)));
+        let s = state.clone();
+        mock.mutators = MockMethod::new_fixed(Box::new(move |()| {
+            Box::new(std::iter::once(the_mutator(&s)))
+        }));
+
+        // Collection
+        mock.spawn_gc_thread = MockMethod::new_fixed(Box::new(|(_, ctx)| {
+            let GCThreadContext::Worker(worker) = ctx;
+            let mmtk = worker.mmtk;
+            // Give each worker a distinct non-null `VMThread` so that it is not a mutator.
+            let tls = VMWorkerThread(VMThread(OpaquePointer::from_address(unsafe {
+                Address::from_usize((worker.ordinal + 1) * BYTES_IN_WORD)
+            })));
+            std::thread::spawn(move || memory_manager::start_worker(mmtk, tls, worker));
+        }));
+        let s = state.clone();
+        mock.stop_all_mutators = MockMethod::new_fixed(Box::new(move |(_, mut visitor)| {
+            let mut sync = s.sync.lock().unwrap();
+            while !sync.mutator_blocked {
+                sync = s.cond.wait(sync).unwrap();
+            }
+            drop(sync);
+            visitor(the_mutator(&s));
+        }));
+        let s = state.clone();
+        mock.resume_mutators = MockMethod::new_fixed(Box::new(move |_| {
+            let mut sync = s.sync.lock().unwrap();
+            sync.gc_count += 1;
+            s.cond.notify_all();
+        }));
+        let s = state.clone();
+        mock.block_for_gc = MockMethod::new_fixed(Box::new(move |_| {
+            let mut sync = s.sync.lock().unwrap();
+            let gc_count = sync.gc_count;
+            sync.mutator_blocked = true;
+            s.cond.notify_all();
+            while sync.gc_count == gc_count {
+                sync = s.cond.wait(sync).unwrap();
+            }
+            sync.mutator_blocked = false;
+        }));
+
+        // Object model
+        mock.get_object_size = MockMethod::new_fixed(Box::new(Self::object_size));
+        mock.get_object_size_when_copied = MockMethod::new_fixed(Box::new(Self::object_size));
+        mock.get_object_reference_when_copied_to =
+            MockMethod::new_fixed(Box::new(|(_, to)| MockVM::object_start_to_ref(to)));
+        mock.copy_object = MockMethod::new_fixed(Box::new(|(from, semantics, copy_context)| {
+            let bytes = Self::object_size(from);
+            let from_start = from.to_raw_address() - DEFAULT_OBJECT_REF_OFFSET;
+            let to_start = copy_context.alloc_copy(from, bytes, BYTES_IN_WORD, 0, semantics);
+            unsafe {
+                std::ptr::copy_nonoverlapping(
+                    from_start.to_ptr::<u8>(),
+                    to_start.to_mut_ptr::<u8>(),
+                    bytes,
+                );
+            }
+            let to = MockVM::object_start_to_ref(to_start);
+            copy_context.post_copy(to, bytes, semantics);
+            to
+        }));
+
+        // Scanning
+        mock.scan_object = MockMethod::new_default();
+        mock.scan_roots_in_mutator_thread = MockMethod::new_default();
+        let s = state.clone();
+        mock.scan_vm_specific_roots = MockMethod::new_fixed(Box::new(move |(_, factory)| {
+            let slots = s
+                .roots
+                .lock()
+                .unwrap()
+                .iter_mut()
+                .map(|root| Address::from_mut_ptr(root as *mut ObjectReference))
+                .collect::<Vec<_>>();
+            if !slots.is_empty() {
+                factory.create_process_roots_work(slots);
+            }
+            let nodes = s.pinning_roots.lock().unwrap().clone();
+            if !nodes.is_empty() {
+                factory.create_process_pinning_roots_work(nodes);
+            }
+            let nodes = s.tpinning_roots.lock().unwrap().clone();
+            if !nodes.is_empty() {
+                factory.create_process_tpinning_roots_work(nodes);
+            }
+        }));
+        mock.notify_initial_thread_scan_complete = MockMethod::new_default();
+        mock.process_weak_refs = Box::new(MockAnyDefault::<bool>::default());
+        mock.forward_weak_refs = Box::new(MockAnyDefault::<()>::default());
+    }
+
+    /// The size of an object allocated with [`GCFixture::alloc`].
+    fn object_size(object: ObjectReference) -> usize {
+        unsafe { (object.to_raw_address() + BYTES_IN_WORD).load::<usize>() }
+    }
+
+    pub fn mmtk(&self) -> &'static MMTK<MockVM> {
+        self.mutator.mmtk()
+    }
+
+    pub fn mutator_mut(&mut self) -> &mut Mutator<MockVM> {
+        &mut self.mutator.mutator
+    }
+
+    /// Allocate an object of `size` bytes.  `size` must be at least
+    /// [`GCFixture::MIN_OBJECT_SIZE`].  The object is not a root.
+    pub fn alloc(&mut self, size: usize, semantics: AllocationSemantics) -> ObjectReference {
+        assert!(size >= Self::MIN_OBJECT_SIZE);
+        let addr = memory_manager::alloc(self.mutator_mut(), size, BYTES_IN_WORD, 0, semantics);
+        assert!(!addr.is_zero());
+        let object = MockVM::object_start_to_ref(addr);
+        unsafe {
+            object.to_raw_address().store::<usize>(0);
+            (object.to_raw_address() + BYTES_IN_WORD).store::<usize>(size);
+        }
+        memory_manager::post_alloc(self.mutator_mut(), object, size, semantics);
+        object
+    }
+
+    /// Allocate `count` objects of `size` bytes, and add every `root_every`-th of them to the
+    /// roots, starting from the first one.  Return all the allocated objects.
+    pub fn alloc_with_roots(
+        &mut self,
+        count: usize,
+        size: usize,
+        root_every: usize,
+    ) -> Vec<ObjectReference> {
+        let objects = (0..count)
+            .map(|_| self.alloc(size, AllocationSemantics::Default))
+            .collect::<Vec<_>>();
+        for object in objects.iter().step_by(root_every) {
+            self.add_root(*object);
+        }
+        objects
+    }
+
+    /// Assert that the objects the roots point to are live and still have `size` bytes.
+    pub fn assert_roots_intact(&self, size: usize) {
+        for root in self.roots() {
+            assert!(root.is_live(), "{} is dead", root);
+            assert_eq!(Self::object_size(root), size, "{} is corrupted", root);
+        }
+    }
+
+    /// Add `object` to the roots.
+    pub fn add_root(&self, object: ObjectReference) {
+        self.state.roots.lock().unwrap().push(object);
+    }
+
+    /// Add `object` to the pinning roots.
+    pub fn add_pinning_root(&self, object: ObjectReference) {
+        self.state.pinning_roots.lock().unwrap().push(object);
+    }
+
+    /// Add `object` to the transitively pinning roots.
+    pub fn add_tpinning_root(&self, object: ObjectReference) {
+        self.state.tpinning_roots.lock().unwrap().push(object);
+    }
+
+    /// Remove all the roots, including pinning roots.
+    pub fn clear_roots(&self) {
+        self.state.roots.lock().unwrap().clear();
+        self.state.pinning_roots.lock().unwrap().clear();
+        self.state.tpinning_roots.lock().unwrap().clear();
+    }
+
+    /// Get the roots.  They are updated if the GC moved the objects.
+    pub fn roots(&self) -> Vec<ObjectReference> {
+        self.state.roots.lock().unwrap().clone()
+    }
+
+    /// Get the number of GCs that have finished.
+    pub fn gc_count(&self) -> usize {
+        self.state.sync.lock().unwrap().gc_count
+    }
+
+    /// Take the records of the packets taken from the stage buckets since the last call.
+    pub(crate) fn take_polled_packets(&self) -> Vec<PolledPacket> {
+        std::mem::take(&mut *self.mmtk().scheduler.polled_packets.lock().unwrap())
+    }
+
+    /// Trigger a full-heap GC, and block until it finishes.
+    pub fn gc(&mut self) {
+        self.trigger_gc(true);
+    }
+
+    /// Trigger a GC without forcing it to be full-heap, and block until it finishes.  For
+    /// generational plans, this is a nursery GC unless the plan decides otherwise.
+    pub fn nursery_gc(&mut self) {
+        self.trigger_gc(false);
+    }
+
+    fn trigger_gc(&mut self, exhaustive: bool) {
+        let gc_count = self.gc_count();
+        let tls = VMMutatorThread(VMThread::UNINITIALIZED);
+        assert!(self
+            .mmtk()
+            .handle_user_collection_request(tls, true, exhaustive));
+        assert!(self.gc_count() > gc_count);
+    }
+}
@@ -1,4 +1,6 @@
 use std::any::Any;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
 /// `MockAny` hides any type information. It is useful when we want to create
 /// a mock method for methods with generic type parameters.
@@ -20,10 +22,29 @@ impl<I: 'static, R: 'static> MockAny for MockMethod<I, R> {
     }
 }
 
+/// A `MockAny` that ignores the arguments and returns the default value of `R`.  This is useful
+/// for methods that are called but not of interest in a test, when the argument types are hard to
+/// name.
+pub struct MockAnyDefault<R>(std::marker::PhantomData<R>);
+
+impl<R> Default for MockAnyDefault<R> {
+    fn default() -> Self {
+        Self(std::marker::PhantomData)
+    }
+}
+
+impl<R: Default + 'static> MockAny for MockAnyDefault<R> {
+    fn call_any(&mut self, _args: Box<dyn Any>) -> Box<dyn Any> {
+        Box::new(R::default())
+    }
+}
+
 /// Mocking a method. The type parameters are the types of arguments
 /// and the return values of the method as tuples.
 pub struct MockMethod<I, R> {
     imp: MockImpl<I, R>,
+    /// The number of times the method has been called.
+    call_count: AtomicUsize,
 }
 
 /// The actual implementation of the mock method.
@@ -37,24 +58,9 @@ pub enum MockImpl<I, R> {
 /// The function pointer for the mock closure.
 pub type MockClosureSignature<I, R> = Box<dyn Fn(I) -> R + Send + Sync>;
 
-/// The function pointer for the closure, and some metadata.
-pub struct MockClosure<I, R> {
-    closure: MockClosureSignature<I, R>,
-    call_count: usize,
-}
-
-impl<I, R> MockClosure<I, R> {
-    fn new(closure: MockClosureSignature<I, R>) -> Self {
-        Self {
-            closure,
-            call_count: 0,
-        }
-    }
-    fn call(&mut self, args: I) -> R {
-        self.call_count += 1;
-        (self.closure)(args)
-    }
-}
+/// The closure of a mock method.  It is reference-counted so that it can be called without
+/// holding the lock of the `MockVM` instance.  See [`MockMethod::closure_for_call`].
+pub type MockClosure<I, R> = Arc<dyn Fn(I) -> R + Send + Sync>;
 
 impl<I, R> std::default::Default for MockMethod<I, R> {
     fn default() -> Self {
@@ -63,47 +69,53 @@ impl<I, R> std::default::Default for MockMethod<I, R> {
 }
 
 impl<I, R> MockMethod<I, R> {
-    /// The method will panic with `unimplemented!()` when called.
-    pub fn new_unimplemented() -> Self {
+    fn new(imp: MockImpl<I, R>) -> Self {
         Self {
-            imp: MockImpl::Fixed(MockClosure::new(Box::new(|_| unimplemented!()))),
+            imp,
+            call_count: AtomicUsize::new(0),
         }
     }
 
+    /// The method will panic with `unimplemented!()` when called.
+    pub fn new_unimplemented() -> Self {
+        Self::new(MockImpl::Fixed(Arc::new(|_| unimplemented!())))
+    }
+
     /// The method will return the default value for the return type.
     pub fn new_default() -> Self
     where
         R: Default,
     {
-        Self {
-            imp: MockImpl::Fixed(MockClosure::new(Box::new(|_| R::default()))),
-        }
+        Self::new(MockImpl::Fixed(Arc::new(|_| R::default())))
     }
 
     /// The method will execute the given closure when called.
     pub fn new_fixed(closure: MockClosureSignature<I, R>) -> Self {
-        Self {
-            imp: MockImpl::Fixed(MockClosure::new(closure)),
-        }
+        Self::new(MockImpl::Fixed(Arc::from(closure)))
     }
 
     /// The method will execute the next closure in the sequence when called.
     pub fn new_sequence(closures: Vec<MockClosureSignature<I, R>>) -> Self {
-        Self {
-            imp: MockImpl::Sequence(closures.into_iter().map(|c| MockClosure::new(c)).collect()),
-        }
+        Self::new(MockImpl::Sequence(
+            closures.into_iter().map(Arc::from).collect(),
+        ))
     }
 
     /// Call the mock method.
     pub fn call(&mut self, args: I) -> R {
-        let cur_call = self.call_count();
-
-        match &mut self.imp {
-            MockImpl::Sequence(closures) => {
-                let len = closures.len();
-                closures[cur_call % len].call(args)
-            }
-            MockImpl::Fixed(closure) => closure.call(args),
+        (self.closure_for_call())(args)
+    }
+
+    /// Count a call to the mock method, and return the closure to be called.  This allows
+    /// calling the closure after releasing the lock of the `MockVM` instance, so that the closure
+    /// can block, or call other mock methods, without blocking other threads that call mock
+    /// methods (e.g. GC workers).
+    pub fn closure_for_call(&self) -> MockClosure<I, R> {
+        let cur_call = self.call_count.fetch_add(1, Ordering::SeqCst);
+
+        match &self.imp {
+            MockImpl::Sequence(closures) => closures[cur_call % closures.len()].clone(),
+            MockImpl::Fixed(closure) => closure.clone(),
         }
     }
 
@@ -114,10 +126,7 @@ impl<I, R> MockMethod<I, R> {
 
     /// How many times has the method been called?
     pub fn call_count(&self) -> usize {
-        match &self.imp {
-            MockImpl::Fixed(c) => c.call_count,
-            MockImpl::Sequence(vec) => vec.iter().map(|c| c.call_count).sum(),
-        }
+        self.call_count.load(Ordering::SeqCst)
     }
 }
 
@@ -2,7 +2,6 @@
 #![allow(clippy::type_complexity)]
 
 use crate::plan::ObjectQueue;
-use crate::scheduler::gc_work::ProcessEdgesWorkRootsWorkFactory;
 use crate::scheduler::gc_work::ProcessEdgesWorkTracerContext;
 use crate::scheduler::gc_work::SFTProcessEdges;
 use crate::scheduler::*;
@@ -24,7 +23,7 @@ use super::mock_method::*;
 
 use std::default::Default;
 use std::ops::Range;
-use std::sync::Mutex;
+use std::sync::RwLock;
 
 /// The offset between object reference and the allocation address if we use
 /// the default mock VM.
@@ -32,9 +31,9 @@ pub const DEFAULT_OBJECT_REF_OFFSET: usize = crate::util::constants::BYTES_IN_AD
 
 // To mock static methods, we have to create a static instance of `MockVM`.
 lazy_static! {
-    // The mutex may get poisoned any time. Accessing this mutex needs to deal with the poisoned case.
+    // The lock may get poisoned any time. Accessing this lock needs to deal with the poisoned case.
     // One can use read/write_mockvm to access mock vm.
-    static ref MOCK_VM_INSTANCE: Mutex<MockVM> = Mutex::new(MockVM::default());
+    static ref MOCK_VM_INSTANCE: RwLock<MockVM> = RwLock::new(MockVM::default());
 }
 
 // MockVM only allows mock methods with references of no lifetime or static lifetime.
@@ -53,11 +52,14 @@ macro_rules! lifetime {
     };
 }
 
-/// Call `MockMethod`.
+/// Call `MockMethod`.  The closure is called after the lock of the `MockVM` instance is released,
+/// so mock methods can be called concurrently by GC workers and mutators, and a mock method may
+/// block (e.g. `block_for_gc`) or call other mock methods.
 macro_rules! mock {
-    ($fn: ident($($arg:expr),*)) => {
-        write_mockvm(|mock| mock.$fn.call(($($arg),*)))
-    };
+    ($fn: ident($($arg:expr),*)) => {{
+        let closure = read_mockvm(|mock| mock.$fn.closure_for_call());
+        closure(($($arg),*))
+    }};
 }
 /// Call `MockAny`.
 macro_rules! mock_any {
@@ -72,7 +74,7 @@ where
     F: FnOnce(&MockVM) -> R,
 {
     let lock = MOCK_VM_INSTANCE
-        .lock()
+        .read()
         .unwrap_or_else(|poisoned| poisoned.into_inner());
     func(&lock)
 }
@@ -82,7 +84,7 @@ where
     F: FnOnce(&mut MockVM) -> R,
 {
     let mut lock = MOCK_VM_INSTANCE
-        .lock()
+        .write()
         .unwrap_or_else(|poisoned| poisoned.into_inner());
     func(&mut lock)
 }
@@ -216,7 +218,7 @@ pub struct MockVM {
         (
             ObjectReference,
             CopySemantics,
-            &'static GCWorkerCopyContext<MockVM>,
+            &'static mut GCWorkerCopyContext<MockVM>,
         ),
         ObjectReference,
     >,
@@ -238,6 +240,7 @@ pub struct MockVM {
     pub weakref_enqueue_references: MockMethod<(&'static [ObjectReference], VMWorkerThread), ()>,
     // scanning
     pub support_slot_enqueuing: MockMethod<(VMWorkerThread, ObjectReference), bool>,
+    pub filter_slot_object: MockMethod<ObjectReference, Option<ObjectReference>>,
     pub scan_object: MockMethod<
         (
             VMWorkerThread,
@@ -254,8 +257,16 @@ pub struct MockVM {
         ),
         (),
     >,
-    pub scan_roots_in_mutator_thread: Box<dyn MockAny>,
-    pub scan_vm_specific_roots: Box<dyn MockAny>,
+    pub scan_roots_in_mutator_thread: MockMethod<
+        (
+            VMWorkerThread,
+            &'static mut Mutator<MockVM>,
+            &'static mut dyn MockRootsWorkFactory,
+        ),
+        (),
+    >,
+    pub scan_vm_specific_roots:
+        MockMethod<(VMWorkerThread, &'static mut dyn MockRootsWorkFactory), ()>,
     pub notify_initial_thread_scan_complete: MockMethod<(bool, VMWorkerThread), ()>,
     pub supports_return_barrier: MockMethod<(), bool>,
     pub prepare_for_roots_re_scanning: MockMethod<(), ()>,
@@ -309,9 +320,15 @@ impl Default for MockVM {
             weakref_enqueue_references: MockMethod::new_unimplemented(),
 
             support_slot_enqueuing: MockMethod::new_fixed(Box::new(|_| true)),
+            filter_slot_object: MockMethod::new_fixed(Box::new(Some)),
             scan_object: MockMethod::new_unimplemented(),
             scan_object_and_trace_edges: MockMethod::new_unimplemented(),
-            // We instantiate a `MockMethod` with the arguments as ProcessEdgesWorkRootsWorkFactory<..., SFTProcessEdges<MockVM>, ...>,
+            scan_roots_in_mutator_thread: MockMethod::new_unimplemented(),
+            scan_vm_specific_roots: MockMethod::new_unimplemented(),
+            notify_initial_thread_scan_complete: MockMethod::new_unimplemented(),
+            supports_return_barrier: MockMethod::new_unimplemented(),
+            prepare_for_roots_re_scanning: MockMethod::new_unimplemented(),
+            // We instantiate a `MockMethod` with the arguments as ProcessEdgesWorkTracerContext<SFTProcessEdges<MockVM>>,
             // thus the mock method expects the actual call arguments to match the type.
             // In most cases, this won't work and this `MockMethod` is just a place holder. It is
             // fine as long as the method is not actually called.
@@ -319,34 +336,6 @@ impl Default for MockVM {
             // they are expected to provide their own
             // `MockMethod` that matches the argument types they will pass for the test case.
             // See the documents on the section about `MockAny` on the `MockVM` type.
-            scan_roots_in_mutator_thread: Box::new(MockMethod::<
-                (
-                    VMWorkerThread,
-                    &'static mut Mutator<MockVM>,
-                    ProcessEdgesWorkRootsWorkFactory<
-                        MockVM,
-                        SFTProcessEdges<MockVM>,
-                        SFTProcessEdges<MockVM>,
-                    >,
-                ),
-                (),
-            >::new_unimplemented()),
-            // Same here: the `MockMethod` is just a place holder. See the above comments.
-            scan_vm_specific_roots: Box::new(MockMethod::<
-                (
-                    VMWorkerThread,
-                    ProcessEdgesWorkRootsWorkFactory<
-                        MockVM,
-                        SFTProcessEdges<MockVM>,
-                        SFTProcessEdges<MockVM>,
-                    >,
-                ),
-                (),
-            >::new_unimplemented()),
-            notify_initial_thread_scan_complete: MockMethod::new_unimplemented(),
-            supports_return_barrier: MockMethod::new_unimplemented(),
-            prepare_for_roots_re_scanning: MockMethod::new_unimplemented(),
-            // Same here: the `MockMethod` is just a place holder. See the above comments.
             process_weak_refs: Box::new(MockMethod::<
                 (
                     &'static mut GCWorker<Self>,
@@ -354,7 +343,7 @@ impl Default for MockVM {
                 ),
                 bool,
             >::new_unimplemented()),
-            // Same here: the `MockMethod` is just a place holder. See the above comments.
+            // Same here: the `MockMethod` is just a place holder.
             forward_weak_refs: Box::new(MockMethod::<
                 (
                     &'static mut GCWorker<Self>,
@@ -463,14 +452,17 @@ impl crate::vm::Collection<MockVM> for MockVM {
 }
 
 impl crate::vm::ObjectModel<MockVM> for MockVM {
-    const GLOBAL_LOG_BIT_SPEC: VMGlobalLogBitSpec = VMGlobalLogBitSpec::in_header(0);
+    const GLOBAL_LOG_BIT_SPEC: VMGlobalLogBitSpec = VMGlobalLogBitSpec::side_first();
+    // The forwarding pointer overwrites the first word of the object.  The other metadata are on
+    // the side so that they do not conflict with each other, or with the forwarding pointer.
     const LOCAL_FORWARDING_POINTER_SPEC: VMLocalForwardingPointerSpec =
         VMLocalForwardingPointerSpec::in_header(0);
     const LOCAL_FORWARDING_BITS_SPEC: VMLocalForwardingBitsSpec =
-        VMLocalForwardingBitsSpec::in_header(0);
-    const LOCAL_MARK_BIT_SPEC: VMLocalMarkBitSpec = VMLocalMarkBitSpec::in_header(0);
+        VMLocalForwardingBitsSpec::side_first();
+    const LOCAL_MARK_BIT_SPEC: VMLocalMarkBitSpec =
+        VMLocalMarkBitSpec::side_after(Self::LOCAL_FORWARDING_BITS_SPEC.as_spec());
     const LOCAL_LOS_MARK_NURSERY_SPEC: VMLocalLOSMarkNurserySpec =
-        VMLocalLOSMarkNurserySpec::in_header(0);
+        VMLocalLOSMarkNurserySpec::side_after(Self::LOCAL_MARK_BIT_SPEC.as_spec());
 
     #[cfg(feature = "object_pinning")]
     const LOCAL_PINNING_BIT_SPEC: VMLocalPinningBitSpec = VMLocalPinningBitSpec::in_header(0);
@@ -549,6 +541,9 @@ impl crate::vm::Scanning<MockVM> for MockVM {
     fn support_slot_enqueuing(tls: VMWorkerThread, object: ObjectReference) -> bool {
         mock!(support_slot_enqueuing(tls, object))
     }
+    fn filter_slot_object(object: ObjectReference) -> Option<ObjectReference> {
+        mock!(filter_slot_object(object))
+    }
     fn scan_object<SV: SlotVisitor<<MockVM as VMBinding>::VMSlot>>(
         tls: VMWorkerThread,
         object: ObjectReference,
@@ -576,17 +571,22 @@ impl crate::vm::Scanning<MockVM> for MockVM {
         mutator: &'static mut Mutator<Self>,
         factory: impl RootsWorkFactory<<MockVM as VMBinding>::VMSlot>,
     ) {
-        mock_any!(scan_roots_in_mutator_thread(
+        let mut factory = factory;
+        mock!(scan_roots_in_mutator_thread(
             tls,
             mutator,
-            Box::new(factory)
+            lifetime!(&mut factory as &mut dyn MockRootsWorkFactory)
         ))
     }
     fn scan_vm_specific_roots(
         tls: VMWorkerThread,
         factory: impl RootsWorkFactory<<MockVM as VMBinding>::VMSlot>,
     ) {
-        mock_any!(scan_vm_specific_roots(tls, Box::new(factory)))
+        let mut factory = factory;
+        mock!(scan_vm_specific_roots(
+            tls,
+            lifetime!(&mut factory as &mut dyn MockRootsWorkFactory)
+        ))
     }
     fn notify_initial_thread_scan_complete(partial_scan: bool, tls: VMWorkerThread) {
         mock!(notify_initial_thread_scan_complete(partial_scan, tls))
@@ -613,6 +613,26 @@ impl crate::vm::Scanning<MockVM> for MockVM {
     }
 }
 
+/// An object-safe subset of [`RootsWorkFactory`], so that root scanning can be mocked with
+/// [`MockMethod`] regardless of the concrete type of the factory the plan uses.
+pub trait MockRootsWorkFactory {
+    fn create_process_roots_work(&mut self, slots: Vec<Address>);
+    fn create_process_pinning_roots_work(&mut self, nodes: Vec<ObjectReference>);
+    fn create_process_tpinning_roots_work(&mut self, nodes: Vec<ObjectReference>);
+}
+
+impl<F: RootsWorkFactory<Address>> MockRootsWorkFactory for F {
+    fn create_process_roots_work(&mut self, slots: Vec<Address>) {
+        RootsWorkFactory::create_process_roots_work(self, slots)
+    }
+    fn create_process_pinning_roots_work(&mut self, nodes: Vec<ObjectReference>) {
+        RootsWorkFactory::create_process_pinning_roots_work(self, nodes)
+    }
+    fn create_process_tpinning_roots_work(&mut self, nodes: Vec<ObjectReference>) {
+        RootsWorkFactory::create_process_tpinning_roots_work(self, nodes)
+    }
+}
+
 impl MockVM {
     pub fn object_start_to_ref(start: Address) -> ObjectReference {
         ObjectReference::from_raw_address(start + DEFAULT_OBJECT_REF_OFFSET).unwrap()
@@ -1,3 +1,4 @@
+use crate::global_state::GcStatus;
 use crate::util::alloc::AllocationError;
 use crate::util::heap::gc_trigger::GCTriggerPolicy;
 use crate::util::opaque_pointer::*;
@@ -104,6 +105,30 @@ pub trait Collection<VM: VMBinding> {
     /// * `tls_worker`: The thread pointer for the worker thread performing this call.
     fn post_forwarding(_tls: VMWorkerThread) {}
 
+    /// Inform the VM that the GC status has changed from `old` to `new`.  A GC goes through
+    /// `NotInGC`, `GcPrepare`, `GcProper`, and back to `NotInGC`.  This can be used to drive the
+    /// VM's own state machine, such as flipping safepoint flags or suspending JIT activity at
+    /// specific phases.
+    ///
+    /// This is called by whichever thread makes the transition, which may be a GC worker or a
+    /// mutator.  It is called for every GC, so it should be cheap.
+    ///
+    /// Arguments:
+    /// * `old`: The GC status before the transition.
+    /// * `new`: The GC status after the transition.
+    fn on_gc_status_change(_old: GcStatus, _new: GcStatus) {}
+
+    /// Inform the VM that the GC trigger has changed the heap size, e.g. when a dynamic heap size
+    /// trigger expands or shrinks the heap.  The heap size is checked at the end of each GC, and this
+    /// is called before the mutators are resumed.  It can be used to report telemetry or to adjust
+    /// off-heap caches.  The VM must not allocate objects in the MMTk heap in this call.
+    ///
+    /// Arguments:
+    /// * `tls`: The thread pointer for the worker thread that finishes the GC.
+    /// * `old_heap_bytes`: The heap size in bytes before the GC.
+    /// * `new_heap_bytes`: The heap size in bytes after the GC.
+    fn on_heap_resize(_tls: VMWorkerThread, _old_heap_bytes: usize, _new_heap_bytes: usize) {}
+
     /// Return the amount of memory (in bytes) which the VM allocated outside the MMTk heap but
     /// wants to include into the current MMTk heap size.  MMTk core will consider the reported
     /// memory as part of MMTk heap for the purpose of heap size accounting.
@@ -140,6 +140,15 @@ pub trait ObjectModel<VM: VMBinding> {
     #[cfg(feature = "vo_bit")]
     const NEED_VO_BITS_DURING_TRACING: bool = false;
 
+    /// When to clear the VO bits of dead objects in ImmixSpace.  By default, the VO bits are
+    /// cleared eagerly in each GC.  For some VMs, clearing the VO bits lazily when the memory is
+    /// reused is cheaper.  See [`crate::util::metadata::vo_bit::VOBitClearingStrategy`] for the
+    /// correctness requirements of lazy clearing.  Lazy clearing cannot be used if
+    /// `NEED_VO_BITS_DURING_TRACING` is `true`.
+    #[cfg(feature = "vo_bit")]
+    const VO_BIT_CLEARING_STRATEGY: crate::util::metadata::vo_bit::VOBitClearingStrategy =
+        crate::util::metadata::vo_bit::VOBitClearingStrategy::Eager;
+
     /// A function to non-atomically load the specified per-object metadata's content.
     /// The default implementation assumes the bits defined by the spec are always avilable for MMTk to use. If that is not the case, a binding should override this method, and provide their implementation.
     /// Returns the metadata value.
@@ -412,6 +421,22 @@ pub trait ObjectModel<VM: VMBinding> {
     /// * `object`: The object to be queried.
     fn get_align_offset_when_copied(object: ObjectReference) -> usize;
 
+    /// Return whether the GC may move an object in the current GC.  This is consulted by policies
+    /// that move objects opportunistically (currently the Immix space) before moving an object.  If
+    /// this returns false, the object is marked in place instead.  Unlike pinning, this is
+    /// evaluated again every time the object is traced, so the VM can keep objects in place only for
+    /// a particular GC (e.g. objects referenced by native iterators).  The default implementation
+    /// allows all objects to move.
+    ///
+    /// This is called from GC worker threads for every object that may be moved, so it should be
+    /// cheap.
+    ///
+    /// Arguments:
+    /// * `object`: The object that is about to be moved.
+    fn should_move_object(_object: ObjectReference) -> bool {
+        true
+    }
+
     /// Get the type descriptor for an object.
     ///
     /// FIXME: Do we need this? If so, determine lifetime, return byte[]
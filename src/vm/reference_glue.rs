@@ -47,6 +47,30 @@ pub trait ReferenceGlue<VM: VMBinding> {
     /// the references slice will be cleared after this call is returned. That means
     /// MMTk will no longer keep these references alive once this method is returned.
     fn enqueue_references(references: &[ObjectReference], tls: VMWorkerThread);
+
+    /// If `true`, MMTk sorts the finalizable objects that are ready for finalization with
+    /// [`ReferenceGlue::compare_ready_finalizers`] after each GC, so that
+    /// [`crate::memory_manager::get_finalized_object`] returns them in ascending order.
+    /// Otherwise, they are returned in an unspecified order.  The default value is `false`.
+    ///
+    /// This is useful for runtimes that need deterministic finalizer ordering, such as running
+    /// finalizers in allocation order.
+    const SORT_READY_FINALIZERS: bool = false;
+
+    /// Compare two finalizable objects that are ready for finalization.  This is only called if
+    /// [`ReferenceGlue::SORT_READY_FINALIZERS`] is `true`.  It is called by a GC worker during
+    /// GC, after the finalizable objects have been kept alive (and possibly moved), so it may read
+    /// the objects.  It must be a total order.
+    ///
+    /// Arguments:
+    /// * `a`: A finalizable object.
+    /// * `b`: Another finalizable object.
+    fn compare_ready_finalizers(
+        _a: &Self::FinalizableType,
+        _b: &Self::FinalizableType,
+    ) -> std::cmp::Ordering {
+        std::cmp::Ordering::Equal
+    }
 }
 
 use crate::scheduler::gc_work::ProcessEdgesWork;
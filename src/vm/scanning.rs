@@ -136,6 +136,46 @@ pub trait RootsWorkFactory<SL: Slot>: Clone + Send + 'static {
     /// Arguments:
     /// * `nodes`: A vector of references to objects pointed by edges from roots.
     fn create_process_tpinning_roots_work(&mut self, nodes: Vec<ObjectReference>);
+
+    /// Create work packets to handle roots that pin their descendents down to a limited depth.
+    ///
+    /// The objects in `nodes` and all objects reachable from them through at most `depth` edges
+    /// will not be moved.  Objects further away may be moved.  A `depth` of 0 is equivalent to
+    /// `create_process_pinning_roots_work`.
+    ///
+    /// The depth of an object reachable from several roots is the depth at which MMTk reaches it
+    /// first, so some objects beyond `depth` may also be pinned.
+    ///
+    /// The default implementation pins the entire transitive closure of `nodes` using
+    /// `create_process_tpinning_roots_work`.
+    ///
+    /// Arguments:
+    /// * `nodes`: A vector of references to objects pointed by edges from roots.
+    /// * `depth`: The maximum number of edges from `nodes` to a pinned descendent.
+    fn create_process_depth_limited_pinning_roots_work(
+        &mut self,
+        nodes: Vec<ObjectReference>,
+        depth: usize,
+    ) {
+        let _ = depth;
+        self.create_process_tpinning_roots_work(nodes);
+    }
+
+    /// Create work packets to handle slots from a remembered set, such as the slots in dirty cards
+    /// found by a card-table write barrier.
+    ///
+    /// The slots are processed like `create_process_roots_work`, and the work packet may update the
+    /// slots.  But unlike roots, the slots are not recorded as roots (e.g. sanity GC will not treat
+    /// them as roots), because they are fields of heap objects that are expected to be reached from
+    /// real roots anyway.
+    ///
+    /// The default implementation processes the slots as roots using `create_process_roots_work`.
+    ///
+    /// Arguments:
+    /// * `slots`: A vector of slots.
+    fn create_process_remembered_slots(&mut self, slots: Vec<SL>) {
+        self.create_process_roots_work(slots);
+    }
 }
 
 /// VM-specific methods for scanning roots/objects.
@@ -243,6 +283,21 @@ pub trait Scanning<VM: VMBinding> {
     /// does not know those roots are stack roots, and cannot perform any possible
     /// optimization for the stack roots.
     ///
+    /// If a mutator has a large root set, the VM binding may split the scanning into multiple
+    /// tasks that can be executed in parallel.  See [`crate::memory_manager::fork_stack_scan`].
+    ///
+    /// The same mechanism makes stack scanning resumable, which is useful for deep stacks and
+    /// tight latency budgets.  This method does not need to scan the whole stack.  It may deliver
+    /// a bounded batch of root slots through `factory`, call
+    /// [`crate::memory_manager::fork_stack_scan`] and add a work packet that continues from where
+    /// it stopped, and return.  The continuation may deliver the next batch with a clone of
+    /// `factory`, and fork again if the stack is still not fully scanned.  Each forked task calls
+    /// [`crate::memory_manager::finish_forked_stack_scan`] when it finishes, which serves as the
+    /// "scan complete" signal.  MMTk does not assume that returning from this method means the
+    /// stack has been fully scanned, and only calls [`Scanning::notify_initial_thread_scan_complete`]
+    /// after all the forked tasks of all the mutators have finished.  The binding is responsible
+    /// for keeping the scanning progress of each mutator, such as the frame to resume from.
+    ///
     /// The `memory_manager::is_mmtk_object` function can be used in this function if
     /// -   the "is_mmtk_object" feature is enabled.
     ///
@@ -280,6 +335,46 @@ pub trait Scanning<VM: VMBinding> {
     /// [`crate::vm::Scanning::scan_vm_specific_roots`].
     fn prepare_for_roots_re_scanning();
 
+    /// Filter the object reference loaded from a slot before it is traced.  Return the object
+    /// reference to trace, or `None` to skip the slot.  This is called for every slot processed by
+    /// MMTk, including root slots.  The binding can override this to normalize object references,
+    /// for example, to strip tag bits.  The returned reference is the one traced, and, if the
+    /// object is moved, the new reference stored back to the slot is derived from it.  This is
+    /// called for every slot, so it must be fast.
+    ///
+    /// The default implementation returns the object unchanged.
+    ///
+    /// Arguments:
+    /// * `object`: The object reference loaded from a slot.
+    fn filter_slot_object(object: ObjectReference) -> Option<ObjectReference> {
+        Some(object)
+    }
+
+    /// Normalize a root slot before it is cached for the sanity GC.  This is only called if the
+    /// `sanity` feature is enabled.  The sanity GC computes another transitive closure from the
+    /// cached roots, and compares it with the result of the GC being checked.  If the binding
+    /// uses compressed or tagged references, it can canonicalize the slot here so that the cached
+    /// roots are comparable with the references seen in the second transitive closure.
+    ///
+    /// The default implementation returns the slot unchanged.
+    ///
+    /// Arguments:
+    /// * `slot`: A root slot reported by the binding.
+    fn normalize_root_slot_for_sanity(slot: VM::VMSlot) -> VM::VMSlot {
+        slot
+    }
+
+    /// Normalize a root node before it is cached for the sanity GC.  This is only called if the
+    /// `sanity` feature is enabled.  See [`Scanning::normalize_root_slot_for_sanity`].
+    ///
+    /// The default implementation returns the object unchanged.
+    ///
+    /// Arguments:
+    /// * `object`: A root node reported by the binding.
+    fn normalize_root_node_for_sanity(object: ObjectReference) -> ObjectReference {
+        object
+    }
+
     /// Process weak references.
     ///
     /// This function is called in a GC after the transitive closure from roots is computed, that
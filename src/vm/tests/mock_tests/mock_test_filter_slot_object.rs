@@ -0,0 +1,45 @@
+// GITHUB-CI: MMTK_PLAN=SemiSpace,GenCopy
+
+use super::mock_test_prelude::*;
+
+use crate::util::ObjectReference;
+use crate::AllocationSemantics;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The object to be rejected by the slot filter, as an address.
+static REJECTED: AtomicUsize = AtomicUsize::new(0);
+
+/// `Scanning::filter_slot_object` is called on the objects loaded from slots.  An object accepted
+/// by the filter is traced and moved, and its slot is updated.  The slot of an object rejected by
+/// the filter is skipped and left unchanged.
+#[test]
+pub fn filter_slot_object() {
+    with_mockvm(
+        default_setup,
+        || {
+            const OBJECT_SIZE: usize = 64;
+            let mut fixture = GCFixture::create_with_builder(|_| {});
+            let accepted = fixture.alloc(OBJECT_SIZE, AllocationSemantics::Default);
+            let rejected = fixture.alloc(OBJECT_SIZE, AllocationSemantics::Default);
+            fixture.add_root(accepted);
+            fixture.add_root(rejected);
+
+            REJECTED.store(rejected.to_raw_address().as_usize(), Ordering::SeqCst);
+            write_mockvm(|mock| {
+                mock.filter_slot_object =
+                    MockMethod::new_fixed(Box::new(|object: ObjectReference| {
+                        (object.to_raw_address().as_usize() != REJECTED.load(Ordering::SeqCst))
+                            .then_some(object)
+                    }));
+            });
+            fixture.gc();
+
+            let roots = fixture.roots();
+            assert_ne!(roots[0], accepted);
+            assert_eq!(MockVM::get_current_size(roots[0]), OBJECT_SIZE);
+            assert_eq!(roots[1], rejected);
+            assert!(read_mockvm(|mock| mock.filter_slot_object.is_called()));
+        },
+        no_cleanup,
+    )
+}
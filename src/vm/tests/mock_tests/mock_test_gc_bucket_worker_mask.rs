@@ -0,0 +1,46 @@
+// GITHUB-CI: MMTK_PLAN=Immix,GenImmix,StickyImmix,SemiSpace,GenCopy,MarkSweep,PageProtect
+
+use super::mock_test_prelude::*;
+
+use crate::scheduler::WorkBucketStage;
+
+/// Run full-heap GCs with multiple workers while the packets in the `Prepare` and `Release`
+/// buckets can only be executed by the first worker.  The other workers may become the last
+/// parked worker while those buckets still have packets.  They must wake the first worker up
+/// instead of concluding that the buckets are drained.
+#[test]
+pub fn gc_bucket_worker_mask() {
+    with_mockvm(
+        default_setup,
+        || {
+            const OBJECT_SIZE: usize = 64;
+            let mut fixture = GCFixture::create_with_builder(|builder| {
+                builder.options.threads.set(4);
+                builder.options.prepare_bucket_workers.set(1);
+                builder.options.release_bucket_workers.set(1);
+            });
+
+            fixture.alloc_with_roots(1000, OBJECT_SIZE, 10);
+            for _ in 0..10 {
+                fixture.gc();
+                fixture.assert_roots_intact(OBJECT_SIZE);
+
+                let masked = fixture
+                    .take_polled_packets()
+                    .into_iter()
+                    .filter(|packet| {
+                        matches!(
+                            packet.stage,
+                            WorkBucketStage::Prepare | WorkBucketStage::Release
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                assert!(!masked.is_empty());
+                for packet in masked {
+                    assert_eq!(packet.ordinal, 0, "{:?} ran on another worker", packet);
+                }
+            }
+        },
+        no_cleanup,
+    )
+}
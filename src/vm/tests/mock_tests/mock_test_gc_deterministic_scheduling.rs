@@ -0,0 +1,46 @@
+// GITHUB-CI: MMTK_PLAN=Immix,GenImmix,StickyImmix,SemiSpace,GenCopy,MarkSweep,PageProtect
+
+use super::mock_test_prelude::*;
+
+/// Run full-heap GCs with multiple workers in the deterministic scheduling mode.  Only the first
+/// worker can execute packets from work buckets, so the other workers may become the last parked
+/// worker while the buckets still have packets.  They must wake the first worker up instead of
+/// concluding that the buckets are drained.  GCs of the same heap must take the packets in the same
+/// order.
+#[test]
+pub fn gc_deterministic_scheduling() {
+    with_mockvm(
+        default_setup,
+        || {
+            const OBJECT_SIZE: usize = 64;
+            let mut fixture = GCFixture::create_with_builder(|builder| {
+                builder.options.threads.set(4);
+                builder.options.deterministic_gc_scheduling.set(true);
+            });
+
+            fixture.alloc_with_roots(1000, OBJECT_SIZE, 10);
+            // The first GC frees the unreachable objects.  The later GCs see the same heap.
+            fixture.gc();
+            fixture.assert_roots_intact(OBJECT_SIZE);
+            fixture.take_polled_packets();
+
+            let mut first_order = None;
+            for _ in 0..10 {
+                fixture.gc();
+                fixture.assert_roots_intact(OBJECT_SIZE);
+
+                let packets = fixture.take_polled_packets();
+                assert!(packets.iter().all(|packet| packet.ordinal == 0));
+                let order = packets
+                    .into_iter()
+                    .map(|packet| (packet.stage, packet.type_name))
+                    .collect::<Vec<_>>();
+                match first_order.as_ref() {
+                    None => first_order = Some(order),
+                    Some(first_order) => assert_eq!(&order, first_order),
+                }
+            }
+        },
+        no_cleanup,
+    )
+}
@@ -0,0 +1,82 @@
+// GITHUB-CI: MMTK_PLAN=Immix,StickyImmix
+
+use super::mock_test_prelude::*;
+
+use crate::util::conversions::*;
+use crate::util::heap::layout::vm_layout::BYTES_IN_CHUNK;
+use crate::util::heap::vm_layout::VMLayout;
+use crate::util::{Address, ObjectReference};
+use crate::AllocationSemantics;
+
+/// A layout with discontiguous spaces, so that the Immix space and the large object space acquire
+/// chunks from the same address range.  The same layout as `mock_test_vm_layout_compressed_pointer`.
+pub fn discontiguous_vm_layout() -> VMLayout {
+    let start = if cfg!(target_os = "macos") {
+        0x2_0000_0000
+    } else {
+        0x4000_0000
+    };
+    VMLayout {
+        log_address_space: 35,
+        heap_start: chunk_align_down(unsafe { Address::from_usize(start) }),
+        heap_end: chunk_align_up(unsafe { Address::from_usize(32usize << 30) }),
+        log_space_extent: 31,
+        force_use_contiguous_spaces: false,
+    }
+}
+
+/// The size of the large objects allocated by [`interleave_immix_and_los_chunks`].  Each of them
+/// needs chunks of its own.
+pub const LARGE_OBJECT_SIZE: usize = BYTES_IN_CHUNK + BYTES_IN_CHUNK / 2;
+
+/// Allocate about a chunk of Immix objects and a large object in turn, so that the chunks of the
+/// large object space lie between the chunks of the Immix space.  Return the large objects.
+pub fn interleave_immix_and_los_chunks(fixture: &mut GCFixture) -> Vec<ObjectReference> {
+    const OBJECT_SIZE: usize = 8 * 1024;
+    (0..4)
+        .map(|_| {
+            for _ in 0..BYTES_IN_CHUNK / OBJECT_SIZE {
+                fixture.alloc(OBJECT_SIZE, AllocationSemantics::Default);
+            }
+            fixture.alloc(LARGE_OBJECT_SIZE, AllocationSemantics::Los)
+        })
+        .collect()
+}
+
+/// Does the chunk at `chunk_start` overlap with the large object?
+pub fn chunk_overlaps_large_object(chunk_start: Address, object: ObjectReference) -> bool {
+    let start = chunk_align_down(object.to_raw_address());
+    let end = object.to_raw_address() + LARGE_OBJECT_SIZE;
+    start <= chunk_start && chunk_start < end
+}
+
+/// `chunk_occupancy` lists the chunks between the lowest and the highest chunk of the Immix space,
+/// but must not list the chunks of the large object space in between.
+#[test]
+pub fn immix_chunk_occupancy() {
+    with_mockvm(
+        default_setup,
+        || {
+            let mut fixture = GCFixture::create_with_builder(|builder| {
+                builder.set_vm_layout(discontiguous_vm_layout());
+            });
+            let large_objects = interleave_immix_and_los_chunks(&mut fixture);
+
+            let occupancy = memory_manager::immix_chunk_occupancy(fixture.mmtk()).unwrap();
+            assert!(occupancy.iter().any(|(_, allocated, _)| *allocated));
+            for (chunk_start, allocated, blocks) in occupancy {
+                for object in large_objects.iter() {
+                    assert!(
+                        !chunk_overlaps_large_object(chunk_start, *object),
+                        "Chunk {} (allocated: {}, blocks: {}) belongs to the large object {}",
+                        chunk_start,
+                        allocated,
+                        blocks,
+                        object
+                    );
+                }
+            }
+        },
+        no_cleanup,
+    )
+}
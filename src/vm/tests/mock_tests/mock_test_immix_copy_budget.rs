@@ -0,0 +1,53 @@
+// GITHUB-CI: MMTK_PLAN=GenImmix
+
+use super::mock_test_prelude::*;
+
+/// Run a defrag GC with a defrag copy budget that cannot pay for any clean block.  Each GC worker
+/// may acquire at most one clean block for the object it is copying when it finds the budget used
+/// up, and the other objects in defrag source blocks are marked in place.
+#[test]
+pub fn immix_copy_budget() {
+    with_mockvm(
+        default_setup,
+        || {
+            const OBJECT_SIZE: usize = 64;
+            const THREADS: usize = 2;
+            let mut fixture = GCFixture::create_with_builder(|builder| {
+                builder.options.threads.set(THREADS);
+                builder
+                    .options
+                    .immix_defrag_copy_budget
+                    .set(f64::MIN_POSITIVE);
+            });
+
+            // Promote all the objects to the mature space, and then free most of them, so that
+            // the mature blocks are fragmented.
+            fixture.alloc_with_roots(100000, OBJECT_SIZE, 1);
+            fixture.gc();
+            let survivors = fixture.roots().into_iter().step_by(10).collect::<Vec<_>>();
+            fixture.clear_roots();
+            for object in survivors {
+                fixture.add_root(object);
+            }
+            fixture.gc();
+
+            let before = fixture.roots();
+            fixture.mmtk().request_defrag_gc();
+            fixture.gc();
+            assert!(fixture.mmtk().last_gc_was_defrag());
+            let stats = memory_manager::copy_alloc_stats_in_last_gc(fixture.mmtk());
+            assert!(
+                stats.blocks_acquired <= THREADS,
+                "{} clean blocks were acquired with an exhausted copy budget",
+                stats.blocks_acquired
+            );
+            let after = fixture.roots();
+            assert!(
+                before.iter().zip(after.iter()).any(|(b, a)| b == a),
+                "All the objects were moved"
+            );
+            fixture.assert_roots_intact(OBJECT_SIZE);
+        },
+        no_cleanup,
+    )
+}
@@ -0,0 +1,56 @@
+// GITHUB-CI: MMTK_PLAN=Immix,StickyImmix
+// GITHUB-CI: FEATURES=sanity
+
+use super::mock_test_prelude::*;
+
+use crate::AllocationSemantics;
+
+/// Run defrag GCs with objects reachable from pinning roots and transitively pinning roots.  Those
+/// objects are marked in place even if they are in defrag source blocks, and the sanity check of
+/// defrag source blocks must accept them.
+#[test]
+pub fn immix_defrag_pinning_roots() {
+    with_mockvm(
+        default_setup,
+        || {
+            const OBJECT_SIZE: usize = 64;
+            let mut fixture = GCFixture::create_with_builder(|builder| {
+                builder.options.threads.set(4);
+            });
+
+            let mut pinned = vec![];
+            for i in 0..10000 {
+                let object = fixture.alloc(OBJECT_SIZE, AllocationSemantics::Default);
+                match i % 30 {
+                    0 => fixture.add_root(object),
+                    10 => {
+                        fixture.add_pinning_root(object);
+                        pinned.push(object);
+                    }
+                    20 => {
+                        fixture.add_tpinning_root(object);
+                        pinned.push(object);
+                    }
+                    _ => {}
+                }
+            }
+
+            let mut moved = false;
+            for _ in 0..3 {
+                // Defrag source blocks are chosen by the numbers of holes found by the last sweep.
+                fixture.gc();
+                let before = fixture.roots();
+                fixture.mmtk().request_defrag_gc();
+                fixture.gc();
+                assert!(fixture.mmtk().last_gc_was_defrag());
+                moved |= before != fixture.roots();
+                for object in pinned.iter() {
+                    assert!(object.is_live());
+                    assert_eq!(MockVM::get_current_size(*object), OBJECT_SIZE);
+                }
+            }
+            assert!(moved, "No object was moved in defrag GCs");
+        },
+        no_cleanup,
+    )
+}
@@ -0,0 +1,38 @@
+// GITHUB-CI: MMTK_PLAN=Immix,StickyImmix
+
+use super::mock_test_prelude::*;
+
+use crate::policy::immix::block::Block;
+use crate::util::linear_scan::Region;
+
+/// Request a block to be evacuated before a GC.  The objects in the block are moved in the next
+/// GC, and the objects in the other blocks, which have no holes, are not.
+#[test]
+pub fn immix_evacuate_block() {
+    with_mockvm(
+        default_setup,
+        || {
+            const OBJECT_SIZE: usize = 64;
+            let mut fixture = GCFixture::create_with_builder(|_| {});
+            fixture.alloc_with_roots(10000, OBJECT_SIZE, 1);
+            fixture.gc();
+
+            let before = fixture.roots();
+            let block = Block::from_unaligned_address(before[0].to_raw_address());
+            assert_eq!(
+                memory_manager::evacuate_immix_block(fixture.mmtk(), block.start()),
+                Ok(())
+            );
+            fixture.gc();
+            assert!(fixture.mmtk().last_gc_was_defrag());
+
+            let after = fixture.roots();
+            for (old, new) in before.iter().zip(after.iter()) {
+                let in_block = Block::from_unaligned_address(old.to_raw_address()) == block;
+                assert_eq!(old != new, in_block, "{} was moved to {}", old, new);
+            }
+            fixture.assert_roots_intact(OBJECT_SIZE);
+        },
+        no_cleanup,
+    )
+}
@@ -0,0 +1,61 @@
+// GITHUB-CI: MMTK_PLAN=Immix
+
+use super::mock_test_prelude::*;
+
+use crate::policy::immix::block::Block;
+use crate::util::linear_scan::Region;
+use crate::AllocationSemantics;
+
+/// With the option `immix_track_max_hole`, the reusable blocks whose holes are too small for a
+/// large object are kept by the allocator instead of being returned to the reusable block list, so
+/// the next large object examines other blocks.  Small objects are then allocated into the kept
+/// blocks.
+#[test]
+pub fn immix_large_hole_rejected_blocks() {
+    with_mockvm(
+        default_setup,
+        || {
+            const OBJECT_SIZE: usize = 64;
+            // Two large objects fill a block.
+            const LARGE_OBJECT_SIZE: usize = crate::policy::immix::MAX_IMMIX_OBJECT_SIZE;
+            let mut fixture = GCFixture::create_with_builder(|builder| {
+                builder.options.threads.set(1);
+                builder.options.immix_track_max_hole.set(true);
+            });
+
+            // Keep one object in every 512 bytes, so that no block has a hole for a large object.
+            fixture.alloc_with_roots(40000, OBJECT_SIZE, 8);
+            fixture.gc();
+            let survivors = fixture.roots();
+            let mmtk = fixture.mmtk();
+            let reusable_blocks = || {
+                memory_manager::immix_reusable_block_stats(mmtk)
+                    .unwrap()
+                    .blocks
+            };
+
+            let before = reusable_blocks();
+            fixture.alloc(LARGE_OBJECT_SIZE, AllocationSemantics::Default);
+            let after_first = reusable_blocks();
+            // The first of these fits in the block taken for the first large object.  The second
+            // one needs another block.
+            fixture.alloc(LARGE_OBJECT_SIZE, AllocationSemantics::Default);
+            fixture.alloc(LARGE_OBJECT_SIZE, AllocationSemantics::Default);
+            let after_second = reusable_blocks();
+            assert!(after_first < before, "Rejected blocks were returned");
+            assert_eq!(
+                before - after_first,
+                after_first - after_second,
+                "Rejected blocks were examined again"
+            );
+
+            let object = fixture.alloc(OBJECT_SIZE, AllocationSemantics::Default);
+            assert_eq!(reusable_blocks(), after_second);
+            assert!(survivors
+                .iter()
+                .any(|survivor| Block::containing(*survivor) == Block::containing(object)));
+            fixture.assert_roots_intact(OBJECT_SIZE);
+        },
+        no_cleanup,
+    )
+}
@@ -0,0 +1,51 @@
+// GITHUB-CI: MMTK_PLAN=Immix,StickyImmix
+
+use super::mock_test_prelude::*;
+
+use crate::policy::immix::block::Block;
+use crate::policy::immix::block::BlockState;
+use crate::util::linear_scan::Region;
+use crate::AllocationSemantics;
+
+/// With the option `immix_lazy_sweep`, the live blocks are left unswept after a GC, and are swept
+/// when they are taken for allocation.  The objects allocated into the holes of the swept blocks
+/// must not overwrite the survivors.
+#[test]
+pub fn immix_lazy_sweep() {
+    with_mockvm(
+        default_setup,
+        || {
+            const OBJECT_SIZE: usize = 64;
+            let mut fixture = GCFixture::create_with_builder(|builder| {
+                builder.options.threads.set(1);
+                builder.options.immix_lazy_sweep.set(true);
+            });
+
+            // Keep one object in every 1KB, so that the blocks have holes.
+            fixture.alloc_with_roots(10000, OBJECT_SIZE, 16);
+            fixture.gc();
+            let survivors = fixture.roots();
+            assert!(
+                survivors
+                    .iter()
+                    .any(|object| Block::containing(*object).get_state() == BlockState::Marked),
+                "No live block was left unswept"
+            );
+
+            let reused = (0..10000)
+                .map(|_| fixture.alloc(OBJECT_SIZE, AllocationSemantics::Default))
+                .filter(|object| {
+                    survivors
+                        .iter()
+                        .any(|survivor| Block::containing(*survivor) == Block::containing(*object))
+                })
+                .count();
+            assert_ne!(reused, 0, "No unswept block was reused");
+            fixture.assert_roots_intact(OBJECT_SIZE);
+
+            fixture.gc();
+            fixture.assert_roots_intact(OBJECT_SIZE);
+        },
+        no_cleanup,
+    )
+}
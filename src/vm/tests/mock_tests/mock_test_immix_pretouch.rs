@@ -0,0 +1,36 @@
+// GITHUB-CI: MMTK_PLAN=Immix,GenImmix,StickyImmix
+
+use super::mock_test_prelude::*;
+
+use crate::util::{VMMutatorThread, VMThread};
+
+/// Pretouch the Immix space from a mutator thread outside a GC, and make sure that the pretouched
+/// blocks can be used for allocation and GC afterwards.
+#[test]
+pub fn immix_pretouch() {
+    with_mockvm(
+        default_setup,
+        || {
+            const PAGES: usize = 1024;
+            const OBJECT_SIZE: usize = 64;
+            let mut fixture = GCFixture::create_with_builder(|builder| {
+                builder.options.threads.set(2);
+            });
+
+            let reserved_pages = fixture.mmtk().get_plan().get_reserved_pages();
+            let tls = VMMutatorThread(VMThread::UNINITIALIZED);
+            let pretouched = memory_manager::pretouch_immix_space(fixture.mmtk(), tls, PAGES);
+            assert_eq!(pretouched, PAGES);
+            // The pretouched blocks are released to the page resource.
+            assert_eq!(
+                fixture.mmtk().get_plan().get_reserved_pages(),
+                reserved_pages
+            );
+
+            fixture.alloc_with_roots(10000, OBJECT_SIZE, 10);
+            fixture.gc();
+            fixture.assert_roots_intact(OBJECT_SIZE);
+        },
+        no_cleanup,
+    )
+}
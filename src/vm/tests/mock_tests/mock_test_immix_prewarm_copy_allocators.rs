@@ -0,0 +1,70 @@
+// GITHUB-CI: MMTK_PLAN=Immix,StickyImmix
+
+use super::mock_test_prelude::*;
+
+use crate::util::constants::BYTES_IN_WORD;
+use crate::AllocationSemantics;
+
+const OBJECT_SIZE: usize = 64;
+
+/// The offset of the word in each object that holds the index of the object.
+const INDEX_OFFSET: usize = 2 * BYTES_IN_WORD;
+
+fn check_roots(fixture: &GCFixture) {
+    for (i, root) in fixture.roots().into_iter().enumerate() {
+        assert!(root.is_live());
+        let index = unsafe { (root.to_raw_address() + INDEX_OFFSET).load::<usize>() };
+        assert_eq!(index, i, "{} is corrupted", root);
+    }
+}
+
+/// Run defrag GCs with the option `immix_prewarm_copy_allocators`.  The prewarmed blocks must
+/// not be reset by the preparation of blocks, otherwise the objects copied into them may be
+/// released by the sweep or overwritten by later allocation.
+#[test]
+pub fn immix_prewarm_copy_allocators() {
+    with_mockvm(
+        default_setup,
+        || {
+            let mut fixture = GCFixture::create_with_builder(|builder| {
+                builder.options.threads.set(4);
+                builder.options.immix_prewarm_copy_allocators.set(true);
+            });
+
+            let alloc_objects = |fixture: &mut GCFixture, roots: bool| {
+                for i in 0..10000 {
+                    let object = fixture.alloc(OBJECT_SIZE, AllocationSemantics::Default);
+                    if roots && i % 10 == 0 {
+                        let index = fixture.roots().len();
+                        unsafe { (object.to_raw_address() + INDEX_OFFSET).store(index) };
+                        fixture.add_root(object);
+                    } else {
+                        unsafe { (object.to_raw_address() + INDEX_OFFSET).store(usize::MAX) };
+                    }
+                }
+            };
+
+            alloc_objects(&mut fixture, true);
+            let mut moved = false;
+            for _ in 0..5 {
+                // Defrag source blocks are chosen by the numbers of holes found by the last sweep.
+                fixture.gc();
+                check_roots(&fixture);
+
+                let before = fixture.roots();
+                fixture.mmtk().request_defrag_gc();
+                fixture.gc();
+                assert!(fixture.mmtk().last_gc_was_defrag());
+                moved |= before != fixture.roots();
+                check_roots(&fixture);
+
+                // Reuse the memory released by the GC.  This overwrites live objects if they were
+                // wrongly released.
+                alloc_objects(&mut fixture, false);
+                check_roots(&fixture);
+            }
+            assert!(moved, "No object was moved in defrag GCs");
+        },
+        no_cleanup,
+    )
+}
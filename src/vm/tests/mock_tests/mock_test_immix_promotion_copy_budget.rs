@@ -0,0 +1,49 @@
+// GITHUB-CI: MMTK_PLAN=StickyImmix
+
+use super::mock_test_prelude::*;
+
+use crate::util::options::GCTriggerSelector;
+
+/// The promotion copy budget is reset in every nursery GC of StickyImmix.  Running out of the
+/// budget in one nursery GC must not stop the next nursery GC from copying nursery objects, and
+/// the budget must be in effect before the first full-heap GC.
+#[test]
+pub fn immix_promotion_copy_budget() {
+    with_mockvm(
+        default_setup,
+        || {
+            const MB: usize = 1024 * 1024;
+            const OBJECT_SIZE: usize = 64;
+            // 256KB of nursery objects, while the budget only covers about 64KB of clean blocks.
+            const OBJECTS: usize = 4096;
+            let mut fixture = GCFixture::create_with_builder(|builder| {
+                builder.options.threads.set(1);
+                builder
+                    .options
+                    .gc_trigger
+                    .set(GCTriggerSelector::FixedHeapSize(64 * MB));
+                builder.options.immix_promotion_copy_budget.set(0.001);
+            });
+
+            for _ in 0..2 {
+                fixture.clear_roots();
+                fixture.alloc_with_roots(OBJECTS, OBJECT_SIZE, 1);
+                let before = fixture.roots();
+                fixture.nursery_gc();
+                let plan = fixture.mmtk().get_plan();
+                assert!(!plan.generational().unwrap().last_collection_full_heap());
+
+                let after = fixture.roots();
+                let mut moved = before.iter().zip(after.iter()).map(|(b, a)| b != a);
+                assert!(moved.any(|m| m), "No nursery object was copied");
+                let mut moved = before.iter().zip(after.iter()).map(|(b, a)| b != a);
+                assert!(
+                    moved.any(|m| !m),
+                    "Nursery objects were copied beyond the budget"
+                );
+                fixture.assert_roots_intact(OBJECT_SIZE);
+            }
+        },
+        no_cleanup,
+    )
+}
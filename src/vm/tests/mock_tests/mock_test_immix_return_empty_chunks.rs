@@ -0,0 +1,53 @@
+// GITHUB-CI: MMTK_PLAN=Immix,StickyImmix
+
+use super::mock_test_immix_chunk_occupancy::*;
+use super::mock_test_prelude::*;
+
+use crate::util::constants::BYTES_IN_PAGE;
+use crate::util::{Address, ObjectReference};
+
+/// The addresses in a large object to write a pattern to, one word per page, after the header and
+/// the size word written by `GCFixture::alloc`.
+fn pattern_addresses(object: ObjectReference) -> impl Iterator<Item = Address> {
+    let start = object.to_raw_address() + 2 * std::mem::size_of::<usize>();
+    let end = object.to_object_start::<MockVM>() + LARGE_OBJECT_SIZE;
+    (start.as_usize()..end.as_usize())
+        .step_by(BYTES_IN_PAGE)
+        .map(|addr| unsafe { Address::from_usize(addr) })
+}
+
+/// Returning the empty chunks of the Immix space to the OS must not touch the chunks of the large
+/// object space in between, which still hold live objects.
+#[test]
+pub fn immix_return_empty_chunks() {
+    with_mockvm(
+        default_setup,
+        || {
+            let mut fixture = GCFixture::create_with_builder(|builder| {
+                builder.set_vm_layout(discontiguous_vm_layout());
+                builder.options.immix_return_empty_chunks.set(true);
+            });
+            // The Immix objects are dead, and the large objects are alive.
+            let large_objects = interleave_immix_and_los_chunks(&mut fixture);
+            for object in large_objects.iter() {
+                fixture.add_root(*object);
+                for addr in pattern_addresses(*object) {
+                    unsafe { addr.store(addr.as_usize()) };
+                }
+            }
+
+            // The empty Immix chunks are returned after a few GCs.
+            for _ in 0..4 {
+                fixture.gc();
+            }
+
+            for object in fixture.roots() {
+                assert_eq!(MockVM::get_current_size(object), LARGE_OBJECT_SIZE);
+                for addr in pattern_addresses(object) {
+                    assert_eq!(unsafe { addr.load::<usize>() }, addr.as_usize());
+                }
+            }
+        },
+        no_cleanup,
+    )
+}
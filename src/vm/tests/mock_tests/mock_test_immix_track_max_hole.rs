@@ -0,0 +1,40 @@
+// GITHUB-CI: MMTK_PLAN=Immix
+
+use super::mock_test_prelude::*;
+
+use crate::policy::immix::block::Block;
+use crate::util::linear_scan::Region;
+use crate::AllocationSemantics;
+
+/// With the option `immix_track_max_hole`, objects larger than a line are allocated into the large
+/// holes of reusable blocks before clean blocks are acquired.
+#[test]
+pub fn immix_track_max_hole() {
+    with_mockvm(
+        default_setup,
+        || {
+            const OBJECT_SIZE: usize = 64;
+            const LARGE_OBJECT_SIZE: usize = 4096;
+            let mut fixture = GCFixture::create_with_builder(|builder| {
+                builder.options.immix_track_max_hole.set(true);
+            });
+
+            // Keep one object in every 8KB, so that the blocks have holes of about 8KB.
+            fixture.alloc_with_roots(10000, OBJECT_SIZE, 128);
+            fixture.gc();
+            let survivors = fixture.roots();
+
+            let reused = (0..10)
+                .map(|_| fixture.alloc(LARGE_OBJECT_SIZE, AllocationSemantics::Default))
+                .filter(|object| {
+                    survivors
+                        .iter()
+                        .any(|survivor| Block::containing(*survivor) == Block::containing(*object))
+                })
+                .count();
+            assert_eq!(reused, 10);
+            fixture.assert_roots_intact(OBJECT_SIZE);
+        },
+        no_cleanup,
+    )
+}
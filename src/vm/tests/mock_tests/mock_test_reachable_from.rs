@@ -0,0 +1,85 @@
+// GITHUB-CI: MMTK_PLAN=Immix,MarkSweep
+
+use super::mock_test_prelude::*;
+
+use crate::scheduler::{GCWork, GCWorker, WorkBucketStage};
+use crate::util::{Address, ObjectReference};
+use crate::{AllocationSemantics, MMTK};
+use std::sync::Mutex;
+
+/// The offset of the only reference field of the objects in this test.  `GCFixture::alloc` writes
+/// the size of an object at offset 8.
+const FIELD_OFFSET: usize = 16;
+
+/// The result of `MMTK::reachable_from` for each root, computed in a GC.
+static RESULTS: Mutex<Vec<Vec<ObjectReference>>> = Mutex::new(vec![]);
+
+/// A work packet that calls `MMTK::reachable_from` for each of the given roots.
+struct ReachableFrom {
+    roots: Vec<ObjectReference>,
+}
+
+impl GCWork<MockVM> for ReachableFrom {
+    fn do_work(&mut self, worker: &mut GCWorker<MockVM>, mmtk: &'static MMTK<MockVM>) {
+        *RESULTS.lock().unwrap() = self
+            .roots
+            .iter()
+            .map(|root| mmtk.reachable_from(worker, &[*root]))
+            .collect();
+    }
+}
+
+fn set_field(object: ObjectReference, target: ObjectReference) {
+    unsafe { (object.to_raw_address() + FIELD_OFFSET).store(target) }
+}
+
+/// `reachable_from` finds the objects reachable from the given roots, including the roots, and
+/// visits each object once even if the object graph has cycles.
+#[test]
+pub fn reachable_from() {
+    with_mockvm(
+        default_setup,
+        || {
+            const OBJECT_SIZE: usize = 64;
+            let mut fixture = GCFixture::create_with_builder(|_| {});
+            write_mockvm(|mock| {
+                mock.scan_object = MockMethod::new_fixed(Box::new(|(_, object, slot_visitor)| {
+                    let slot: Address = object.to_raw_address() + FIELD_OFFSET;
+                    if unsafe { slot.load::<usize>() } != 0 {
+                        slot_visitor.visit_slot(slot);
+                    }
+                }));
+            });
+
+            // a -> b -> c -> a, and d -> a.
+            let [a, b, c, d] = [(); 4].map(|_| {
+                let object = fixture.alloc(OBJECT_SIZE, AllocationSemantics::Default);
+                unsafe { (object.to_raw_address() + FIELD_OFFSET).store(0usize) };
+                object
+            });
+            set_field(a, b);
+            set_field(b, c);
+            set_field(c, a);
+            set_field(d, a);
+            fixture.add_root(a);
+            fixture.add_root(d);
+
+            let bucket = memory_manager::register_custom_work_bucket(
+                fixture.mmtk(),
+                WorkBucketStage::Release,
+            );
+            memory_manager::add_work_packet_to_custom_bucket(
+                fixture.mmtk(),
+                bucket,
+                ReachableFrom { roots: vec![a, d] },
+            );
+            fixture.gc();
+
+            let results = RESULTS.lock().unwrap();
+            assert_eq!(results[0], vec![a, b, c]);
+            assert_eq!(results[1], vec![d, a, b, c]);
+            assert!(a.is_live() && b.is_live() && c.is_live() && d.is_live());
+        },
+        no_cleanup,
+    )
+}
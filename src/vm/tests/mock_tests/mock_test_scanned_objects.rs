@@ -0,0 +1,31 @@
+// GITHUB-CI: MMTK_PLAN=Immix,SemiSpace
+
+use super::mock_test_prelude::*;
+
+use crate::AllocationSemantics;
+
+/// The scanned objects are counted for the spaces they are in, including when consecutive objects
+/// in a work packet are in different spaces.
+#[test]
+pub fn scanned_objects() {
+    with_mockvm(
+        default_setup,
+        || {
+            let mut fixture = GCFixture::create_with_builder(|_| {});
+            for i in 0..1000 {
+                let object = fixture.alloc(64, AllocationSemantics::Default);
+                fixture.add_root(object);
+                if i % 100 == 0 {
+                    let object = fixture.alloc(16 * 1024, AllocationSemantics::Los);
+                    fixture.add_root(object);
+                }
+            }
+            fixture.gc();
+
+            let scanned_objects = memory_manager::scanned_objects_in_last_gc(fixture.mmtk());
+            assert_eq!(scanned_objects["los"], 10);
+            assert_eq!(scanned_objects.values().sum::<usize>(), 1010);
+        },
+        no_cleanup,
+    )
+}
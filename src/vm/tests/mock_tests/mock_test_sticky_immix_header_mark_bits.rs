@@ -0,0 +1,26 @@
+// GITHUB-CI: MMTK_PLAN=StickyImmix
+
+use super::mock_test_prelude::*;
+
+use crate::plan::sticky::immix::StickyImmix;
+use crate::util::metadata::header_metadata::HeaderMetadataSpec;
+use crate::util::metadata::MetadataSpec;
+
+/// StickyImmix rejects in-header mark bits when it is created, as they cannot be used in nursery
+/// GCs.
+#[test]
+#[should_panic(expected = "StickyImmix requires the mark bits to be on the side")]
+pub fn sticky_immix_header_mark_bits() {
+    with_mockvm(
+        default_setup,
+        || {
+            StickyImmix::<MockVM>::validate_mark_bit_spec(&MetadataSpec::InHeader(
+                HeaderMetadataSpec {
+                    bit_offset: 0,
+                    num_of_bits: 1,
+                },
+            ));
+        },
+        no_cleanup,
+    )
+}
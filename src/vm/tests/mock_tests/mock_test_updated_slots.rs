@@ -0,0 +1,34 @@
+// GITHUB-CI: MMTK_PLAN=SemiSpace
+// GITHUB-CI: FEATURES=count_updated_slots
+
+use super::mock_test_prelude::*;
+
+use crate::AllocationSemantics;
+
+/// Every slot updated to point to a moved object is counted, so an object referenced from two
+/// slots is counted twice.
+#[test]
+pub fn updated_slots() {
+    with_mockvm(
+        default_setup,
+        || {
+            let mut fixture = GCFixture::create_with_builder(|_| {});
+            for _ in 0..1000 {
+                let object = fixture.alloc(64, AllocationSemantics::Default);
+                fixture.add_root(object);
+            }
+            fixture.add_root(fixture.roots()[0]);
+            let before = fixture.roots();
+            fixture.gc();
+
+            let after = fixture.roots();
+            assert!(before.iter().zip(after.iter()).all(|(b, a)| b != a));
+            assert_eq!(after[0], after[1000]);
+            assert_eq!(
+                memory_manager::updated_slots_in_last_gc(fixture.mmtk()),
+                1001
+            );
+        },
+        no_cleanup,
+    )
+}
@@ -0,0 +1,17 @@
+// GITHUB-CI: MMTK_PLAN=Immix,GenImmix,StickyImmix,SemiSpace,GenCopy,MarkSweep
+// GITHUB-CI: FEATURES=extreme_assertions,vm_space
+
+use super::mock_test_prelude::*;
+use super::mock_test_verify_sft_space_map::run_gcs_with_verify_sft;
+
+/// `VerifySFT` with `SFTDenseChunkMap`, which is used on 64-bit if there is off-heap memory, such
+/// as a VM space.  The contiguous spaces only acquire a few chunks of their address ranges, and
+/// the other chunks have no SFT entries.
+#[test]
+pub fn verify_sft_dense_chunk_map() {
+    with_mockvm(
+        default_setup,
+        || run_gcs_with_verify_sft(|_| {}),
+        no_cleanup,
+    )
+}
@@ -0,0 +1,39 @@
+// GITHUB-CI: MMTK_PLAN=Immix,GenImmix,StickyImmix,SemiSpace,GenCopy,MarkSweep
+// GITHUB-CI: FEATURES=extreme_assertions
+
+use super::mock_test_prelude::*;
+
+use crate::AllocationSemantics;
+
+/// Allocate some objects and run GCs, so that the `VerifySFT` packet checks the SFT map against
+/// the spaces in each GC.
+pub fn run_gcs_with_verify_sft<F>(with_builder: F)
+where
+    F: FnOnce(&mut crate::MMTKBuilder),
+{
+    let mut fixture = GCFixture::create_with_builder(with_builder);
+    for (semantics, size) in [
+        (AllocationSemantics::Default, 64),
+        (AllocationSemantics::Los, 64 * 1024),
+    ] {
+        for i in 0..100 {
+            let object = fixture.alloc(size, semantics);
+            if i % 10 == 0 {
+                fixture.add_root(object);
+            }
+        }
+    }
+    for _ in 0..3 {
+        fixture.gc();
+    }
+}
+
+/// `VerifySFT` with `SFTSpaceMap`, which is used on 64-bit with contiguous spaces.
+#[test]
+pub fn verify_sft_space_map() {
+    with_mockvm(
+        default_setup,
+        || run_gcs_with_verify_sft(|_| {}),
+        no_cleanup,
+    )
+}
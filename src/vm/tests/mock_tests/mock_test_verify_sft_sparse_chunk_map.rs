@@ -0,0 +1,34 @@
+// GITHUB-CI: MMTK_PLAN=Immix,GenImmix,StickyImmix,SemiSpace,GenCopy,MarkSweep
+// GITHUB-CI: FEATURES=extreme_assertions
+
+use super::mock_test_prelude::*;
+use super::mock_test_verify_sft_space_map::run_gcs_with_verify_sft;
+use crate::util::conversions::*;
+use crate::util::heap::vm_layout::VMLayout;
+use crate::util::Address;
+
+/// `VerifySFT` with `SFTSparseChunkMap`, which is used with discontiguous spaces.  The spaces
+/// only acquire a few chunks of the address range, and the other chunks have no SFT entries.
+#[test]
+pub fn verify_sft_sparse_chunk_map() {
+    with_mockvm(
+        default_setup,
+        || {
+            // The same layout as `mock_test_vm_layout_compressed_pointer`.
+            let start = if cfg!(target_os = "macos") {
+                0x2_0000_0000
+            } else {
+                0x4000_0000
+            };
+            let layout = VMLayout {
+                log_address_space: 35,
+                heap_start: chunk_align_down(unsafe { Address::from_usize(start) }),
+                heap_end: chunk_align_up(unsafe { Address::from_usize(32usize << 30) }),
+                log_space_extent: 31,
+                force_use_contiguous_spaces: false,
+            };
+            run_gcs_with_verify_sft(|builder| builder.set_vm_layout(layout));
+        },
+        no_cleanup,
+    )
+}
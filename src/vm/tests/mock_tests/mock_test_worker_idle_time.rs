@@ -0,0 +1,30 @@
+// GITHUB-CI: MMTK_PLAN=Immix,SemiSpace
+
+use super::mock_test_prelude::*;
+
+/// With the option `record_worker_idle_time`, the idle time of the workers and the parallel
+/// efficiency are measured in each GC.
+#[test]
+pub fn worker_idle_time() {
+    with_mockvm(
+        default_setup,
+        || {
+            let mut fixture = GCFixture::create_with_builder(|builder| {
+                builder.options.threads.set(4);
+                builder.options.record_worker_idle_time.set(true);
+            });
+            assert_eq!(
+                memory_manager::parallel_efficiency_in_last_gc(fixture.mmtk()),
+                1.0
+            );
+            fixture.alloc_with_roots(1000, 64, 1);
+            fixture.gc();
+
+            // With 4 workers and only a few work packets, some workers must have been idle.
+            let efficiency = memory_manager::parallel_efficiency_in_last_gc(fixture.mmtk());
+            assert!(efficiency > 0.0 && efficiency < 1.0, "{}", efficiency);
+            assert!(!memory_manager::worker_idle_time_in_last_gc(fixture.mmtk()).is_zero());
+        },
+        no_cleanup,
+    )
+}
@@ -33,11 +33,26 @@ mod mock_test_allocator_info;
 mod mock_test_barrier_slow_path_assertion;
 #[cfg(feature = "is_mmtk_object")]
 mod mock_test_conservatism;
+mod mock_test_filter_slot_object;
+mod mock_test_gc_bucket_worker_mask;
+mod mock_test_gc_deterministic_scheduling;
 #[cfg(target_os = "linux")]
 mod mock_test_handle_mmap_conflict;
 mod mock_test_handle_mmap_oom;
 #[cfg(feature = "vo_bit")]
 mod mock_test_heap_traversal;
+mod mock_test_immix_chunk_occupancy;
+mod mock_test_immix_copy_budget;
+#[cfg(feature = "sanity")]
+mod mock_test_immix_defrag_pinning_roots;
+mod mock_test_immix_evacuate_block;
+mod mock_test_immix_large_hole_rejected_blocks;
+mod mock_test_immix_lazy_sweep;
+mod mock_test_immix_pretouch;
+mod mock_test_immix_prewarm_copy_allocators;
+mod mock_test_immix_promotion_copy_budget;
+mod mock_test_immix_return_empty_chunks;
+mod mock_test_immix_track_max_hole;
 mod mock_test_init_fork;
 #[cfg(feature = "is_mmtk_object")]
 mod mock_test_internal_ptr_before_object_ref;
@@ -59,12 +74,28 @@ mod mock_test_malloc_ms;
 mod mock_test_mmtk_julia_pr_143;
 #[cfg(feature = "nogc_lock_free")]
 mod mock_test_nogc_lock_free;
+mod mock_test_reachable_from;
+mod mock_test_scanned_objects;
 mod mock_test_slots;
+mod mock_test_sticky_immix_header_mark_bits;
+#[cfg(feature = "count_updated_slots")]
+mod mock_test_updated_slots;
+#[cfg(all(
+    feature = "extreme_assertions",
+    feature = "vm_space",
+    target_pointer_width = "64"
+))]
+mod mock_test_verify_sft_dense_chunk_map;
+#[cfg(feature = "extreme_assertions")]
+mod mock_test_verify_sft_space_map;
+#[cfg(all(feature = "extreme_assertions", target_pointer_width = "64"))]
+mod mock_test_verify_sft_sparse_chunk_map;
 #[cfg(target_pointer_width = "64")]
 mod mock_test_vm_layout_compressed_pointer;
 mod mock_test_vm_layout_default;
 mod mock_test_vm_layout_heap_start;
 mod mock_test_vm_layout_log_address_space;
+mod mock_test_worker_idle_time;
 
 mod mock_test_doc_avoid_resolving_allocator;
 mod mock_test_doc_mutator_storage;